@@ -0,0 +1,72 @@
+/* The C interface of aoc-session, built with the `ffi` feature. Generated by cbindgen from
+ * src/ffi.rs; regenerate with `cbindgen --config cbindgen.toml --output include/aoc_session.h`. */
+
+#ifndef AOC_SESSION_H
+#define AOC_SESSION_H
+
+#include <stdint.h>
+
+/**
+ * The session cookie value was found.
+ */
+#define AOC_SESSION_OK 0
+
+/**
+ * No source holds a session cookie value.
+ */
+#define AOC_SESSION_NOT_FOUND 1
+
+/**
+ * A null pointer was passed where a pointer was expected.
+ */
+#define AOC_SESSION_NULL_ARGUMENT 2
+
+/**
+ * A source failed, e.g. a cookie database couldn't be decrypted.
+ */
+#define AOC_SESSION_ERROR 3
+
+/**
+ * The library panicked, which is a bug.
+ */
+#define AOC_SESSION_PANIC 4
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Look the session cookie value up as `aoc_session` does and store it in
+ * `*session` as a NUL-terminated string, which must be released with `aoc_session_free`.
+ *
+ * Returns `AOC_SESSION_OK` or one of the other `AOC_SESSION_*` codes, in which case
+ * `*session` is set to null and `aoc_session_last_error` describes the error.
+ *
+ * # Safety
+ *
+ * `session` must be null or point to writable memory for a pointer.
+ */
+int32_t aoc_session_get(char **session);
+
+/**
+ * Release a session cookie value returned by `aoc_session_get`, wiping it from memory with
+ * the `zeroize` feature. Does nothing with a null pointer.
+ *
+ * # Safety
+ *
+ * `session` must be null or a pointer set by `aoc_session_get` that wasn't released yet.
+ */
+void aoc_session_free(char *session);
+
+/**
+ * The message of the last error of `aoc_session_get` on the calling thread, or an empty
+ * string. It stays valid until the next call of `aoc_session_get` on the same thread, and
+ * must not be released.
+ */
+const char *aoc_session_last_error(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* AOC_SESSION_H */
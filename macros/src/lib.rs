@@ -0,0 +1,120 @@
+//! The [`aoc_input!`] macro, re-exported by `aoc-session` with the `macros` feature.
+//!
+//! The macro can't use `aoc-session` as a library, since that crate depends on this one. It runs
+//! the `aoc-session` executable instead, which shares the configuration and the input cache.
+
+use std::process::Command;
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{LitInt, LitStr, Token};
+
+/// The environment variable that overrides the path of the `aoc-session` executable.
+const EXE_VAR: &str = "AOC_SESSION_EXE";
+
+/// Expand to the puzzle input of the given year and day as a `&'static str`, downloaded at
+/// compile time, so that solutions do no I/O at run time.
+///
+/// The input is fetched by `aoc-session input <YEAR> <DAY>`, so the `aoc-session` executable
+/// must be installed (`cargo install aoc-session`), or its path given by the `AOC_SESSION_EXE`
+/// environment variable. Inputs are cached, so each is downloaded only once.
+///
+/// # Examples
+///
+/// ```ignore
+/// use aoc_session::aoc_input;
+///
+/// const INPUT: &str = aoc_input!(2024, 7);
+/// ```
+#[proc_macro]
+pub fn aoc_input(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    expand(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: TokenStream) -> syn::Result<TokenStream> {
+    let Puzzle { year, day } = syn::parse2(input)?;
+    let exe = std::env::var(EXE_VAR).unwrap_or_else(|_| "aoc-session".to_string());
+    let input = fetch(&exe, year, day).map_err(|e| syn::Error::new(Span::call_site(), e))?;
+    let input = LitStr::new(&input, Span::call_site());
+    Ok(quote!(#input))
+}
+
+/// The arguments of [`aoc_input!`].
+#[derive(Debug, PartialEq, Eq)]
+struct Puzzle {
+    year: u16,
+    day: u8,
+}
+
+impl Parse for Puzzle {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let year: LitInt = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let day: LitInt = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+        let puzzle = Puzzle {
+            year: year.base10_parse()?,
+            day: day.base10_parse()?,
+        };
+        if puzzle.year < 2015 {
+            return Err(syn::Error::new(
+                year.span(),
+                "Advent of Code started in 2015",
+            ));
+        }
+        if !(1..=25).contains(&puzzle.day) {
+            return Err(syn::Error::new(day.span(), "the day must be from 1 to 25"));
+        }
+        Ok(puzzle)
+    }
+}
+
+/// Run `<exe> input <year> <day>` and return what it printed, or an error message.
+fn fetch(exe: &str, year: u16, day: u8) -> Result<String, String> {
+    let output = Command::new(exe)
+        .args(["input", &year.to_string(), &day.to_string()])
+        .output()
+        .map_err(|e| {
+            format!(
+                "failed to run `{exe}`: {e}; install it with `cargo install aoc-session` or set \
+                 {EXE_VAR} to its path"
+            )
+        })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = stderr.trim().trim_start_matches("error: ");
+        return Err(format!(
+            "failed to get the input of {year} day {day}: {message}"
+        ));
+    }
+    String::from_utf8(output.stdout)
+        .map_err(|_| format!("the input of {year} day {day} is not valid UTF-8"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arguments() {
+        let parse = |tokens: TokenStream| syn::parse2::<Puzzle>(tokens);
+        assert_eq!(
+            parse(quote!(2024, 7)).unwrap(),
+            Puzzle { year: 2024, day: 7 }
+        );
+        assert!(parse(quote!(2024, 7,)).is_ok());
+        assert!(parse(quote!(2024)).is_err());
+        assert!(parse(quote!(2014, 1)).is_err());
+        assert!(parse(quote!(2024, 26)).is_err());
+        assert!(parse(quote!("2024", 7)).is_err());
+    }
+
+    #[test]
+    fn missing_executable_is_explained() {
+        let e = fetch("/nonexistent/aoc-session", 2024, 7).unwrap_err();
+        assert!(e.contains("cargo install aoc-session"), "{e}");
+    }
+}
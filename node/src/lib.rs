@@ -0,0 +1,150 @@
+//! Node.js bindings of `aoc-session`: the session cookie lookup and the client, for solutions
+//! written in JavaScript or TypeScript. The network calls run on the libuv thread pool and
+//! return promises.
+//!
+//! ```js
+//! const aoc = require("aoc-session");
+//!
+//! const data = await aoc.getInput(2024, 1);
+//! const outcome = await aoc.submit(2024, 1, 1, String(solve(data)));
+//! console.log(outcome.message);
+//! ```
+//!
+//! Build it with `npm run build` in this directory, which runs `napi build`.
+
+use aoc_session::{AocClient, AocSession, Config, PuzzleId};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+/// The session cookie value for Advent of Code, looked up as `aoc-session` does: in the
+/// environment, the files, the configuration, and the cookie stores of the browsers.
+///
+/// Scanning the browsers may take a few seconds; prefer `getSessionAsync` on a busy event loop.
+#[napi]
+pub fn get_session() -> Result<String> {
+    session()
+        .map(|session| session.expose_secret().to_string())
+        .map_err(to_js)
+}
+
+/// Like `getSession`, but off the event loop.
+#[napi(ts_return_type = "Promise<string>")]
+pub fn get_session_async() -> AsyncTask<GetSession> {
+    AsyncTask::new(GetSession)
+}
+
+/// The puzzle input of the given day, downloaded once and then read from the cache.
+#[napi(ts_return_type = "Promise<string>")]
+pub fn get_input(year: u32, day: u32) -> Result<AsyncTask<GetInput>> {
+    Ok(AsyncTask::new(GetInput {
+        puzzle: puzzle(year, day)?,
+    }))
+}
+
+/// Submit the answer to the given part, 1 or 2, of the puzzle of the given day.
+#[napi(ts_return_type = "Promise<SubmitOutcome>")]
+pub fn submit(year: u32, day: u32, part: u32, answer: String) -> Result<AsyncTask<Submit>> {
+    let part = u8::try_from(part).map_err(|_| invalid(format!("there's no part {part}")))?;
+    Ok(AsyncTask::new(Submit {
+        puzzle: puzzle(year, day)?,
+        part,
+        answer,
+    }))
+}
+
+/// The outcome of `submit`: whether the answer is right, what Advent of Code said, and how many
+/// seconds to wait before submitting again, if it said so.
+#[napi(object)]
+pub struct SubmitOutcome {
+    pub correct: bool,
+    pub message: String,
+    pub wait: Option<f64>,
+}
+
+pub struct GetSession;
+
+impl Task for GetSession {
+    type Output = String;
+    type JsValue = String;
+
+    fn compute(&mut self) -> Result<String> {
+        get_session()
+    }
+
+    fn resolve(&mut self, _env: Env, output: String) -> Result<String> {
+        Ok(output)
+    }
+}
+
+pub struct GetInput {
+    puzzle: PuzzleId,
+}
+
+impl Task for GetInput {
+    type Output = String;
+    type JsValue = String;
+
+    fn compute(&mut self) -> Result<String> {
+        client()
+            .and_then(|client| client.get_input(self.puzzle))
+            .map_err(to_js)
+    }
+
+    fn resolve(&mut self, _env: Env, output: String) -> Result<String> {
+        Ok(output)
+    }
+}
+
+pub struct Submit {
+    puzzle: PuzzleId,
+    part: u8,
+    answer: String,
+}
+
+impl Task for Submit {
+    type Output = aoc_session::SubmitOutcome;
+    type JsValue = SubmitOutcome;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        client()
+            .and_then(|client| client.submit(self.puzzle, self.part, &self.answer))
+            .map_err(to_js)
+    }
+
+    fn resolve(&mut self, _env: Env, outcome: Self::Output) -> Result<SubmitOutcome> {
+        let wait = match outcome {
+            aoc_session::SubmitOutcome::Incorrect { wait, .. } => wait,
+            aoc_session::SubmitOutcome::RateLimited { wait } => Some(wait),
+            _ => None,
+        };
+        Ok(SubmitOutcome {
+            correct: outcome == aoc_session::SubmitOutcome::Correct,
+            message: outcome.to_string(),
+            wait: wait.map(|wait| wait.as_secs_f64()),
+        })
+    }
+}
+
+fn session() -> aoc_session::Result<AocSession> {
+    Config::load()?.session_builder().resolve()
+}
+
+fn client() -> aoc_session::Result<AocClient> {
+    let config = Config::load()?;
+    let session = config.session_builder().resolve()?;
+    Ok(AocClient::from_config(session, &config))
+}
+
+fn puzzle(year: u32, day: u32) -> Result<PuzzleId> {
+    let year = u16::try_from(year).map_err(|_| invalid(format!("there's no year {year}")))?;
+    let day = u8::try_from(day).map_err(|_| invalid(format!("there's no day {day}")))?;
+    PuzzleId::new(year, day).map_err(|e| invalid(e.to_string()))
+}
+
+fn invalid(reason: String) -> Error {
+    Error::new(Status::InvalidArg, reason)
+}
+
+fn to_js(error: aoc_session::Error) -> Error {
+    Error::from_reason(error.to_string())
+}
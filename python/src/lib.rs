@@ -0,0 +1,118 @@
+//! The `aoc_session` Python module: the session cookie lookup and the client of `aoc-session`,
+//! for solutions written in Python.
+//!
+//! ```python
+//! import aoc_session
+//!
+//! data = aoc_session.get_input(2024, 1)
+//! outcome = aoc_session.submit(2024, 1, 1, solve(data))
+//! print(outcome)
+//! ```
+//!
+//! Build it with `maturin develop` or `maturin build` in this directory.
+
+use aoc_session::{AocClient, AocSession, Config, Error, PuzzleId};
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+
+create_exception!(
+    aoc_session,
+    AocSessionError,
+    PyException,
+    "An error of aoc-session, e.g. no source holds a session cookie value."
+);
+
+/// The session cookie value for Advent of Code, looked up as `aoc-session` does: in the
+/// environment, the files, the configuration, and the cookie stores of the browsers.
+#[pyfunction]
+fn get_session(py: Python<'_>) -> PyResult<String> {
+    let session = py.detach(session).map_err(to_py)?;
+    Ok(session.expose_secret().to_string())
+}
+
+/// The puzzle input of the given day, downloaded once and then read from the cache.
+#[pyfunction]
+fn get_input(py: Python<'_>, year: u16, day: u8) -> PyResult<String> {
+    let puzzle = puzzle(year, day)?;
+    py.detach(|| client()?.get_input(puzzle)).map_err(to_py)
+}
+
+/// Submit the answer to the given part, 1 or 2, of the puzzle of the given day.
+#[pyfunction]
+fn submit(
+    py: Python<'_>,
+    year: u16,
+    day: u8,
+    part: u8,
+    answer: &Bound<'_, PyAny>,
+) -> PyResult<SubmitOutcome> {
+    let puzzle = puzzle(year, day)?;
+    let answer = answer.str()?.to_string();
+    let outcome = py
+        .detach(|| client()?.submit(puzzle, part, answer))
+        .map_err(to_py)?;
+    Ok(SubmitOutcome {
+        correct: outcome == aoc_session::SubmitOutcome::Correct,
+        message: outcome.to_string(),
+        wait: match outcome {
+            aoc_session::SubmitOutcome::Incorrect { wait, .. } => wait,
+            aoc_session::SubmitOutcome::RateLimited { wait } => Some(wait),
+            _ => None,
+        }
+        .map(|wait| wait.as_secs_f64()),
+    })
+}
+
+/// The outcome of `submit`: whether the answer is right, what Advent of Code said, and how many
+/// seconds to wait before submitting again, if it said so.
+#[pyclass(frozen, get_all, module = "aoc_session")]
+struct SubmitOutcome {
+    correct: bool,
+    message: String,
+    wait: Option<f64>,
+}
+
+#[pymethods]
+impl SubmitOutcome {
+    fn __str__(&self) -> &str {
+        &self.message
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "SubmitOutcome(correct={}, message={:?})",
+            if self.correct { "True" } else { "False" },
+            self.message
+        )
+    }
+}
+
+fn session() -> aoc_session::Result<AocSession> {
+    Config::load()?.session_builder().resolve()
+}
+
+fn client() -> aoc_session::Result<AocClient> {
+    let config = Config::load()?;
+    let session = config.session_builder().resolve()?;
+    Ok(AocClient::from_config(session, &config))
+}
+
+fn puzzle(year: u16, day: u8) -> PyResult<PuzzleId> {
+    PuzzleId::new(year, day).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+fn to_py(error: Error) -> PyErr {
+    AocSessionError::new_err(error.to_string())
+}
+
+#[pymodule]
+#[pyo3(name = "aoc_session")]
+fn aoc_session_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(get_session, m)?)?;
+    m.add_function(wrap_pyfunction!(get_input, m)?)?;
+    m.add_function(wrap_pyfunction!(submit, m)?)?;
+    m.add_class::<SubmitOutcome>()?;
+    m.add("AocSessionError", m.py().get_type::<AocSessionError>())?;
+    Ok(())
+}
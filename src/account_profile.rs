@@ -0,0 +1,112 @@
+//! Named account profiles, for people who juggle several Advent of Code accounts, e.g. their own
+//! and their kid's.
+//!
+//! Each profile has its own configuration directory, `profiles/<name>` in the usual one, which
+//! holds its stored token and `config.toml`, and its own cache directory for the puzzle inputs,
+//! the answer history, and the remembered session.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::{Error, Result};
+
+/// The environment variable naming the account profile, unless [`set_account_profile`] is called.
+pub const PROFILE_ENV_VAR: &str = "AOC_SESSION_PROFILE";
+
+/// The longest name of an account profile.
+const MAX_NAME_LEN: usize = 64;
+
+static ACCOUNT_PROFILE: Mutex<Option<String>> = Mutex::new(None);
+
+/// Use the account profile with the given name from now on, in this process.
+///
+/// In a profile, the session cookie value is only looked up where it was stored for the profile:
+/// its token file, its configuration file, the OS credential store under its name, and the
+/// Firefox profile and SSH destination its configuration file names. The environment variables,
+/// the session files of the project, and the browsers are skipped, since they hold whichever
+/// account was logged in last.
+///
+/// Names are made of letters, digits, `-` and `_`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoc_session::{aoc_session, set_account_profile, AocSession};
+///
+/// set_account_profile("kid").unwrap();
+/// AocSession::try_new("53616c7465645f5f".repeat(8)).unwrap().store_in_token_file().unwrap();
+/// // Finds the value stored for the profile.
+/// let session = aoc_session().unwrap();
+/// ```
+pub fn set_account_profile(name: impl Into<String>) -> Result<()> {
+    let name = name.into();
+    if !is_valid_name(&name) {
+        return Err(Error::InvalidProfileName(name));
+    }
+    *ACCOUNT_PROFILE.lock().unwrap_or_else(|e| e.into_inner()) = Some(name);
+    Ok(())
+}
+
+/// The account profile in use: the one given to [`set_account_profile`], or else the one named by
+/// the [`PROFILE_ENV_VAR`] environment variable, if valid.
+pub fn account_profile() -> Option<String> {
+    let selected = ACCOUNT_PROFILE.lock().unwrap_or_else(|e| e.into_inner());
+    selected.clone().or_else(|| {
+        std::env::var(PROFILE_ENV_VAR)
+            .ok()
+            .filter(|name| is_valid_name(name))
+    })
+}
+
+/// The names of the account profiles that have a configuration directory, sorted.
+pub fn account_profiles() -> Vec<String> {
+    let Some(dir) = crate::dirs::shared_config_dir() else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(dir.join("profiles")) else {
+        return Vec::new();
+    };
+    let mut names: Vec<_> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| is_valid_name(name))
+        .collect();
+    names.sort();
+    names
+}
+
+/// The directory of the account profile in use within `dir`, or `dir` itself if there's none.
+pub(crate) fn scoped(dir: PathBuf) -> PathBuf {
+    match account_profile() {
+        Some(name) => dir.join("profiles").join(name),
+        None => dir,
+    }
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_are_checked() {
+        assert!(is_valid_name("work"));
+        assert!(is_valid_name("kid_2-2024"));
+        assert!(!is_valid_name(""));
+        assert!(!is_valid_name(".."));
+        assert!(!is_valid_name("a/b"));
+        assert!(!is_valid_name(&"a".repeat(65)));
+        assert!(matches!(
+            set_account_profile("../work"),
+            Err(Error::InvalidProfileName(_))
+        ));
+    }
+}
@@ -0,0 +1,388 @@
+//! A long-running agent that keeps the session cookie value in memory and hands it to the local
+//! processes of the same user, so that the browsers are scanned once rather than by every run.
+//!
+//! The agent listens on a Unix domain socket, or a named pipe on Windows. A client sends a line
+//! `get`, and the agent answers with a line `ok <value> <expiry>`, where the expiry is in seconds
+//! since the Unix epoch or `-` if unknown, or `err <code> <message>`. A line `stop` shuts the
+//! agent down.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::{AocSession, Config, Error, Result, Source};
+
+/// The longest request the agent reads.
+#[cfg_attr(not(any(unix, windows)), allow(dead_code))]
+const MAX_REQUEST_LEN: u64 = 64;
+
+/// The location of the socket of the agent: `aoc-session/agent.sock` in `XDG_RUNTIME_DIR`, or in
+/// the cache directory if it isn't set, on Unix, and the named pipe
+/// `\\.\pipe\aoc-session-agent-<user>` on Windows. Each account profile has its own agent.
+pub fn agent_socket_path() -> Option<PathBuf> {
+    #[cfg(unix)]
+    let path = crate::dirs::runtime_dir().map(|dir| dir.join("agent.sock"));
+    #[cfg(windows)]
+    let path = std::env::var("USERNAME").ok().map(|user| {
+        let profile = crate::account_profile().map_or(String::new(), |name| format!("-{name}"));
+        PathBuf::from(format!(r"\\.\pipe\aoc-session-agent-{user}{profile}"))
+    });
+    #[cfg(not(any(unix, windows)))]
+    let path = None;
+    path
+}
+
+/// Get the session cookie for Advent of Code from the agent started by `aoc-session agent` (see
+/// [`run_agent`]).
+///
+/// Fails with [`Error::AgentNotRunning`] if no agent listens.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoc_session::aoc_session_from_agent;
+///
+/// let session = aoc_session_from_agent().unwrap();
+/// println!("My session ID: {session}");
+/// ```
+pub fn aoc_session_from_agent() -> Result<AocSession> {
+    let path = agent_socket_path().ok_or(Error::AgentNotRunning)?;
+    parse_reply(&request(&path, "get")?)
+}
+
+/// Run the agent on [`agent_socket_path`] until it's asked to stop (see [`stop_agent`]).
+///
+/// The agent resolves the session cookie value with the chain of
+/// [`Config::session_builder`], less the agent itself, once on start and again whenever the
+/// value it holds has expired or its last attempt failed. Only the user who started it can
+/// connect.
+pub fn run_agent() -> Result<()> {
+    let path = agent_socket_path().ok_or(Error::NoConfigDir)?;
+    let builder = Config::load()?.session_builder().without(&Source::Agent);
+    let mut session = builder.resolve();
+    serve(&path, |request| match request {
+        "get" => {
+            if session.as_ref().map_or(true, AocSession::is_expired) {
+                session = builder.resolve();
+            }
+            (format_reply(&session), false)
+        }
+        "stop" => ("ok".to_string(), true),
+        _ => (format!("err usage unknown request {request:?}"), false),
+    })
+}
+
+/// Ask the agent on [`agent_socket_path`] to stop.
+///
+/// Fails with [`Error::AgentNotRunning`] if no agent listens.
+pub fn stop_agent() -> Result<()> {
+    let path = agent_socket_path().ok_or(Error::AgentNotRunning)?;
+    request(&path, "stop").map(drop)
+}
+
+/// Serve the requests on `path` with `handle`, which returns the reply and whether to stop.
+fn serve(path: &Path, handle: impl FnMut(&str) -> (String, bool)) -> Result<()> {
+    platform::listen(path, handle).map_err(|source| match source.kind() {
+        io::ErrorKind::AddrInUse => {
+            Error::Agent(format!("another agent listens on {}", path.display()))
+        }
+        _ => Error::Io {
+            path: path.to_path_buf(),
+            source,
+        },
+    })
+}
+
+/// Read a request from the stream and write the reply of `handle`. Returns whether to stop.
+#[cfg_attr(not(any(unix, windows)), allow(dead_code))]
+fn handle_connection<S>(stream: &S, handle: &mut impl FnMut(&str) -> (String, bool)) -> bool
+where
+    for<'a> &'a S: Read + Write,
+{
+    let mut request = String::new();
+    if BufReader::new(stream.take(MAX_REQUEST_LEN))
+        .read_line(&mut request)
+        .is_err()
+    {
+        return false;
+    }
+    let (reply, stop) = handle(request.trim());
+    let mut stream = stream;
+    // The client may have left without waiting for the reply.
+    let _ = writeln!(stream, "{reply}");
+    stop
+}
+
+/// Send the request to the agent on `path` and return its reply.
+fn request(path: &Path, request: &str) -> Result<String> {
+    let stream = platform::connect(path).map_err(|_| Error::AgentNotRunning)?;
+    let exchange = || {
+        let mut writer = &stream;
+        writeln!(writer, "{request}")?;
+        let mut reply = String::new();
+        BufReader::new(&stream).read_line(&mut reply)?;
+        io::Result::Ok(reply)
+    };
+    exchange().map_err(|e| Error::Agent(e.to_string()))
+}
+
+/// The reply to `get`.
+fn format_reply(session: &Result<AocSession>) -> String {
+    match session {
+        Ok(session) => {
+            let expires = session.expires_at().map_or("-".to_string(), |time| {
+                let secs = time.duration_since(SystemTime::UNIX_EPOCH);
+                secs.unwrap_or_default().as_secs().to_string()
+            });
+            format!("ok {} {expires}", session.expose_secret())
+        }
+        Err(e) => format!("err {} {}", e.code(), e.to_string().replace('\n', " ")),
+    }
+}
+
+/// The session in the reply to `get`.
+fn parse_reply(reply: &str) -> Result<AocSession> {
+    let mut fields = reply.trim_end().splitn(3, ' ');
+    match (fields.next(), fields.next(), fields.next()) {
+        (Some("ok"), Some(value), Some(expires)) => {
+            let expires = match expires {
+                "-" => None,
+                secs => Some(
+                    secs.parse()
+                        .map_err(|_| Error::Agent(format!("invalid expiry time {secs:?}")))?,
+                ),
+            };
+            let expires = expires.map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs));
+            Ok(AocSession::try_new(value)?.with_expiry(expires))
+        }
+        (Some("err"), Some("no_session_cookie_found"), _) => Err(Error::NoSessionCookieFound),
+        (Some("err"), Some(_), message) => Err(Error::Agent(message.unwrap_or_default().into())),
+        _ => Err(Error::Agent(format!("unexpected reply {reply:?}"))),
+    }
+}
+
+#[cfg(unix)]
+mod platform {
+    use std::fs::{self, DirBuilder, Permissions};
+    use std::io;
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::Path;
+    use std::time::Duration;
+
+    /// How long a client waits for the agent, which may be scanning the browsers.
+    const TIMEOUT: Duration = Duration::from_secs(120);
+    /// How long the agent waits for a client to send its request, so that a client that
+    /// connects and stays silent can't block the others.
+    const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+    pub(super) fn connect(path: &Path) -> io::Result<UnixStream> {
+        let stream = UnixStream::connect(path)?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        Ok(stream)
+    }
+
+    pub(super) fn listen(
+        path: &Path,
+        mut handle: impl FnMut(&str) -> (String, bool),
+    ) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+        }
+        if UnixStream::connect(path).is_ok() {
+            return Err(io::ErrorKind::AddrInUse.into());
+        }
+        // The socket of an agent that didn't stop cleanly.
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+        let listener = UnixListener::bind(path)?;
+        fs::set_permissions(path, Permissions::from_mode(0o600))?;
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else { continue };
+            if stream.set_read_timeout(Some(REQUEST_TIMEOUT)).is_err() {
+                continue;
+            }
+            if super::handle_connection(&stream, &mut handle) {
+                break;
+            }
+        }
+        fs::remove_file(path)
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::ffi::c_void;
+    use std::fs::{File, OpenOptions};
+    use std::io;
+    use std::os::windows::ffi::OsStrExt;
+    use std::os::windows::io::{FromRawHandle, RawHandle};
+    use std::path::Path;
+    use std::ptr;
+
+    const PIPE_ACCESS_DUPLEX: u32 = 0x0000_0003;
+    const FILE_FLAG_FIRST_PIPE_INSTANCE: u32 = 0x0008_0000;
+    const PIPE_REJECT_REMOTE_CLIENTS: u32 = 0x0000_0008;
+    const PIPE_UNLIMITED_INSTANCES: u32 = 255;
+    const BUFFER_SIZE: u32 = 4096;
+    const ERROR_ACCESS_DENIED: i32 = 5;
+    const ERROR_PIPE_CONNECTED: i32 = 535;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn CreateNamedPipeW(
+            name: *const u16,
+            open_mode: u32,
+            pipe_mode: u32,
+            max_instances: u32,
+            out_buffer_size: u32,
+            in_buffer_size: u32,
+            default_timeout: u32,
+            security_attributes: *mut c_void,
+        ) -> RawHandle;
+        fn ConnectNamedPipe(pipe: RawHandle, overlapped: *mut c_void) -> i32;
+        fn DisconnectNamedPipe(pipe: RawHandle) -> i32;
+        fn FlushFileBuffers(file: RawHandle) -> i32;
+    }
+
+    pub(super) fn connect(path: &Path) -> io::Result<File> {
+        OpenOptions::new().read(true).write(true).open(path)
+    }
+
+    pub(super) fn listen(
+        path: &Path,
+        mut handle: impl FnMut(&str) -> (String, bool),
+    ) -> io::Result<()> {
+        let name: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+        // The first instance fails if another process, e.g. another agent, owns the name.
+        let mut first_instance = FILE_FLAG_FIRST_PIPE_INSTANCE;
+        loop {
+            // SAFETY: `name` is NUL-terminated, and no security attributes are passed, so the
+            // pipe gets the default ones, which only grant access to this user.
+            let pipe = unsafe {
+                CreateNamedPipeW(
+                    name.as_ptr(),
+                    PIPE_ACCESS_DUPLEX | first_instance,
+                    PIPE_REJECT_REMOTE_CLIENTS,
+                    PIPE_UNLIMITED_INSTANCES,
+                    BUFFER_SIZE,
+                    BUFFER_SIZE,
+                    0,
+                    ptr::null_mut(),
+                )
+            };
+            if pipe as isize == -1 {
+                let error = io::Error::last_os_error();
+                return match error.raw_os_error() {
+                    Some(ERROR_ACCESS_DENIED) if first_instance != 0 => {
+                        Err(io::ErrorKind::AddrInUse.into())
+                    }
+                    _ => Err(error),
+                };
+            }
+            first_instance = 0;
+            // SAFETY: `pipe` is a valid handle that nothing else owns, and `file` closes it.
+            let file = unsafe { File::from_raw_handle(pipe) };
+            // SAFETY: `pipe` is valid, and the call is synchronous.
+            if unsafe { ConnectNamedPipe(pipe, ptr::null_mut()) } == 0 {
+                let error = io::Error::last_os_error();
+                if error.raw_os_error() != Some(ERROR_PIPE_CONNECTED) {
+                    return Err(error);
+                }
+            }
+            let stop = super::handle_connection(&file, &mut handle);
+            // SAFETY: `pipe` is still open, as `file` is alive.
+            unsafe {
+                FlushFileBuffers(pipe);
+                DisconnectNamedPipe(pipe);
+            }
+            if stop {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Elsewhere, e.g. on WebAssembly, there's no agent to reach, and [`agent_socket_path`] is `None`.
+#[cfg(not(any(unix, windows)))]
+mod platform {
+    use std::fs::File;
+    use std::io;
+    use std::path::Path;
+
+    pub(super) fn connect(_path: &Path) -> io::Result<File> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    pub(super) fn listen(
+        _path: &Path,
+        _handle: impl FnMut(&str) -> (String, bool),
+    ) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replies_are_parsed() {
+        let value = "53616c7465645f5f".repeat(8);
+        let expires = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let session = AocSession::try_new(value.clone())
+            .unwrap()
+            .with_expiry(Some(expires));
+        let parsed = parse_reply(&format_reply(&Ok(session))).unwrap();
+        assert_eq!(parsed.expose_secret(), value);
+        assert_eq!(parsed.expires_at(), Some(expires));
+        let parsed = parse_reply(&format!("ok {value} -\n")).unwrap();
+        assert_eq!(parsed.expires_at(), None);
+        assert!(matches!(
+            parse_reply(&format_reply(&Err(Error::NoSessionCookieFound))),
+            Err(Error::NoSessionCookieFound)
+        ));
+        assert!(matches!(
+            parse_reply(&format_reply(&Err(Error::NoConfigDir))),
+            Err(Error::Agent(message)) if message.contains("HOME")
+        ));
+        assert!(matches!(parse_reply(""), Err(Error::Agent(_))));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn agent_serves_until_stopped() {
+        let path =
+            std::env::temp_dir().join(format!("aoc-session-agent-{}.sock", std::process::id()));
+        assert!(matches!(request(&path, "get"), Err(Error::AgentNotRunning)));
+        let value = "25a16c74".repeat(16);
+        let reply = format!("ok {value} -");
+        let agent = {
+            let path = path.clone();
+            std::thread::spawn(move || {
+                serve(&path, |request| match request {
+                    "get" => (reply.clone(), false),
+                    "stop" => ("ok".to_string(), true),
+                    _ => ("err usage".to_string(), false),
+                })
+            })
+        };
+        while !path.exists() {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let session = parse_reply(&request(&path, "get").unwrap()).unwrap();
+        assert_eq!(session.expose_secret(), value);
+        // A client that never sends a request doesn't block the next one.
+        let _silent = std::os::unix::net::UnixStream::connect(&path).unwrap();
+        assert!(request(&path, "get").is_ok());
+        assert!(matches!(
+            serve(&path, |_| unreachable!()),
+            Err(Error::Agent(_))
+        ));
+        request(&path, "stop").unwrap();
+        agent.join().unwrap().unwrap();
+        assert!(!path.exists());
+    }
+}
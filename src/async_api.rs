@@ -0,0 +1,313 @@
+//! Async wrappers around the blocking API.
+//!
+//! The blocking work runs on a dedicated thread, and the returned futures complete when it's
+//! done. They don't depend on any particular async runtime, so they work with tokio, async-std,
+//! smol, etc. without blocking their executors.
+
+use std::collections::VecDeque;
+use std::future::Future;
+#[cfg(feature = "client")]
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+#[cfg(feature = "client")]
+use crate::{
+    AnswerBounds, AocClient, InputIntegrity, Leaderboard, PersonalTimes, Progress, Puzzle,
+    PuzzleId, PuzzleYear, Submission, SubmitOutcome,
+};
+use crate::{AocSession, Result, SessionWatcher};
+
+struct Shared<T> {
+    result: Option<std::thread::Result<T>>,
+    waker: Option<Waker>,
+}
+
+/// A future that completes with the result of a closure running on its own thread.
+struct Blocking<T> {
+    shared: Arc<Mutex<Shared<T>>>,
+}
+
+fn spawn_blocking<T, F>(f: F) -> Blocking<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let shared = Arc::new(Mutex::new(Shared {
+        result: None,
+        waker: None,
+    }));
+    let thread_shared = Arc::clone(&shared);
+    std::thread::spawn(move || {
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f));
+        let mut shared = thread_shared.lock().unwrap_or_else(|e| e.into_inner());
+        shared.result = Some(result);
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+    });
+    Blocking { shared }
+}
+
+impl<T> Future for Blocking<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut shared = self.shared.lock().unwrap_or_else(|e| e.into_inner());
+        match shared.result.take() {
+            Some(Ok(value)) => Poll::Ready(value),
+            Some(Err(panic)) => std::panic::resume_unwind(panic),
+            None => {
+                shared.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Async version of [`aoc_session`](crate::aoc_session). The slow browser scan runs on its own
+/// thread, so it doesn't block the executor.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn example() {
+/// let session = aoc_session::aoc_session_async().await.unwrap();
+/// println!("My session ID: {session}");
+/// # }
+/// ```
+pub async fn aoc_session_async() -> Result<AocSession> {
+    spawn_blocking(crate::aoc_session).await
+}
+
+/// Async version of [`watch_session`](crate::watch_session): a stream of the session cookie
+/// values held by the browsers, the current one first, then every new one.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn example() {
+/// let mut sessions = aoc_session::watch_session_async();
+/// loop {
+///     let session = sessions.next().await;
+///     println!("New session ID: {session}");
+/// }
+/// # }
+/// ```
+pub fn watch_session_async() -> SessionStream {
+    let queue = Arc::new(Mutex::new(Queue::default()));
+    let watcher = {
+        let queue = Arc::clone(&queue);
+        crate::watch_session(move |session| {
+            let mut queue = queue.lock().unwrap_or_else(|e| e.into_inner());
+            queue.sessions.push_back(session);
+            if let Some(waker) = queue.waker.take() {
+                waker.wake();
+            }
+        })
+    };
+    SessionStream {
+        queue,
+        _watcher: watcher,
+    }
+}
+
+/// The session cookie values found by [`watch_session_async`]. Dropping it stops the watch.
+#[derive(Debug)]
+pub struct SessionStream {
+    queue: Arc<Mutex<Queue>>,
+    _watcher: SessionWatcher,
+}
+
+#[derive(Debug, Default)]
+struct Queue {
+    sessions: VecDeque<AocSession>,
+    waker: Option<Waker>,
+}
+
+impl SessionStream {
+    /// Wait for the next session cookie value. The stream never ends.
+    pub async fn next(&mut self) -> AocSession {
+        std::future::poll_fn(|cx| {
+            let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+            match queue.sessions.pop_front() {
+                Some(session) => Poll::Ready(session),
+                None => {
+                    queue.waker = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            }
+        })
+        .await
+    }
+}
+
+/// Async version of [`AocClient`]. Each request runs on its own thread.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoc_session::{aoc_session_async, AocClient, PuzzleId};
+///
+/// # async fn example() {
+/// let client = AocClient::new(aoc_session_async().await.unwrap()).into_async();
+/// let input = client.get_input(PuzzleId::new(2023, 1).unwrap()).await.unwrap();
+/// # }
+/// ```
+#[cfg(feature = "client")]
+#[derive(Debug, Clone)]
+pub struct AsyncAocClient {
+    client: Arc<AocClient>,
+}
+
+#[cfg(feature = "client")]
+impl AocClient {
+    /// Turn the client into an [`AsyncAocClient`].
+    pub fn into_async(self) -> AsyncAocClient {
+        AsyncAocClient {
+            client: Arc::new(self),
+        }
+    }
+}
+
+#[cfg(feature = "client")]
+impl AsyncAocClient {
+    /// The underlying blocking client.
+    pub fn blocking(&self) -> &AocClient {
+        &self.client
+    }
+
+    /// See [`AocClient::get_input`].
+    pub async fn get_input(&self, puzzle: PuzzleId) -> Result<String> {
+        let client = Arc::clone(&self.client);
+        spawn_blocking(move || client.get_input(puzzle)).await
+    }
+
+    /// See [`AocClient::refresh_input`].
+    pub async fn refresh_input(&self, puzzle: PuzzleId) -> Result<String> {
+        let client = Arc::clone(&self.client);
+        spawn_blocking(move || client.refresh_input(puzzle)).await
+    }
+
+    /// See [`AocClient::verify_input`].
+    pub async fn verify_input(&self, puzzle: PuzzleId) -> Result<InputIntegrity> {
+        let client = Arc::clone(&self.client);
+        spawn_blocking(move || client.verify_input(puzzle)).await
+    }
+
+    /// See [`AocClient::wait_for_unlock`].
+    pub async fn wait_for_unlock(&self, puzzle: PuzzleId) {
+        let client = Arc::clone(&self.client);
+        spawn_blocking(move || client.wait_for_unlock(puzzle)).await
+    }
+
+    /// See [`AocClient::download_year_inputs`].
+    pub async fn download_year_inputs(
+        &self,
+        year: PuzzleYear,
+        dir: impl Into<PathBuf>,
+    ) -> Result<Vec<PathBuf>> {
+        let client = Arc::clone(&self.client);
+        let dir = dir.into();
+        spawn_blocking(move || client.download_year_inputs(year, dir)).await
+    }
+
+    /// See [`AocClient::get_puzzle`].
+    pub async fn get_puzzle(&self, puzzle: PuzzleId) -> Result<Puzzle> {
+        let client = Arc::clone(&self.client);
+        spawn_blocking(move || client.get_puzzle(puzzle)).await
+    }
+
+    /// See [`AocClient::private_leaderboard`].
+    pub async fn private_leaderboard(&self, year: PuzzleYear, id: u64) -> Result<Leaderboard> {
+        let client = Arc::clone(&self.client);
+        spawn_blocking(move || client.private_leaderboard(year, id)).await
+    }
+
+    /// See [`AocClient::progress`].
+    pub async fn progress(&self, year: PuzzleYear) -> Result<Progress> {
+        let client = Arc::clone(&self.client);
+        spawn_blocking(move || client.progress(year)).await
+    }
+
+    /// See [`AocClient::personal_times`].
+    pub async fn personal_times(&self, year: PuzzleYear) -> Result<PersonalTimes> {
+        let client = Arc::clone(&self.client);
+        spawn_blocking(move || client.personal_times(year)).await
+    }
+
+    /// See [`AocClient::submit`].
+    pub async fn submit(
+        &self,
+        puzzle: PuzzleId,
+        level: u8,
+        answer: impl ToString,
+    ) -> Result<SubmitOutcome> {
+        let client = Arc::clone(&self.client);
+        let answer = answer.to_string();
+        spawn_blocking(move || client.submit(puzzle, level, answer)).await
+    }
+
+    /// See [`AocClient::answer_history`].
+    pub async fn answer_history(&self, puzzle: PuzzleId) -> Result<Vec<Submission>> {
+        let client = Arc::clone(&self.client);
+        spawn_blocking(move || client.answer_history(puzzle)).await
+    }
+
+    /// See [`AocClient::answer_bounds`].
+    pub async fn answer_bounds(&self, puzzle: PuzzleId, level: u8) -> Result<AnswerBounds> {
+        let client = Arc::clone(&self.client);
+        spawn_blocking(move || client.answer_bounds(puzzle, level)).await
+    }
+
+    /// See [`AocClient::validate_session`].
+    pub async fn validate_session(&self) -> Result<()> {
+        let client = Arc::clone(&self.client);
+        spawn_blocking(move || client.validate_session()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake};
+    use std::thread::Thread;
+
+    use super::*;
+
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = std::pin::pin!(future);
+        let waker = Arc::new(ThreadWaker(std::thread::current())).into();
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => std::thread::park(),
+            }
+        }
+    }
+
+    #[test]
+    fn blocking_work_completes() {
+        let value = block_on(spawn_blocking(|| {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            42
+        }));
+        assert_eq!(value, 42);
+    }
+
+    #[test]
+    #[should_panic(expected = "boom")]
+    fn panics_are_propagated() {
+        block_on(spawn_blocking(|| panic!("boom")))
+    }
+}
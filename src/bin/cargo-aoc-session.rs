@@ -0,0 +1,34 @@
+//! `cargo aoc-session`: the `aoc-session` executable under the name that cargo looks up for the
+//! `aoc-session` subcommand.
+
+#[path = "../main.rs"]
+#[allow(dead_code)]
+mod aoc_session_main;
+
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    aoc_session_main::run(cargo_args(std::env::args().skip(1)))
+}
+
+/// The arguments without the subcommand name, which cargo passes first, so that both
+/// `cargo aoc-session status` and `cargo-aoc-session status` work.
+fn cargo_args(args: impl Iterator<Item = String>) -> Vec<String> {
+    let mut args: Vec<_> = args.collect();
+    if args.first().is_some_and(|arg| arg == "aoc-session") {
+        args.remove(0);
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subcommand_name_is_skipped() {
+        let args = |args: &[&str]| cargo_args(args.iter().map(|arg| arg.to_string()));
+        assert_eq!(args(&["aoc-session", "status"]), ["status"]);
+        assert_eq!(args(&["status"]), ["status"]);
+    }
+}
@@ -0,0 +1,636 @@
+use core::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+#[cfg(all(feature = "browser", not(target_family = "wasm")))]
+use std::time::Duration;
+use std::time::SystemTime;
+
+#[cfg(all(feature = "browser", not(target_family = "wasm")))]
+use rookie::common::enums::{BrowserConfig, Cookie};
+
+use crate::{AocSession, Error, Result, SessionSpec};
+
+/// A browser whose cookie store can be queried on its own.
+///
+/// Querying a single browser is much faster than scanning all of them and does not trigger
+/// keychain prompts for browsers you don't use.
+///
+/// Each family of browsers is read only with its cargo feature, all of which are on by default:
+/// `firefox` for Firefox and LibreWolf, `chromium` for the browsers based on Chromium, and
+/// `safari` for Safari. Without any of them, the `browser` feature is off too and no browser can
+/// be read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Browser {
+    Firefox,
+    LibreWolf,
+    Chrome,
+    Chromium,
+    Edge,
+    Brave,
+    Opera,
+    Vivaldi,
+    /// Only available on macOS.
+    Safari,
+    /// Only available on macOS.
+    Arc,
+}
+
+#[cfg(all(target_os = "macos", feature = "chromium"))]
+static ARC_CONFIG: BrowserConfig<'static> = BrowserConfig {
+    data_paths: &[
+        "~/Library/Application Support/Arc/User Data/Default/Cookies",
+        "~/Library/Application Support/Arc/User Data/Profile */Cookies",
+    ],
+    channels: None,
+    os_crypt_name: Some("chrome"),
+    osx_key_service: Some("Arc Safe Storage"),
+    osx_key_user: Some("Arc"),
+};
+
+/// The family of cookie store formats a browser uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Engine {
+    Gecko,
+    Chromium,
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    WebKit,
+}
+
+/// The location of the cookie database of a browser.
+#[derive(Debug, Clone)]
+pub(crate) struct CookieStore {
+    pub(crate) browser: Browser,
+    pub(crate) db_path: PathBuf,
+    /// The `Local State` file holding the key of Chromium-based browsers on Windows.
+    #[cfg_attr(not(windows), allow(dead_code))]
+    key_path: Option<PathBuf>,
+}
+
+impl CookieStore {
+    /// The cookie database in the directory of a profile of the browser.
+    pub(crate) fn in_profile(browser: Browser, profile: &Path) -> Result<CookieStore> {
+        let candidates: &[&str] = match browser.engine() {
+            Engine::Gecko => &["cookies.sqlite"],
+            Engine::Chromium => &["Network/Cookies", "Cookies"],
+            Engine::WebKit => &[],
+        };
+        let db_path = candidates
+            .iter()
+            .map(|name| profile.join(name))
+            .find(|path| path.is_file())
+            .ok_or_else(|| Error::CookieStoreNotFound {
+                browser,
+                reason: format!("{} holds no cookie database", profile.display()),
+            })?;
+        CookieStore::at(browser, &db_path)
+    }
+
+    /// The cookie database at the given path, written by the browser.
+    pub(crate) fn at(browser: Browser, db_path: &Path) -> Result<CookieStore> {
+        if !db_path.is_file() {
+            return Err(Error::Io {
+                path: db_path.to_path_buf(),
+                source: std::io::ErrorKind::NotFound.into(),
+            });
+        }
+        // Like `rookie`, look for the `Local State` file in the user data directory above the
+        // profile, which is one level higher when the database is in `Network`.
+        let key_path = match browser.engine() {
+            Engine::Chromium => db_path
+                .ancestors()
+                .skip(1)
+                .take(3)
+                .map(|dir| dir.join("Local State"))
+                .find(|path| path.is_file()),
+            _ => None,
+        };
+        Ok(CookieStore {
+            browser,
+            db_path: db_path.to_path_buf(),
+            key_path,
+        })
+    }
+
+    /// The directory of the browser profile the cookie database belongs to.
+    pub(crate) fn profile(&self) -> PathBuf {
+        let parent = self.db_path.parent().unwrap_or(Path::new(""));
+        match parent.file_name() {
+            Some(name) if name == "Network" => parent.parent().unwrap_or(parent).to_path_buf(),
+            _ => parent.to_path_buf(),
+        }
+    }
+
+    /// The session cookies of adventofcode.com in the store, usually one.
+    pub(crate) fn sessions(&self) -> Result<Vec<AocSession>> {
+        self.sessions_for(&SessionSpec::ADVENT_OF_CODE)
+    }
+
+    /// The cookies described by the spec in the store, usually one.
+    #[cfg(all(feature = "browser", not(target_family = "wasm")))]
+    pub(crate) fn sessions_for(&self, spec: &SessionSpec) -> Result<Vec<AocSession>> {
+        let cookies = match self.cookies(self.db_path.clone(), spec) {
+            Ok(cookies) => cookies,
+            Err(e) => match classify(self.browser, &self.db_path, e) {
+                // The browser is running and holds the database; read a copy instead.
+                locked @ Error::LockedDatabase { .. } => {
+                    let copy = crate::fs::TempCopy::of_database(&self.db_path);
+                    match copy.map(|copy| self.cookies(copy.path().to_path_buf(), spec)) {
+                        Ok(Ok(cookies)) => cookies,
+                        _ => return Err(locked),
+                    }
+                }
+                e => return Err(e),
+            },
+        };
+        Ok(cookies
+            .into_iter()
+            .filter(|cookie| cookie.name == spec.cookie_name() && spec.matches_host(&cookie.domain))
+            .map(session)
+            .collect())
+    }
+
+    /// The cookies of the domain of the spec in the database at `db_path`, read by [`rookie`].
+    #[cfg(all(feature = "browser", not(target_family = "wasm")))]
+    fn cookies(&self, db_path: PathBuf, spec: &SessionSpec) -> anyhow::Result<Vec<Cookie>> {
+        // `rookie` keeps any cookie whose host contains one of the domains, so the hosts are
+        // matched exactly by the caller.
+        let domains = Some(vec![spec.domain()]);
+        match self.browser.engine() {
+            #[cfg(feature = "firefox")]
+            Engine::Gecko => rookie::browser::mozilla::firefox_based(db_path, domains),
+            #[cfg(all(windows, feature = "chromium"))]
+            Engine::Chromium => {
+                let key_path = self.key_path.clone().unwrap_or_default();
+                rookie::browser::chromium::chromium_based(key_path, db_path, domains)
+            }
+            #[cfg(all(unix, feature = "chromium"))]
+            Engine::Chromium => {
+                rookie::browser::chromium::chromium_based(self.browser.config()?, db_path, domains)
+            }
+            #[cfg(all(target_os = "macos", feature = "safari"))]
+            Engine::WebKit => rookie::browser::safari::safari_based(db_path, domains),
+            #[allow(unreachable_patterns)]
+            _ => {
+                let _ = (db_path, domains);
+                Err(anyhow::anyhow!("{} is not supported", self.browser))
+            }
+        }
+    }
+
+    /// The cookies described by the spec in the store, which can't be read without the `browser`
+    /// feature.
+    #[cfg(any(not(feature = "browser"), target_family = "wasm"))]
+    pub(crate) fn sessions_for(&self, _spec: &SessionSpec) -> Result<Vec<AocSession>> {
+        Err(Error::UnsupportedBrowser(self.browser))
+    }
+}
+
+/// Get the session cookie for Advent of Code from the cookie database at the given path, e.g. the
+/// `cookies.sqlite` of a portable Firefox or the `Cookies` file of Chrome in a container.
+///
+/// `kind` is the browser that wrote the database. It sets the format of the database and, for the
+/// browsers based on Chromium, where the key that encrypts the cookies is kept. On Windows, that
+/// key is read from the `Local State` file of the user data directory above the database.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoc_session::{aoc_session_from_cookie_db, Browser};
+///
+/// let path = "/opt/firefox-portable/profile/cookies.sqlite";
+/// let session = aoc_session_from_cookie_db(path, Browser::Firefox).unwrap();
+/// println!("My session ID: {session}");
+/// ```
+pub fn aoc_session_from_cookie_db(path: impl AsRef<Path>, kind: Browser) -> Result<AocSession> {
+    if !kind.is_supported() {
+        return Err(Error::UnsupportedBrowser(kind));
+    }
+    CookieStore::at(kind, path.as_ref())?
+        .sessions()?
+        .into_iter()
+        .next()
+        .ok_or(Error::NoSessionCookieFound)
+}
+
+/// A session cookie found in a browser, along with where it came from.
+#[derive(Debug)]
+pub struct DiscoveredSession {
+    /// The browser whose cookie store holds the cookie.
+    pub browser: Browser,
+    /// The directory of the browser profile.
+    pub profile: PathBuf,
+    /// When the cookie expires, if the browser recorded it.
+    pub expires: Option<SystemTime>,
+    /// The value of the cookie.
+    pub session: AocSession,
+}
+
+impl Browser {
+    /// All browsers, in the order they are listed in the enum.
+    pub const ALL: [Browser; 10] = [
+        Browser::Firefox,
+        Browser::LibreWolf,
+        Browser::Chrome,
+        Browser::Chromium,
+        Browser::Edge,
+        Browser::Brave,
+        Browser::Opera,
+        Browser::Vivaldi,
+        Browser::Safari,
+        Browser::Arc,
+    ];
+
+    /// Lowercase name of the browser, as accepted by [`FromStr`].
+    pub fn name(self) -> &'static str {
+        match self {
+            Browser::Firefox => "firefox",
+            Browser::LibreWolf => "librewolf",
+            Browser::Chrome => "chrome",
+            Browser::Chromium => "chromium",
+            Browser::Edge => "edge",
+            Browser::Brave => "brave",
+            Browser::Opera => "opera",
+            Browser::Vivaldi => "vivaldi",
+            Browser::Safari => "safari",
+            Browser::Arc => "arc",
+        }
+    }
+
+    /// Whether the cookie store of the browser can be read on the current platform, with the
+    /// enabled cargo features.
+    pub fn is_supported(self) -> bool {
+        match self.engine() {
+            Engine::Gecko => cfg!(feature = "firefox"),
+            Engine::Chromium => {
+                cfg!(feature = "chromium") && (self != Browser::Arc || cfg!(target_os = "macos"))
+            }
+            Engine::WebKit => cfg!(feature = "safari") && cfg!(target_os = "macos"),
+        }
+    }
+
+    /// Whether reading the cookies of the browser may prompt for the password of the macOS
+    /// Keychain, which holds the key that the browsers based on Chromium encrypt them with.
+    pub fn prompts_for_keychain(self) -> bool {
+        cfg!(target_os = "macos") && self.engine() == Engine::Chromium
+    }
+
+    /// Whether the browser is based on Firefox, whose profiles are listed in `profiles.ini`.
+    pub(crate) fn is_gecko(self) -> bool {
+        self.engine() == Engine::Gecko
+    }
+
+    /// Whether the browser is based on Chromium, whose profiles are the `Default` and
+    /// `Profile N` directories in its user data directory.
+    pub(crate) fn is_chromium(self) -> bool {
+        self.engine() == Engine::Chromium
+    }
+
+    fn engine(self) -> Engine {
+        match self {
+            Browser::Firefox | Browser::LibreWolf => Engine::Gecko,
+            Browser::Safari => Engine::WebKit,
+            _ => Engine::Chromium,
+        }
+    }
+
+    /// The [`rookie`] configuration describing where the browser keeps its cookies.
+    #[cfg(all(feature = "browser", not(target_family = "wasm")))]
+    fn config(self) -> Result<&'static BrowserConfig<'static>> {
+        #[allow(unused_imports)]
+        use rookie::config::*;
+
+        match self {
+            #[cfg(feature = "firefox")]
+            Browser::Firefox => Ok(&FIREFOX_CONFIG),
+            #[cfg(feature = "firefox")]
+            Browser::LibreWolf => Ok(&LIBRE_WOLF_CONFIG),
+            #[cfg(feature = "chromium")]
+            Browser::Chrome => Ok(&CHROME_CONFIG),
+            #[cfg(feature = "chromium")]
+            Browser::Chromium => Ok(&CHROMIUM_CONFIG),
+            #[cfg(feature = "chromium")]
+            Browser::Edge => Ok(&EDGE_CONFIG),
+            #[cfg(feature = "chromium")]
+            Browser::Brave => Ok(&BRAVE_CONFIG),
+            #[cfg(feature = "chromium")]
+            Browser::Opera => Ok(&OPERA_CONFIG),
+            #[cfg(feature = "chromium")]
+            Browser::Vivaldi => Ok(&VIVALDI_CONFIG),
+            #[cfg(all(target_os = "macos", feature = "safari"))]
+            Browser::Safari => Ok(&SAFARI_CONFIG),
+            #[cfg(all(target_os = "macos", feature = "chromium"))]
+            Browser::Arc => Ok(&ARC_CONFIG),
+            #[allow(unreachable_patterns)]
+            _ => Err(Error::UnsupportedBrowser(self)),
+        }
+    }
+
+    /// The directories the browser keeps its profiles in, whether they exist or not: the
+    /// directory holding `profiles.ini` for browsers based on Firefox, and the user data directory
+    /// for browsers based on Chromium.
+    #[cfg(all(feature = "browser", not(target_family = "wasm")))]
+    pub(crate) fn data_dirs(self) -> Vec<PathBuf> {
+        let Ok(config) = self.config() else {
+            return Vec::new();
+        };
+        let channels = config.channels.unwrap_or(&[""]);
+        let paths = config
+            .data_paths
+            .iter()
+            .flat_map(|path| {
+                channels
+                    .iter()
+                    .map(move |channel| path.replace("{channel}", channel))
+            })
+            .filter_map(|path| match self.engine() {
+                Engine::Gecko => Some(path),
+                Engine::Chromium => user_data_dir(&path).map(str::to_string),
+                Engine::WebKit => None,
+            })
+            .filter_map(|path| rookie::common::paths::expand_path(&path).ok());
+        let home = crate::dirs::home_dir();
+        let sandboxed = home.map(|home| crate::sandbox::data_dirs(self, &home));
+        let mut dirs = Vec::new();
+        for path in paths.chain(sandboxed.into_iter().flatten()) {
+            if !dirs.contains(&path) {
+                dirs.push(path);
+            }
+        }
+        dirs
+    }
+
+    /// The directories the browser keeps its profiles in, which aren't known without the
+    /// `browser` feature.
+    #[cfg(any(not(feature = "browser"), target_family = "wasm"))]
+    pub(crate) fn data_dirs(self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    /// Locate the cookie database of the browser.
+    #[cfg(all(feature = "browser", not(target_family = "wasm")))]
+    pub(crate) fn find_store(self) -> Result<CookieStore> {
+        let config = self.config()?;
+        let paths = match self.engine() {
+            #[cfg(feature = "firefox")]
+            Engine::Gecko => rookie::common::paths::find_mozilla_based_paths(config)
+                .map(|db_path| (None, db_path)),
+            #[cfg(feature = "chromium")]
+            Engine::Chromium => rookie::common::paths::find_chrome_based_paths(config)
+                .map(|(key_path, db_path)| (Some(key_path), db_path)),
+            #[cfg(all(target_os = "macos", feature = "safari"))]
+            Engine::WebKit => rookie::common::paths::find_safari_based_paths(config)
+                .map(|db_path| (None, db_path)),
+            #[allow(unreachable_patterns)]
+            _ => {
+                let _ = config;
+                Err(anyhow::anyhow!("{self} is not supported"))
+            }
+        };
+        match paths {
+            Ok((key_path, db_path)) => Ok(CookieStore {
+                browser: self,
+                db_path,
+                key_path,
+            }),
+            Err(e) => self.find_sandboxed_store().unwrap_or_else(|| {
+                Err(match crate::sandbox::current() {
+                    Some(sandbox) => Error::Sandboxed {
+                        browser: self,
+                        sandbox,
+                    },
+                    None => Error::CookieStoreNotFound {
+                        browser: self,
+                        reason: e.to_string(),
+                    },
+                })
+            }),
+        }
+    }
+
+    /// Locate the cookie database of the default profile of a Snap or Flatpak package of the
+    /// browser, which [`rookie`] doesn't always know about. Returns `None` if there's no such
+    /// package.
+    #[cfg(all(feature = "browser", not(target_family = "wasm")))]
+    fn find_sandboxed_store(self) -> Option<Result<CookieStore>> {
+        let home = crate::dirs::home_dir()?;
+        let dir = crate::sandbox::data_dirs(self, &home).into_iter().next()?;
+        if let Err(e) = std::fs::read_dir(&dir) {
+            if e.kind() == std::io::ErrorKind::PermissionDenied {
+                return Some(Err(Error::PermissionDenied {
+                    browser: self,
+                    path: dir,
+                }));
+            }
+        }
+        let profiles = self.profiles_in(&dir);
+        let profile = profiles
+            .iter()
+            .find(|profile| profile.is_default)
+            .or(profiles.first())
+            .map(|profile| profile.path.clone())
+            .unwrap_or(dir);
+        Some(CookieStore::in_profile(self, &profile))
+    }
+
+    /// Locate the cookie database of the browser, which can't be done without the `browser`
+    /// feature.
+    #[cfg(any(not(feature = "browser"), target_family = "wasm"))]
+    pub(crate) fn find_store(self) -> Result<CookieStore> {
+        Err(Error::UnsupportedBrowser(self))
+    }
+
+    /// The cookies described by the spec in the browser, usually one.
+    pub(crate) fn sessions_for(self, spec: &SessionSpec) -> Result<Vec<AocSession>> {
+        self.find_store()?.sessions_for(spec)
+    }
+}
+
+impl fmt::Display for Browser {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The user data directory in the path of a cookie database listed by [`rookie`], e.g.
+/// `~/.config/google-chrome` in `~/.config/google-chrome/Profile */Cookies`.
+#[cfg(all(feature = "browser", not(target_family = "wasm")))]
+fn user_data_dir(cookies: &str) -> Option<&str> {
+    ["/Default/", "/Profile */"]
+        .iter()
+        .filter_map(|profile| cookies.find(profile))
+        .min()
+        .map(|end| &cookies[..end])
+}
+
+/// Turn an error of [`rookie`] while reading the cookie database at `path` into the matching
+/// variant of [`Error`]. `rookie` mostly reports errors as bare messages, so they are told apart
+/// here, once, by the I/O errors they wrap or else by their wording.
+#[cfg(all(feature = "browser", not(target_family = "wasm")))]
+fn classify(browser: Browser, path: &Path, error: anyhow::Error) -> Error {
+    let path = path.to_path_buf();
+    let io_kind = error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<std::io::Error>())
+        .map(std::io::Error::kind);
+    // `{:#}` includes the causes, e.g. the SQLite error under a generic message.
+    let message = format!("{error:#}").to_lowercase();
+    let mentions = |words: &[&str]| words.iter().any(|word| message.contains(word));
+    if io_kind == Some(std::io::ErrorKind::PermissionDenied)
+        || mentions(&["permission denied", "access is denied"])
+    {
+        Error::PermissionDenied { browser, path }
+    } else if mentions(&[
+        "database is locked",
+        "database table is locked",
+        // A sharing violation on Windows.
+        "used by another process",
+    ]) {
+        Error::LockedDatabase { browser, path }
+    } else if mentions(&[
+        "decrypt",
+        "keychain",
+        "secret",
+        "unlock",
+        "cryptunprotectdata",
+        "encrypted_key",
+        "os crypt",
+        "dbus",
+        "d-bus",
+    ]) {
+        Error::DecryptionFailed {
+            browser,
+            reason: error.to_string(),
+        }
+    } else {
+        Error::CookieStoreUnreadable {
+            browser,
+            path,
+            reason: error.to_string(),
+        }
+    }
+}
+
+/// Convert a cookie to a session, along with the expiry recorded by [`rookie`] (seconds since the
+/// Unix epoch).
+#[cfg(all(feature = "browser", not(target_family = "wasm")))]
+fn session(cookie: Cookie) -> AocSession {
+    let expires = cookie
+        .expires
+        .map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs));
+    AocSession::unchecked(cookie.value).with_expiry(expires)
+}
+
+impl FromStr for Browser {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Browser::ALL
+            .into_iter()
+            .find(|browser| browser.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| Error::UnknownBrowser(s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_round_trip() {
+        for browser in Browser::ALL {
+            assert_eq!(browser.name().parse::<Browser>().unwrap(), browser);
+        }
+        assert_eq!("LibreWolf".parse::<Browser>().unwrap(), Browser::LibreWolf);
+        assert!(matches!(
+            "netscape".parse::<Browser>(),
+            Err(Error::UnknownBrowser(_))
+        ));
+    }
+
+    #[test]
+    fn only_chromium_prompts_for_the_keychain() {
+        assert_eq!(
+            Browser::Chrome.prompts_for_keychain(),
+            cfg!(target_os = "macos")
+        );
+        assert!(!Browser::Firefox.prompts_for_keychain());
+        assert!(!Browser::Safari.prompts_for_keychain());
+    }
+
+    #[cfg(all(feature = "browser", not(target_family = "wasm")))]
+    #[test]
+    fn rookie_errors_are_classified() {
+        let path = Path::new("/profile/cookies.sqlite");
+        let classify = |error| classify(Browser::Firefox, path, error);
+        let denied = std::io::Error::from(std::io::ErrorKind::PermissionDenied);
+        assert!(matches!(
+            classify(anyhow::Error::new(denied).context("Error opening connection")),
+            Error::PermissionDenied { .. }
+        ));
+        assert!(matches!(
+            classify(anyhow::anyhow!("database is locked")),
+            Error::LockedDatabase { browser: Browser::Firefox, path: p } if p == path
+        ));
+        assert!(matches!(
+            classify(anyhow::anyhow!(
+                "The process cannot access the file because it is being used by another process."
+            )),
+            Error::LockedDatabase { .. }
+        ));
+        assert!(matches!(
+            classify(anyhow::anyhow!(
+                "Failed to retrieve password from OSX Keychain"
+            )),
+            Error::DecryptionFailed { .. }
+        ));
+        assert!(matches!(
+            classify(anyhow::anyhow!("not a cookie file")),
+            Error::CookieStoreUnreadable { .. }
+        ));
+    }
+
+    #[cfg(all(feature = "browser", not(target_family = "wasm")))]
+    #[test]
+    fn user_data_dir_is_found() {
+        assert_eq!(
+            user_data_dir("~/.config/google-chrome-beta/Profile */Cookies"),
+            Some("~/.config/google-chrome-beta")
+        );
+        assert_eq!(
+            user_data_dir("%APPDATA%/Microsoft/Edge/User Data/Default/Network/Cookies"),
+            Some("%APPDATA%/Microsoft/Edge/User Data")
+        );
+        assert_eq!(user_data_dir("~/.config/opera/Cookies"), None);
+    }
+
+    #[test]
+    fn cookie_db_key_is_found_above_it() {
+        let dir = std::env::temp_dir().join("aoc-session-cookie-db-test");
+        let network = dir.join("User Data").join("Default").join("Network");
+        std::fs::create_dir_all(&network).unwrap();
+        std::fs::write(network.join("Cookies"), "").unwrap();
+        std::fs::write(dir.join("User Data").join("Local State"), "{}").unwrap();
+        let store = CookieStore::at(Browser::Edge, &network.join("Cookies")).unwrap();
+        assert_eq!(
+            store.key_path,
+            Some(dir.join("User Data").join("Local State"))
+        );
+        assert!(matches!(
+            CookieStore::at(Browser::Firefox, &dir.join("cookies.sqlite")),
+            Err(e) if e.is_absence()
+        ));
+    }
+
+    #[test]
+    fn profile_skips_network_directory() {
+        let store = CookieStore {
+            browser: Browser::Chrome,
+            db_path: PathBuf::from("/home/me/.config/google-chrome/Default/Network/Cookies"),
+            key_path: None,
+        };
+        assert_eq!(
+            store.profile(),
+            Path::new("/home/me/.config/google-chrome/Default")
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! Helpers for build scripts, which embed the session cookie value in the binaries they build.
+//!
+//! # Examples
+//!
+//! In the `main` function of `build.rs`, with `aoc-session` among the `[build-dependencies]`:
+//!
+//! ```no_run
+//! aoc_session::build::emit_rustc_env().expect("no session cookie for Advent of Code");
+//! ```
+//!
+//! Then, in the crate:
+//!
+//! ```ignore
+//! let session = aoc_session::AocSession::try_new(env!("AOC_SESSION")).unwrap();
+//! ```
+
+use std::io::Write;
+use std::path::Path;
+
+use crate::{Config, Result, Source, ENV_VARS, FILE_ENV_VAR_SUFFIX};
+
+/// Resolve the session cookie value as [`aoc_session`](crate::aoc_session) does and hand it to
+/// the crate being built as the `AOC_SESSION` environment variable, to be read with
+/// `env!("AOC_SESSION")`.
+///
+/// The build script reruns when one of [`ENV_VARS`] changes, or when the file the value was
+/// found in or the configuration file changes. A value found in a browser is not tracked, since
+/// browsers write their cookie stores all the time; run `cargo clean` after logging in again.
+///
+/// The value ends up in the binary, so don't distribute it.
+pub fn emit_rustc_env() -> Result<()> {
+    let (session, source) = Config::load()?.session_builder().resolve_with_source()?;
+    let config = Config::path().filter(|path| path.exists());
+    emit(
+        &mut std::io::stdout().lock(),
+        session.expose_secret(),
+        &source,
+        config.as_deref(),
+    );
+    Ok(())
+}
+
+fn emit(out: &mut impl Write, value: &str, source: &Source, config: Option<&Path>) {
+    let mut directives: Vec<String> = ENV_VARS
+        .iter()
+        .flat_map(|var| [var.to_string(), format!("{var}{FILE_ENV_VAR_SUFFIX}")])
+        .map(|var| format!("cargo:rerun-if-env-changed={var}"))
+        .collect();
+    match source {
+        Source::File(path) | Source::CookiesTxt(path) | Source::CookieDb(path, _) => {
+            directives.push(format!("cargo:rerun-if-changed={}", path.display()))
+        }
+        #[cfg(feature = "har")]
+        Source::Har(path) => directives.push(format!("cargo:rerun-if-changed={}", path.display())),
+        _ => {}
+    }
+    if let Some(config) = config {
+        directives.push(format!("cargo:rerun-if-changed={}", config.display()));
+    }
+    directives.push(format!("cargo:rustc-env={}={value}", ENV_VARS[0]));
+    for directive in directives {
+        // A build script can't do anything sensible about a closed standard output.
+        let _ = writeln!(out, "{directive}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    #[test]
+    fn directives() {
+        let mut out = Vec::new();
+        emit(
+            &mut out,
+            "25a16c74",
+            &Source::File(PathBuf::from("/home/me/.aoc-session")),
+            Some(Path::new("/home/me/.config/aoc-session/config.toml")),
+        );
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "cargo:rerun-if-env-changed=AOC_SESSION\n\
+             cargo:rerun-if-env-changed=AOC_SESSION_FILE\n\
+             cargo:rerun-if-env-changed=ADVENT_OF_CODE_SESSION\n\
+             cargo:rerun-if-env-changed=ADVENT_OF_CODE_SESSION_FILE\n\
+             cargo:rerun-if-changed=/home/me/.aoc-session\n\
+             cargo:rerun-if-changed=/home/me/.config/aoc-session/config.toml\n\
+             cargo:rustc-env=AOC_SESSION=25a16c74\n"
+        );
+    }
+}
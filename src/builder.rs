@@ -0,0 +1,501 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::session_cache::SessionCache;
+use crate::source::Exclusions;
+use crate::{
+    AocSession, Browser, BrowserSource, DiagnosticsReport, Error, FirefoxProfile, Result,
+    SelectionStrategy, SessionSpec, Source, SourceWarning,
+};
+
+/// Builder of an ordered chain of [`Source`]s. The session cookie value is taken from the first
+/// source that yields one.
+///
+/// # Examples
+///
+/// ```
+/// use aoc_session::AocSessionBuilder;
+///
+/// let session = AocSessionBuilder::new()
+///     .env()
+///     .file(".aoc-session")
+///     .browsers()
+///     .resolve();
+///
+/// match session {
+///     Ok(session) => println!("My session ID: {session}"),
+///     Err(e) => panic!("Error: {e}"),
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AocSessionBuilder {
+    sources: Vec<Source>,
+    selection: SelectionStrategy,
+    persist: Option<Duration>,
+    spec: Option<SessionSpec>,
+    excluded: Exclusions,
+}
+
+impl AocSessionBuilder {
+    /// Create a builder with an empty chain of sources.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a source to the chain.
+    pub fn source(mut self, source: Source) -> Self {
+        self.sources.push(source);
+        self
+    }
+
+    /// Remove every occurrence of `source` from the chain.
+    #[cfg(feature = "agent")]
+    pub(crate) fn without(mut self, source: &Source) -> Self {
+        self.sources.retain(|s| s != source);
+        self
+    }
+
+    /// The same builder with a chain of [`Source::Browsers`] alone, and no cache.
+    pub(crate) fn browsers_only(mut self) -> Self {
+        self.sources = vec![Source::Browsers];
+        self.persist = None;
+        self
+    }
+
+    /// The browsers the browser sources must not read.
+    pub(crate) fn exclusions(&self) -> &Exclusions {
+        &self.excluded
+    }
+
+    /// Append [`Source::Env`] to the chain.
+    pub fn env(self) -> Self {
+        self.source(Source::Env)
+    }
+
+    /// Append [`Source::DotEnv`] to the chain.
+    #[cfg(feature = "dotenv")]
+    pub fn dotenv(self) -> Self {
+        self.source(Source::DotEnv)
+    }
+
+    /// Append [`Source::File`] to the chain.
+    pub fn file(self, path: impl Into<PathBuf>) -> Self {
+        self.source(Source::File(path.into()))
+    }
+
+    /// Append [`Source::CookiesTxt`] to the chain.
+    pub fn cookies_txt(self, path: impl Into<PathBuf>) -> Self {
+        self.source(Source::CookiesTxt(path.into()))
+    }
+
+    /// Append [`Source::Har`] to the chain.
+    #[cfg(feature = "har")]
+    pub fn har(self, path: impl Into<PathBuf>) -> Self {
+        self.source(Source::Har(path.into()))
+    }
+
+    /// Append [`Source::CookieDb`] to the chain.
+    pub fn cookie_db(self, path: impl Into<PathBuf>, kind: Browser) -> Self {
+        self.source(Source::CookieDb(path.into(), kind))
+    }
+
+    /// Append [`Source::Keyring`] to the chain.
+    #[cfg(feature = "keyring")]
+    pub fn keyring(self) -> Self {
+        self.source(Source::Keyring)
+    }
+
+    /// Append [`Source::SessionFiles`] to the chain.
+    pub fn session_files(self) -> Self {
+        self.source(Source::SessionFiles)
+    }
+
+    /// Append [`Source::Browser`] to the chain.
+    pub fn browser(self, browser: Browser) -> Self {
+        self.source(Source::Browser(browser))
+    }
+
+    /// Append [`Source::FirefoxProfile`] to the chain.
+    pub fn firefox_profile(self, profile: FirefoxProfile) -> Self {
+        self.source(Source::FirefoxProfile(profile))
+    }
+
+    /// Append [`Source::Profile`] to the chain, e.g. to read a single profile of Chrome.
+    pub fn browser_source(self, source: BrowserSource) -> Self {
+        self.source(Source::Profile(source))
+    }
+
+    /// Append [`Source::Agent`] to the chain.
+    #[cfg(feature = "agent")]
+    pub fn agent(self) -> Self {
+        self.source(Source::Agent)
+    }
+
+    /// Append [`Source::Ssh`] to the chain.
+    #[cfg(feature = "remote")]
+    pub fn ssh(self, destination: impl Into<String>) -> Self {
+        self.source(Source::Ssh(destination.into()))
+    }
+
+    /// Append [`Source::Cdp`] to the chain.
+    #[cfg(feature = "cdp")]
+    pub fn cdp(self, port: u16) -> Self {
+        self.source(Source::Cdp(port))
+    }
+
+    /// Append [`Source::SecretManager`] to the chain.
+    #[cfg(feature = "secret-managers")]
+    pub fn secret_manager(self, item: crate::SecretManager) -> Self {
+        self.source(Source::SecretManager(item))
+    }
+
+    /// Append [`Source::Browsers`] to the chain.
+    pub fn browsers(self) -> Self {
+        self.source(Source::Browsers)
+    }
+
+    /// Set how [`Source::Browsers`] picks a session when several browsers hold one. Defaults to
+    /// [`SelectionStrategy::Newest`].
+    pub fn selection(mut self, selection: SelectionStrategy) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    /// Look for the `session` cookie of the given domain in the browsers instead of the one of
+    /// adventofcode.com, e.g. for a mirror of Advent of Code run by a classroom. Only
+    /// [`Source::Browsers`] and [`Source::Browser`] are affected.
+    pub fn domain(mut self, domain: impl Into<String>) -> Self {
+        let cookie_name = SessionSpec::ADVENT_OF_CODE.cookie_name().to_string();
+        self.spec = Some(SessionSpec::new(domain.into(), cookie_name));
+        self
+    }
+
+    /// Whether the browsers that may prompt for the password of the macOS Keychain, i.e. those
+    /// based on Chromium, are read. If not, [`Source::Browsers`] only reads Firefox, LibreWolf,
+    /// and Safari, and the other browser sources fail with
+    /// [`Error::KeychainPromptNotAllowed`] for them, so that a tool running unattended never pops
+    /// up a window. Allowed by default; no other platform prompts.
+    pub fn allow_keychain_prompt(mut self, allow: bool) -> Self {
+        self.excluded.keychain_prompt = !allow;
+        self
+    }
+
+    /// Never read the cookies of the browser: [`Source::Browsers`] and `Source::Wsl` skip it, and
+    /// the other sources that would read it fail with [`Error::BrowserExcluded`], including
+    /// `Source::Cdp` when Chrome is excluded. Together with a chain
+    /// that only holds the sources you trust, this keeps the library away from the browser
+    /// profiles you don't want it to touch.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use aoc_session::{AocSessionBuilder, Browser};
+    ///
+    /// let session = AocSessionBuilder::new()
+    ///     .env()
+    ///     .browsers()
+    ///     .exclude_browser(Browser::Chrome)
+    ///     .resolve();
+    /// ```
+    pub fn exclude_browser(mut self, browser: Browser) -> Self {
+        if !self.excluded.browsers.contains(&browser) {
+            self.excluded.browsers.push(browser);
+        }
+        self
+    }
+
+    /// Remember the resolved session cookie value on disk for `ttl`, so that later runs skip the
+    /// slow sources, e.g. the browser scan and the OS credential store. The sources before the
+    /// first slow one, e.g. the environment variables and files, are still consulted first, so
+    /// that a value set there overrides the remembered one.
+    ///
+    /// The value is stored in the cache directory, e.g. `~/.cache/aoc-session/session` on
+    /// Linux, and is only readable by the current user on Unix. It's forgotten once the cookie
+    /// expires, or when [`AocSession::validate`] finds that Advent of Code rejects it.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use aoc_session::AocSessionBuilder;
+    ///
+    /// let session = AocSessionBuilder::new()
+    ///     .env()
+    ///     .browsers()
+    ///     .persist(Duration::from_secs(24 * 60 * 60))
+    ///     .resolve();
+    /// ```
+    pub fn persist(mut self, ttl: Duration) -> Self {
+        self.persist = Some(ttl);
+        self
+    }
+
+    /// The chain of sources, in the order they are consulted.
+    pub fn sources(&self) -> &[Source] {
+        &self.sources
+    }
+
+    /// Consult every source of the chain and report what each one holds or why it failed, as
+    /// [`diagnose`](crate::diagnose) does for the chain of [`aoc_session`](crate::aoc_session).
+    /// The cache of [`persist`](Self::persist) isn't consulted.
+    pub fn diagnose(&self) -> DiagnosticsReport {
+        let spec = self.spec.as_ref().unwrap_or(&SessionSpec::ADVENT_OF_CODE);
+        crate::diagnostics::diagnose_chain(&self.sources, spec, self.selection, &self.excluded)
+    }
+
+    /// Consult the sources in order and return the first session cookie value found.
+    ///
+    /// If no source yields a value, the first error that is not merely about the value being
+    /// absent is returned, so that e.g. a broken cookie store is not hidden by an unset
+    /// environment variable. Otherwise, [`Error::NoSessionCookieFound`] is returned.
+    ///
+    /// Failing to read or write the cache of [`persist`](Self::persist) is not an error; the
+    /// sources are consulted as if there were no cache.
+    pub fn resolve(&self) -> Result<AocSession> {
+        self.resolve_with_source().map(|(session, _)| session)
+    }
+
+    /// Like [`resolve`](Self::resolve), but also returns the source the value came from, as
+    /// precisely as known: a value found by [`Source::Browsers`] is reported as coming from the
+    /// [`Source::Browser`] that holds it, one found by [`Source::SessionFiles`] as coming from
+    /// the [`Source::File`], and one remembered by [`persist`](Self::persist) as coming from
+    /// [`Source::Cache`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use aoc_session::AocSessionBuilder;
+    ///
+    /// let (session, source) = AocSessionBuilder::new()
+    ///     .env()
+    ///     .browsers()
+    ///     .resolve_with_source()
+    ///     .unwrap();
+    /// println!("Found {session} in the {source}");
+    /// ```
+    pub fn resolve_with_source(&self) -> Result<(AocSession, Source)> {
+        self.resolve_reporting()
+            .map(|(session, source, _)| (session, source))
+    }
+
+    /// Like [`resolve`](Self::resolve), but also returns the failures of the sources that were
+    /// consulted before the value was found, including the browsers that couldn't be read while
+    /// another one held the session cookie, so that they can be reported without failing.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use aoc_session::AocSessionBuilder;
+    ///
+    /// let (session, warnings) = AocSessionBuilder::new()
+    ///     .browsers()
+    ///     .resolve_with_warnings()
+    ///     .unwrap();
+    /// for warning in warnings {
+    ///     eprintln!("warning: {warning}");
+    /// }
+    /// ```
+    pub fn resolve_with_warnings(&self) -> Result<(AocSession, Vec<SourceWarning>)> {
+        self.resolve_reporting()
+            .map(|(session, _, warnings)| (session, warnings))
+    }
+
+    fn resolve_reporting(&self) -> Result<(AocSession, Source, Vec<SourceWarning>)> {
+        let spec = self.spec.as_ref().unwrap_or(&SessionSpec::ADVENT_OF_CODE);
+        let cache = self
+            .persist
+            .and_then(|ttl| Some((SessionCache::for_domain(spec.domain())?, ttl)));
+        let mut warnings = Vec::new();
+        let (session, source) = self.resolve_sources(cache.as_ref(), &mut warnings)?;
+        if let Some((cache, _)) = &cache {
+            if source != Source::Cache {
+                let _ = cache.store(&session);
+            }
+        }
+        Ok((session, source, warnings))
+    }
+
+    /// Consult the sources in order, adding the failures to `warnings`. If no source yields a
+    /// value, the first failure is returned instead.
+    ///
+    /// The value remembered in `cache` is tried right before the first slow source, so that it
+    /// stands in for e.g. the browser scan but not for the explicit sources before it.
+    fn resolve_sources(
+        &self,
+        mut cache: Option<&(SessionCache, Duration)>,
+        warnings: &mut Vec<SourceWarning>,
+    ) -> Result<(AocSession, Source)> {
+        let spec = self.spec.as_ref().unwrap_or(&SessionSpec::ADVENT_OF_CODE);
+        for source in &self.sources {
+            if source.is_slow() {
+                if let Some((cache, ttl)) = cache.take() {
+                    if let Ok(Some(session)) = cache.load(*ttl) {
+                        return Ok((session, Source::Cache));
+                    }
+                }
+            }
+            match source.locate(spec, self.selection, &self.excluded, warnings) {
+                Ok(found) => return Ok(found),
+                Err(e) if e.is_absence() => {}
+                Err(error) => warnings.push(SourceWarning {
+                    source: source.clone(),
+                    error,
+                }),
+            }
+        }
+        Err(match warnings.drain(..).next() {
+            Some(warning) => warning.error,
+            None => Error::NoSessionCookieFound,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_source_with_a_value_wins() {
+        let dir = std::env::temp_dir().join("aoc-session-builder-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let empty = dir.join("empty");
+        let full = dir.join("full");
+        std::fs::write(&empty, "\n").unwrap();
+        std::fs::write(&full, "25a16c74\n").unwrap();
+
+        let session = AocSessionBuilder::new()
+            .file(dir.join("missing"))
+            .file(empty)
+            .file(&full)
+            .resolve_with_source()
+            .unwrap();
+        assert_eq!(session.0.as_str(), "25a16c74");
+        assert_eq!(session.1, Source::File(full));
+    }
+
+    #[test]
+    fn failures_before_the_value_are_warnings() {
+        let dir = std::env::temp_dir().join("aoc-session-warnings-test");
+        std::fs::create_dir_all(dir.join("directory")).unwrap();
+        std::fs::write(dir.join("full"), "25a16c74\n").unwrap();
+
+        let builder = AocSessionBuilder::new()
+            .file(dir.join("missing"))
+            .file(dir.join("directory"));
+        let (session, warnings) = builder
+            .clone()
+            .file(dir.join("full"))
+            .resolve_with_warnings()
+            .unwrap();
+        assert_eq!(session.as_str(), "25a16c74");
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].source, Source::File(dir.join("directory")));
+        assert!(matches!(builder.resolve(), Err(Error::Io { .. })));
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn keychain_prompts_can_be_forbidden() {
+        let result = AocSessionBuilder::new()
+            .source(Source::Browser(Browser::Chrome))
+            .allow_keychain_prompt(false)
+            .resolve();
+        assert!(matches!(
+            result,
+            Err(Error::KeychainPromptNotAllowed(Browser::Chrome))
+        ));
+    }
+
+    #[test]
+    fn excluded_browsers_are_not_read() {
+        let dir = std::env::temp_dir().join("aoc-session-exclusions-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let fake = AocSession::fake(1);
+        std::fs::write(dir.join("full"), fake.expose_secret()).unwrap();
+
+        let builder = AocSessionBuilder::new()
+            .browser(Browser::Chrome)
+            .firefox_profile(FirefoxProfile::Named("work".to_string()))
+            .exclude_browser(Browser::Chrome)
+            .exclude_browser(Browser::Firefox);
+        assert!(matches!(
+            builder.resolve(),
+            Err(Error::BrowserExcluded(Browser::Chrome))
+        ));
+        let (session, warnings) = builder
+            .file(dir.join("full"))
+            .resolve_with_warnings()
+            .unwrap();
+        assert_eq!(session.expose_secret(), fake.expose_secret());
+        let firefox = Source::FirefoxProfile(FirefoxProfile::Named("work".to_string()));
+        let warning = warnings.iter().find(|w| w.source == firefox).unwrap();
+        assert!(matches!(
+            warning.error,
+            Error::BrowserExcluded(Browser::Firefox)
+        ));
+    }
+
+    #[cfg(feature = "cdp")]
+    #[test]
+    fn excluding_chrome_excludes_devtools() {
+        let result = AocSessionBuilder::new()
+            .cdp(1)
+            .exclude_browser(Browser::Chrome)
+            .resolve();
+        assert!(matches!(
+            result,
+            Err(Error::BrowserExcluded(Browser::Chrome))
+        ));
+    }
+
+    #[test]
+    fn cache_only_stands_in_for_slow_sources() {
+        let dir = std::env::temp_dir().join("aoc-session-persist-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("full"), "25a16c74\n").unwrap();
+        let cache = SessionCache::new(dir.join("session"));
+        cache
+            .store(&AocSession::unchecked("cafe".to_string()))
+            .unwrap();
+        let cache = (cache, Duration::from_secs(60));
+
+        let builder = AocSessionBuilder::new()
+            .file(dir.join("full"))
+            .browser(Browser::Chrome);
+        let (session, source) = builder
+            .resolve_sources(Some(&cache), &mut Vec::new())
+            .unwrap();
+        assert_eq!(session.as_str(), "25a16c74");
+        assert_eq!(source, Source::File(dir.join("full")));
+
+        let builder = AocSessionBuilder::new()
+            .file(dir.join("missing"))
+            .browser(Browser::Chrome);
+        let (session, source) = builder
+            .resolve_sources(Some(&cache), &mut Vec::new())
+            .unwrap();
+        assert_eq!(session.as_str(), "cafe");
+        assert_eq!(source, Source::Cache);
+    }
+
+    #[test]
+    fn browsers_only_keeps_the_exclusions() {
+        let builder = AocSessionBuilder::new()
+            .env()
+            .exclude_browser(Browser::Chrome)
+            .persist(Duration::from_secs(60))
+            .browsers_only();
+        assert_eq!(builder.sources(), [Source::Browsers]);
+        assert_eq!(builder.persist, None);
+        assert!(builder.exclusions().check(Browser::Chrome).is_err());
+    }
+
+    #[test]
+    fn empty_chain_finds_nothing() {
+        assert!(matches!(
+            AocSessionBuilder::new().resolve(),
+            Err(Error::NoSessionCookieFound)
+        ));
+    }
+}
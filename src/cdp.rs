@@ -0,0 +1,329 @@
+//! The session cookie read live from a running Chrome through the Chrome DevTools Protocol, which
+//! hands out cookies already decrypted, even those under app-bound encryption that no other
+//! program can decrypt from the disk.
+//!
+//! The protocol runs over a WebSocket, of which only the little that a local, short exchange needs
+//! is implemented here: a text message out and text messages back.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpStream};
+use std::time::{Duration, SystemTime};
+
+use serde_json::Value;
+
+use crate::{AocSession, Error, Result, SessionSpec};
+
+/// The port Chrome listens on when started with `--remote-debugging-port` and no value.
+pub const DEFAULT_CDP_PORT: u16 = 9222;
+
+/// How long to wait for Chrome, which answers at once if it listens at all.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Get the session cookie for Advent of Code from a running Chrome, or another browser based on
+/// Chromium, started with `--remote-debugging-port=<port>`, through the Chrome DevTools Protocol.
+///
+/// Since Chrome 136, the port is only opened for a profile outside of the default data
+/// directory, e.g. with `--user-data-dir=$HOME/.config/chrome-aoc`; log in to Advent of Code in
+/// that profile. Only `127.0.0.1` is contacted.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoc_session::{aoc_session_from_cdp, DEFAULT_CDP_PORT};
+///
+/// let session = aoc_session_from_cdp(DEFAULT_CDP_PORT).unwrap();
+/// println!("My session ID: {session}");
+/// ```
+pub fn aoc_session_from_cdp(port: u16) -> Result<AocSession> {
+    from_cdp(port, &SessionSpec::ADVENT_OF_CODE)
+}
+
+pub(crate) fn from_cdp(port: u16, spec: &SessionSpec) -> Result<AocSession> {
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let version = http_get(addr, "/json/version")?;
+    let url = version["webSocketDebuggerUrl"]
+        .as_str()
+        .ok_or_else(|| Error::Cdp("Chrome announced no WebSocket".to_string()))?;
+    // The host in the URL is whatever Chrome was asked for; only the path is taken from it.
+    let path = url
+        .strip_prefix("ws://")
+        .and_then(|rest| rest.find('/').map(|slash| &rest[slash..]))
+        .ok_or_else(|| Error::Cdp(format!("unexpected WebSocket URL {url:?}")))?;
+    let mut socket = WebSocket::connect(addr, path)?;
+    socket.send(r#"{"id":1,"method":"Storage.getCookies"}"#)?;
+    let reply = loop {
+        let message: Value = serde_json::from_str(&socket.receive()?)
+            .map_err(|e| Error::Cdp(format!("unexpected message: {e}")))?;
+        if message["id"] == 1 {
+            break message;
+        }
+    };
+    if let Some(error) = reply["error"]["message"].as_str() {
+        return Err(Error::Cdp(format!("Storage.getCookies failed: {error}")));
+    }
+    find_cookie(&reply["result"]["cookies"], spec)
+}
+
+/// The cookie described by `spec` among the cookies listed by `Storage.getCookies`.
+fn find_cookie(cookies: &Value, spec: &SessionSpec) -> Result<AocSession> {
+    let cookie = cookies
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|cookie| {
+            cookie["name"] == spec.cookie_name()
+                && cookie["domain"]
+                    .as_str()
+                    .is_some_and(|domain| spec.matches_host(domain))
+        })
+        .ok_or(Error::NoSessionCookieFound)?;
+    let value = cookie["value"].as_str().unwrap_or_default();
+    if value.is_empty() {
+        return Err(Error::NoSessionCookieFound);
+    }
+    // Session cookies have no expiry, which is given as -1.
+    let expires = cookie["expires"]
+        .as_f64()
+        .filter(|&secs| secs > 0.0)
+        .map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs_f64(secs));
+    Ok(AocSession::unchecked(value.to_string()).with_expiry(expires))
+}
+
+fn connect(addr: SocketAddr) -> Result<TcpStream> {
+    let stream = TcpStream::connect_timeout(&addr, TIMEOUT).map_err(|e| {
+        Error::Cdp(format!(
+            "nothing listens on port {}; start Chrome with --remote-debugging-port={}: {e}",
+            addr.port(),
+            addr.port()
+        ))
+    })?;
+    stream
+        .set_read_timeout(Some(TIMEOUT))
+        .and_then(|()| stream.set_write_timeout(Some(TIMEOUT)))
+        .map_err(io)?;
+    Ok(stream)
+}
+
+/// The JSON body of the answer to a GET request.
+fn http_get(addr: SocketAddr, path: &str) -> Result<Value> {
+    let mut stream = connect(addr)?;
+    write!(
+        stream,
+        "GET {path} HTTP/1.1\r\nHost: {addr}\r\nConnection: close\r\n\r\n"
+    )
+    .map_err(io)?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).map_err(io)?;
+    let response = String::from_utf8_lossy(&response);
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| Error::Cdp("truncated HTTP response".to_string()))?;
+    if head.split(' ').nth(1) != Some("200") {
+        let status = head.lines().next().unwrap_or_default();
+        return Err(Error::Cdp(format!("GET {path} answered {status}")));
+    }
+    serde_json::from_str(body).map_err(|e| Error::Cdp(format!("unexpected answer: {e}")))
+}
+
+fn io(e: std::io::Error) -> Error {
+    Error::Cdp(e.to_string())
+}
+
+/// The client side of a WebSocket, per RFC 6455.
+struct WebSocket {
+    stream: BufReader<TcpStream>,
+}
+
+impl WebSocket {
+    const TEXT: u8 = 0x1;
+    const CLOSE: u8 = 0x8;
+    const PING: u8 = 0x9;
+    const PONG: u8 = 0xa;
+
+    fn connect(addr: SocketAddr, path: &str) -> Result<WebSocket> {
+        let mut stream = connect(addr)?;
+        let key = base64(&random_bytes::<16>());
+        write!(
+            stream,
+            "GET {path} HTTP/1.1\r\nHost: {addr}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+             Sec-WebSocket-Key: {key}\r\nSec-WebSocket-Version: 13\r\n\r\n"
+        )
+        .map_err(io)?;
+        let mut stream = BufReader::new(stream);
+        let mut status = String::new();
+        stream.read_line(&mut status).map_err(io)?;
+        if status.split(' ').nth(1) != Some("101") {
+            return Err(Error::Cdp(format!(
+                "the WebSocket was refused: {}",
+                status.trim()
+            )));
+        }
+        // The rest of the head, up to the blank line; the frames follow it.
+        loop {
+            let mut line = String::new();
+            if stream.read_line(&mut line).map_err(io)? == 0 || line.trim().is_empty() {
+                break;
+            }
+        }
+        Ok(WebSocket { stream })
+    }
+
+    fn send(&mut self, text: &str) -> Result<()> {
+        self.send_frame(Self::TEXT, text.as_bytes())
+    }
+
+    fn send_frame(&mut self, opcode: u8, payload: &[u8]) -> Result<()> {
+        let frame = frame(opcode, payload, random_bytes::<4>());
+        self.stream.get_mut().write_all(&frame).map_err(io)
+    }
+
+    /// The next text message, answering pings on the way.
+    fn receive(&mut self) -> Result<String> {
+        let mut message = Vec::new();
+        loop {
+            let (fin, opcode, payload) = self.receive_frame()?;
+            match opcode {
+                Self::CLOSE => return Err(Error::Cdp("Chrome closed the WebSocket".to_string())),
+                Self::PING => self.send_frame(Self::PONG, &payload)?,
+                Self::PONG => {}
+                _ => {
+                    message.extend_from_slice(&payload);
+                    if fin {
+                        return String::from_utf8(message)
+                            .map_err(|_| Error::Cdp("a message isn't text".to_string()));
+                    }
+                }
+            }
+        }
+    }
+
+    fn receive_frame(&mut self) -> Result<(bool, u8, Vec<u8>)> {
+        let mut head = [0; 2];
+        self.stream.read_exact(&mut head).map_err(io)?;
+        let fin = head[0] & 0x80 != 0;
+        let opcode = head[0] & 0x0f;
+        let len = match head[1] & 0x7f {
+            126 => {
+                let mut len = [0; 2];
+                self.stream.read_exact(&mut len).map_err(io)?;
+                u64::from(u16::from_be_bytes(len))
+            }
+            127 => {
+                let mut len = [0; 8];
+                self.stream.read_exact(&mut len).map_err(io)?;
+                u64::from_be_bytes(len)
+            }
+            len => u64::from(len),
+        };
+        // Servers don't mask their frames.
+        let mut payload = Vec::new();
+        (&mut self.stream)
+            .take(len)
+            .read_to_end(&mut payload)
+            .map_err(io)?;
+        if payload.len() as u64 != len {
+            return Err(Error::Cdp("truncated WebSocket frame".to_string()));
+        }
+        Ok((fin, opcode, payload))
+    }
+}
+
+/// A final frame from the client, which must be masked.
+fn frame(opcode: u8, payload: &[u8], mask: [u8; 4]) -> Vec<u8> {
+    let mut frame = vec![0x80 | opcode];
+    match payload.len() {
+        len @ 0..=125 => frame.push(0x80 | len as u8),
+        len @ 126..=0xffff => {
+            frame.push(0x80 | 126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(0x80 | 127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(&mask);
+    frame.extend(payload.iter().zip(mask.iter().cycle()).map(|(b, m)| b ^ m));
+    frame
+}
+
+/// Bytes that only need to be unpredictable to proxies, taken from the random keys of the
+/// standard library's hasher.
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0; N];
+    for chunk in bytes.chunks_mut(8) {
+        let random = RandomState::new().build_hasher().finish().to_le_bytes();
+        chunk.copy_from_slice(&random[..chunk.len()]);
+    }
+    bytes
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::new();
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | u32::from(b) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn cookie_is_found() {
+        let value = "53616c7465645f5f".repeat(8);
+        let cookies = json!([
+            {"name": "session", "value": "other", "domain": "example.com", "expires": -1},
+            {"name": "session", "value": value, "domain": ".adventofcode.com", "expires": 1.7e9},
+        ]);
+        let session = find_cookie(&cookies, &SessionSpec::ADVENT_OF_CODE).unwrap();
+        assert_eq!(session.expose_secret(), value);
+        assert_eq!(
+            session.expires_at(),
+            Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000))
+        );
+        assert!(matches!(
+            find_cookie(&json!([]), &SessionSpec::ADVENT_OF_CODE),
+            Err(Error::NoSessionCookieFound)
+        ));
+    }
+
+    #[test]
+    fn frames_are_masked() {
+        let masked = frame(WebSocket::TEXT, b"Hello", [0x37, 0xfa, 0x21, 0x3d]);
+        // The example of RFC 6455, section 5.7.
+        assert_eq!(
+            masked,
+            [0x81, 0x85, 0x37, 0xfa, 0x21, 0x3d, 0x7f, 0x9f, 0x4d, 0x51, 0x58]
+        );
+        assert_eq!(
+            frame(WebSocket::TEXT, &[0; 200], [0; 4])[1..4],
+            [0xfe, 0, 200]
+        );
+    }
+
+    #[test]
+    fn base64_encoding() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+        assert_eq!(base64(&random_bytes::<16>()).len(), 24);
+    }
+}
@@ -0,0 +1,128 @@
+//! Continuous integration services, where no browser is logged in to Advent of Code and the
+//! session cookie value has to come from a secret.
+
+/// A continuous integration service, with how to hand it the session cookie value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Ci {
+    pub(crate) name: &'static str,
+    pub(crate) hint: &'static str,
+}
+
+/// The services, each with the environment variable it sets in every job. The last one is the
+/// variable most services set, for the others.
+const SERVICES: [(&str, Ci); 9] = [
+    (
+        "GITHUB_ACTIONS",
+        Ci {
+            name: "GitHub Actions",
+            hint: "add a repository secret named AOC_SESSION and pass it to the step with \
+                   `env: AOC_SESSION: ${{ secrets.AOC_SESSION }}`",
+        },
+    ),
+    (
+        "GITLAB_CI",
+        Ci {
+            name: "GitLab CI",
+            hint: "add a masked CI/CD variable named AOC_SESSION under Settings > CI/CD > \
+                   Variables",
+        },
+    ),
+    (
+        "CIRCLECI",
+        Ci {
+            name: "CircleCI",
+            hint: "add an environment variable named AOC_SESSION to the project settings or to \
+                   a context",
+        },
+    ),
+    (
+        "TRAVIS",
+        Ci {
+            name: "Travis CI",
+            hint: "add an environment variable named AOC_SESSION to the repository settings",
+        },
+    ),
+    (
+        "TF_BUILD",
+        Ci {
+            name: "Azure Pipelines",
+            hint: "add a secret variable named AOC_SESSION and map it into the step with \
+                   `env: AOC_SESSION: $(AOC_SESSION)`",
+        },
+    ),
+    (
+        "BITBUCKET_BUILD_NUMBER",
+        Ci {
+            name: "Bitbucket Pipelines",
+            hint: "add a secured repository variable named AOC_SESSION",
+        },
+    ),
+    (
+        "JENKINS_URL",
+        Ci {
+            name: "Jenkins",
+            hint: "bind a secret text credential to AOC_SESSION with `withCredentials`",
+        },
+    ),
+    (
+        "BUILDKITE",
+        Ci {
+            name: "Buildkite",
+            hint: "expose a secret as the AOC_SESSION environment variable of the step",
+        },
+    ),
+    (
+        "CI",
+        Ci {
+            name: "a CI service",
+            hint: "set the AOC_SESSION environment variable from a secret of the service, or \
+                   AOC_SESSION_FILE to the file it's mounted at",
+        },
+    ),
+];
+
+/// The continuous integration service this program runs on, if any. Self-hosted runners of
+/// GitHub Actions don't count, since a browser may well be logged in on them.
+pub(crate) fn current() -> Option<Ci> {
+    detect(|name| std::env::var(name).ok())
+}
+
+fn detect(var: impl Fn(&str) -> Option<String>) -> Option<Ci> {
+    if var("RUNNER_ENVIRONMENT").as_deref() == Some("self-hosted") {
+        return None;
+    }
+    SERVICES.iter().find_map(|(name, ci)| {
+        let value = var(name)?;
+        let value = value.trim();
+        let set = !value.is_empty() && value != "0" && !value.eq_ignore_ascii_case("false");
+        set.then_some(*ci)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn services_are_detected() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        let ci = detect(env(&[("CI", "true"), ("GITHUB_ACTIONS", "true")])).unwrap();
+        assert_eq!(ci.name, "GitHub Actions");
+        assert_eq!(detect(env(&[("CI", "1")])).unwrap().name, "a CI service");
+        assert_eq!(detect(env(&[("CI", "false")])), None);
+        assert_eq!(
+            detect(env(&[
+                ("GITHUB_ACTIONS", "true"),
+                ("RUNNER_ENVIRONMENT", "self-hosted")
+            ])),
+            None
+        );
+        assert_eq!(detect(env(&[])), None);
+    }
+}
@@ -0,0 +1,22 @@
+use aoc_session::{agent_socket_path, Result};
+use serde_json::json;
+
+/// Run the agent until `aoc-session agent stop`, or stop the running one if `stop`. With `json`,
+/// `{"listening_on": ...}` or `{"stopped": true}` is printed.
+pub(crate) fn agent(stop: bool, json: bool) -> Result<()> {
+    if stop {
+        aoc_session::stop_agent()?;
+        if json {
+            println!("{}", json!({ "stopped": true }));
+        }
+        return Ok(());
+    }
+    if let Some(path) = agent_socket_path() {
+        if json {
+            println!("{}", json!({ "listening_on": path }));
+        } else {
+            eprintln!("Listening on {}", path.display());
+        }
+    }
+    aoc_session::run_agent()
+}
@@ -0,0 +1,62 @@
+use aoc_session::{compare_sessions, AccountStatus, ComparedSession, Result};
+use serde_json::{json, Value};
+
+use super::{format_time, unix_secs};
+
+/// Check the session cookie of every browser against Advent of Code, show which account each
+/// belongs to, and warn if the browsers are logged in to different accounts.
+///
+/// Fails if no browser holds a session cookie.
+pub(crate) fn compare(json: bool) -> Result<()> {
+    let comparison = compare_sessions()?;
+    if json {
+        let sessions: Vec<_> = comparison.sessions.iter().map(compared_json).collect();
+        let json = json!({
+            "sessions": sessions,
+            "accounts": comparison.accounts(),
+            "mixed_accounts": comparison.mixes_accounts(),
+        });
+        println!("{json}");
+        return Ok(());
+    }
+    for compared in &comparison.sessions {
+        let found = &compared.found;
+        let account = match &compared.status {
+            AccountStatus::Account(name) => format!("account {name}"),
+            AccountStatus::Rejected => "rejected".to_string(),
+            AccountStatus::Unknown(e) => format!("unknown: {e}"),
+        };
+        println!("{:<10} {} {account}", found.browser.name(), found.session);
+        println!("{:<10} profile: {}", "", found.profile.display());
+        if let Some(expires) = found.expires {
+            println!("{:<10} expires: {}", "", format_time(expires));
+        }
+    }
+    if comparison.mixes_accounts() {
+        eprintln!(
+            "warning: the browsers are logged in to different accounts ({}); make sure the \
+             session in use belongs to the right one",
+            comparison.accounts().join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// The session as a JSON object, whose `status` is one of `valid`, `rejected` and `unknown`.
+fn compared_json(compared: &ComparedSession) -> Value {
+    let found = &compared.found;
+    let (status, account, error) = match &compared.status {
+        AccountStatus::Account(name) => ("valid", Some(name), None),
+        AccountStatus::Rejected => ("rejected", None, None),
+        AccountStatus::Unknown(e) => ("unknown", None, Some(e)),
+    };
+    json!({
+        "browser": found.browser.name(),
+        "profile": found.profile,
+        "session": found.session.to_string(),
+        "expires": found.expires.map(unix_secs),
+        "status": status,
+        "account": account,
+        "error": error,
+    })
+}
@@ -0,0 +1,53 @@
+use std::str::FromStr;
+
+/// The shells that `aoc-session completions` prints a completion script for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+}
+
+impl FromStr for Shell {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        Shell::ALL
+            .into_iter()
+            .find(|shell| shell.name() == s)
+            .ok_or_else(|| {
+                format!("invalid shell '{s}', expected one of bash, zsh, fish, powershell")
+            })
+    }
+}
+
+impl Shell {
+    pub(crate) const ALL: [Shell; 4] = [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::Powershell];
+
+    /// The name of the shell on the command line.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Fish => "fish",
+            Shell::Powershell => "powershell",
+        }
+    }
+
+    /// The completion script, to be sourced by the shell, or saved as `_aoc-session` in
+    /// `$fpath` for zsh.
+    pub(crate) fn script(self) -> &'static str {
+        match self {
+            Shell::Bash => include_str!("completions/aoc-session.bash"),
+            Shell::Zsh => include_str!("completions/_aoc-session"),
+            Shell::Fish => include_str!("completions/aoc-session.fish"),
+            Shell::Powershell => include_str!("completions/aoc-session.ps1"),
+        }
+    }
+}
+
+/// Print the completion script of the shell.
+pub(crate) fn completions(shell: Shell) {
+    print!("{}", shell.script());
+}
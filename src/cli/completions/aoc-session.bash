@@ -0,0 +1,40 @@
+_aoc_session() {
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    local prev="${COMP_WORDS[COMP_CWORD-1]}"
+    case "$prev" in
+        -f | --format)
+            COMPREPLY=($(compgen -W "env json cookies-txt curl-header gh-secret github-actions" -- "$cur"))
+            return
+            ;;
+        -o | --output)
+            COMPREPLY=($(compgen -f -- "$cur"))
+            return
+            ;;
+        --curl)
+            return
+            ;;
+        -p | --profile)
+            COMPREPLY=($(compgen -W "$(aoc-session profiles 2>/dev/null | cut -c3-)" -- "$cur"))
+            return
+            ;;
+        agent)
+            COMPREPLY=($(compgen -W "stop" -- "$cur"))
+            return
+            ;;
+        git-credential)
+            COMPREPLY=($(compgen -W "get store erase" -- "$cur"))
+            return
+            ;;
+        completions)
+            COMPREPLY=($(compgen -W "bash zsh fish powershell" -- "$cur"))
+            return
+            ;;
+    esac
+    if [[ "$cur" == -* ]]; then
+        COMPREPLY=($(compgen -W "-c --cookie-header --cookies-txt --curl -f --format --today -o --output -p --profile --json -q --quiet -h --help -V --version" -- "$cur"))
+    elif [[ "$COMP_CWORD" -eq 1 ]]; then
+        COMPREPLY=($(compgen -W "status doctor compare export set clear input submit login agent profiles git-credential native-host completions" -- "$cur"))
+    fi
+}
+
+complete -F _aoc_session aoc-session
@@ -0,0 +1,31 @@
+complete -c aoc-session -f
+
+complete -c aoc-session -n __fish_use_subcommand -a status -d 'Show where the session cookie comes from and whether it is valid'
+complete -c aoc-session -n __fish_use_subcommand -a doctor -d 'Check every source, including each browser'
+complete -c aoc-session -n __fish_use_subcommand -a compare -d 'Show which account the session cookie of each browser belongs to'
+complete -c aoc-session -n __fish_use_subcommand -a export -d 'Print the session cookie in the format given by --format'
+complete -c aoc-session -n __fish_use_subcommand -a set -d 'Store the session cookie value'
+complete -c aoc-session -n __fish_use_subcommand -a clear -d 'Remove the session cookie value stored by set'
+complete -c aoc-session -n __fish_use_subcommand -a input -d 'Print the puzzle input of a day'
+complete -c aoc-session -n __fish_use_subcommand -a submit -d 'Submit an answer'
+complete -c aoc-session -n __fish_use_subcommand -a login -d 'Log in with the browser and wait for the session cookie'
+complete -c aoc-session -n __fish_use_subcommand -a agent -d 'Keep the session cookie value in memory for other runs'
+complete -c aoc-session -n __fish_use_subcommand -a profiles -d 'List the account profiles'
+complete -c aoc-session -n __fish_use_subcommand -a git-credential -d 'Act as a git credential helper for adventofcode.com'
+complete -c aoc-session -n __fish_use_subcommand -a native-host -d 'Store the session cookie values pushed by a browser extension over native messaging'
+complete -c aoc-session -n __fish_use_subcommand -a completions -d 'Print the completion script of a shell'
+complete -c aoc-session -n '__fish_seen_subcommand_from completions' -a 'bash zsh fish powershell'
+complete -c aoc-session -n '__fish_seen_subcommand_from agent' -a stop -d 'Stop the running agent'
+complete -c aoc-session -n '__fish_seen_subcommand_from git-credential' -a 'get store erase'
+
+complete -c aoc-session -s c -l cookie-header -d 'Print the value as session=<value>'
+complete -c aoc-session -l cookies-txt -d 'Print the session cookie in the Netscape cookies.txt format'
+complete -c aoc-session -l curl -x -d 'Print a curl command that fetches the URL with the session cookie'
+complete -c aoc-session -s f -l format -x -a 'env json cookies-txt curl-header gh-secret github-actions' -d 'The format of export'
+complete -c aoc-session -l today -d 'Pick the puzzle that unlocked last'
+complete -c aoc-session -s o -l output -r -F -d 'Write the puzzle input to the file'
+complete -c aoc-session -s p -l profile -x -a '(aoc-session profiles 2>/dev/null | string sub -s 3)' -d 'Use the named account profile'
+complete -c aoc-session -l json -d 'Print the output, and errors, as JSON'
+complete -c aoc-session -s q -l quiet -d 'Do not print error messages'
+complete -c aoc-session -s h -l help -d 'Print help'
+complete -c aoc-session -s V -l version -d 'Print version'
@@ -0,0 +1,28 @@
+Register-ArgumentCompleter -Native -CommandName aoc-session -ScriptBlock {
+    param($wordToComplete, $commandAst, $cursorPosition)
+    $words = @($commandAst.CommandElements |
+        Where-Object { $_.Extent.EndOffset -lt $cursorPosition } |
+        ForEach-Object { $_.ToString() })
+    $previous = $words[-1]
+    $candidates = if ($previous -in '-f', '--format') {
+        'env', 'json', 'cookies-txt', 'curl-header', 'gh-secret', 'github-actions'
+    } elseif ($previous -eq 'completions') {
+        'bash', 'zsh', 'fish', 'powershell'
+    } elseif ($previous -eq 'agent') {
+        'stop'
+    } elseif ($previous -eq 'git-credential') {
+        'get', 'store', 'erase'
+    } elseif ($previous -in '-p', '--profile') {
+        aoc-session profiles 2>$null | ForEach-Object { $_.Substring(2) }
+    } elseif ($wordToComplete -like '-*') {
+        '-c', '--cookie-header', '--cookies-txt', '--curl', '-f', '--format', '--today',
+        '-o', '--output', '-p', '--profile', '--json', '-q', '--quiet', '-h', '--help', '-V', '--version'
+    } elseif ($words.Count -eq 1) {
+        'status', 'doctor', 'compare', 'export', 'set', 'clear', 'input', 'submit', 'login', 'agent', 'profiles', 'git-credential', 'native-host', 'completions'
+    } else {
+        @()
+    }
+    $candidates | Where-Object { $_ -like "$wordToComplete*" } | ForEach-Object {
+        [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_)
+    }
+}
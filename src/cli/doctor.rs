@@ -0,0 +1,47 @@
+use aoc_session::{diagnose, Error, Result, SourceStatus};
+
+use super::format_time;
+
+/// Report, for every source of the chain, whether it's available and holds a session cookie,
+/// along with hints to fix the failures. [`Source::Browsers`](aoc_session::Source::Browsers) is
+/// reported browser by browser.
+///
+/// Fails if no source holds a session cookie.
+pub(crate) fn doctor(json: bool) -> Result<()> {
+    let report = diagnose();
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string(&report).expect("reports serialize")
+        );
+    } else {
+        for diagnosis in &report.sources {
+            let status = match &diagnosis.status {
+                SourceStatus::Unsupported => "not supported on this platform or build".to_string(),
+                SourceStatus::Unavailable(e) => format!("not found: {}", e.message),
+                SourceStatus::Failed(e) => format!("failed: {}", e.message),
+                SourceStatus::NoSession => "no session cookie".to_string(),
+                SourceStatus::Session { expires: None } => "session cookie found".to_string(),
+                SourceStatus::Session {
+                    expires: Some(expires),
+                } => format!("session cookie found, expires {}", format_time(*expires)),
+            };
+            println!(
+                "{}: {status} ({} ms)",
+                diagnosis.source,
+                diagnosis.elapsed.as_millis()
+            );
+            if let Some(path) = &diagnosis.db_path {
+                println!("  database: {}", path.display());
+            }
+            if let Some(hint) = &diagnosis.hint {
+                println!("  hint: {hint}");
+            }
+        }
+    }
+    if report.has_session() {
+        Ok(())
+    } else {
+        Err(Error::NoSessionCookieFound)
+    }
+}
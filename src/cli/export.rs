@@ -0,0 +1,171 @@
+use std::ffi::OsString;
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use aoc_session::{aoc_session, AocSession, Error, Result, ENV_VARS};
+use serde_json::json;
+
+use super::unix_secs;
+
+/// The formats of `aoc-session export`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum Format {
+    /// `AOC_SESSION=<value>`, for `.env` files.
+    #[default]
+    Env,
+    /// `{"session": "<value>", "expires": <seconds since the Unix epoch or null>}`.
+    Json,
+    /// The Netscape `cookies.txt` format.
+    CookiesTxt,
+    /// `Cookie: session=<value>`, for `curl --header @file`.
+    CurlHeader,
+    /// The bare value without a trailing newline, for `gh secret set AOC_SESSION`, which stores
+    /// its standard input verbatim.
+    GhSecret,
+    /// `::add-mask::<value>`, which hides the value in the logs of GitHub Actions, while
+    /// `AOC_SESSION=<value>` is appended to `$GITHUB_ENV` and `session=<value>` to
+    /// `$GITHUB_OUTPUT`, for the later steps of the job.
+    GithubActions,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, String> {
+        match s {
+            "env" => Ok(Format::Env),
+            "json" => Ok(Format::Json),
+            "cookies-txt" => Ok(Format::CookiesTxt),
+            "curl-header" => Ok(Format::CurlHeader),
+            "gh-secret" => Ok(Format::GhSecret),
+            "github-actions" => Ok(Format::GithubActions),
+            _ => Err(format!(
+                "invalid format '{s}', expected one of env, json, cookies-txt, curl-header, \
+                 gh-secret, github-actions"
+            )),
+        }
+    }
+}
+
+/// Print the session cookie in the given format.
+pub(crate) fn export(format: Format) -> Result<()> {
+    let session = aoc_session()?;
+    print!("{}", render(&session, format));
+    if format == Format::GithubActions {
+        // The mask has to reach the runner before the value can show up in a log.
+        let _ = std::io::stdout().flush();
+        to_github_files(&session, |name| std::env::var_os(name))?;
+    }
+    Ok(())
+}
+
+/// Append the session cookie value to the files that GitHub Actions reads the environment
+/// variables and the outputs of a step from, whichever are set.
+fn to_github_files(session: &AocSession, var: impl Fn(&str) -> Option<OsString>) -> Result<()> {
+    let files = [
+        (
+            "GITHUB_ENV",
+            format!("{}={}\n", ENV_VARS[0], session.expose_secret()),
+        ),
+        (
+            "GITHUB_OUTPUT",
+            format!("session={}\n", session.expose_secret()),
+        ),
+    ];
+    let mut found = false;
+    for (name, line) in files {
+        let Some(path) = var(name).filter(|path| !path.is_empty()).map(PathBuf::from) else {
+            continue;
+        };
+        found = true;
+        std::fs::OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|source| Error::Io { path, source })?;
+    }
+    if found {
+        Ok(())
+    } else {
+        Err(Error::Io {
+            path: "$GITHUB_ENV".into(),
+            source: std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "not set; run this in a step of GitHub Actions",
+            ),
+        })
+    }
+}
+
+fn render(session: &AocSession, format: Format) -> String {
+    match format {
+        Format::Env => format!("{}={}\n", ENV_VARS[0], session.expose_secret()),
+        Format::Json => {
+            let expires = session.expires_at().map(unix_secs);
+            let json = json!({"session": session.expose_secret(), "expires": expires});
+            format!("{json}\n")
+        }
+        Format::CookiesTxt => session.to_cookies_txt(),
+        Format::CurlHeader => format!("{}\n", session.to_cookie_header()),
+        Format::GhSecret => session.expose_secret().to_string(),
+        Format::GithubActions => format!("::add-mask::{}\n", session.expose_secret()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats() {
+        let session: AocSession = "25a16c7465645f5f286128b604b18e3d".parse().unwrap();
+        assert_eq!(
+            render(&session, Format::Env),
+            "AOC_SESSION=25a16c7465645f5f286128b604b18e3d\n"
+        );
+        assert_eq!(
+            render(&session, Format::Json),
+            "{\"expires\":null,\"session\":\"25a16c7465645f5f286128b604b18e3d\"}\n"
+        );
+        assert_eq!(
+            render(&session, Format::CurlHeader),
+            "Cookie: session=25a16c7465645f5f286128b604b18e3d\n"
+        );
+        assert_eq!(
+            render(&session, Format::GhSecret),
+            "25a16c7465645f5f286128b604b18e3d"
+        );
+        assert_eq!(
+            render(&session, Format::GithubActions),
+            "::add-mask::25a16c7465645f5f286128b604b18e3d\n"
+        );
+    }
+
+    #[test]
+    fn github_files() {
+        let session: AocSession = "25a16c7465645f5f286128b604b18e3d".parse().unwrap();
+        let dir = std::env::temp_dir().join(format!("aoc-session-gha-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let env = dir.join("env");
+        std::fs::write(&env, "CI=true\n").unwrap();
+        let output = dir.join("output");
+        to_github_files(&session, |name| match name {
+            "GITHUB_ENV" => Some(env.clone().into()),
+            "GITHUB_OUTPUT" => Some(output.clone().into()),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&env).unwrap(),
+            "CI=true\nAOC_SESSION=25a16c7465645f5f286128b604b18e3d\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(&output).unwrap(),
+            "session=25a16c7465645f5f286128b604b18e3d\n"
+        );
+        assert!(to_github_files(&session, |_| None).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,103 @@
+use std::io::Read;
+
+use aoc_session::{AocSession, Config, Error, Result};
+
+use super::token;
+
+/// The user name the session cookie value is handed to git under.
+const USERNAME: &str = "session";
+
+/// Act as a git credential helper for adventofcode.com, per `gitcredentials(7)`: `get` prints the
+/// session cookie value as the password, `store` stores the password as the session cookie
+/// value, and `erase` removes the stored one. Requests for other hosts, and other actions, are
+/// ignored, as git expects of helpers.
+///
+/// Set it up with `git config --global credential.https://adventofcode.com.helper
+/// '!aoc-session git-credential'`, after which `git credential fill` hands the session out.
+pub(crate) fn git_credential(action: &str) -> Result<()> {
+    let mut request = String::new();
+    std::io::stdin()
+        .read_to_string(&mut request)
+        .map_err(|source| Error::Io {
+            path: "<stdin>".into(),
+            source,
+        })?;
+    let request = Request::parse(&request);
+    let Some(host) = request.host.filter(|_| request.is_for_advent_of_code()) else {
+        return Ok(());
+    };
+    match action {
+        "get" => {
+            let session = match Config::load()?.session_builder().resolve() {
+                // Git moves on to the next helper.
+                Err(e) if super::exit_code(&e) == 2 => return Ok(()),
+                result => result?,
+            };
+            println!("protocol=https");
+            println!("host={host}");
+            println!("username={USERNAME}");
+            println!("password={}", session.expose_secret());
+            Ok(())
+        }
+        "store" => match request.password {
+            Some(password) => token::store(&AocSession::try_new(password)?).map(drop),
+            None => Ok(()),
+        },
+        "erase" => token::remove(),
+        _ => Ok(()),
+    }
+}
+
+/// The attributes that git describes a credential with.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Request<'a> {
+    protocol: Option<&'a str>,
+    host: Option<&'a str>,
+    password: Option<&'a str>,
+}
+
+impl<'a> Request<'a> {
+    /// Parse the `key=value` lines that git writes, up to a blank line.
+    fn parse(input: &'a str) -> Self {
+        let mut request = Request::default();
+        for line in input.lines().take_while(|line| !line.is_empty()) {
+            match line.split_once('=') {
+                Some(("protocol", value)) => request.protocol = Some(value),
+                Some(("host", value)) => request.host = Some(value),
+                Some(("password", value)) => request.password = Some(value),
+                _ => {}
+            }
+        }
+        request
+    }
+
+    fn is_for_advent_of_code(&self) -> bool {
+        let host = self.host.unwrap_or_default().to_ascii_lowercase();
+        self.protocol.is_none_or(|protocol| protocol == "https")
+            && (host == "adventofcode.com" || host.ends_with(".adventofcode.com"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_are_parsed() {
+        let request = Request::parse(
+            "protocol=https\nhost=adventofcode.com\nusername=session\npassword=a=b\n\nhost=x\n",
+        );
+        assert_eq!(
+            request,
+            Request {
+                protocol: Some("https"),
+                host: Some("adventofcode.com"),
+                password: Some("a=b"),
+            }
+        );
+        assert!(request.is_for_advent_of_code());
+        assert!(!Request::parse("protocol=https\nhost=github.com\n").is_for_advent_of_code());
+        assert!(!Request::parse("protocol=http\nhost=adventofcode.com\n").is_for_advent_of_code());
+        assert!(!Request::parse("").is_for_advent_of_code());
+    }
+}
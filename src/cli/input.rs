@@ -0,0 +1,30 @@
+use std::path::PathBuf;
+
+use aoc_session::{AocClient, Config, Error, PuzzleId, Result};
+use serde_json::json;
+
+/// Print the puzzle input of the day, or write it to `output`. Inputs are cached, so each is
+/// downloaded only once.
+///
+/// With `json`, `{"year": ..., "day": ..., "input": ...}` is printed, or `"path"` in place of
+/// `"input"` when the input was written to a file.
+pub(crate) fn input(puzzle: PuzzleId, output: Option<PathBuf>, json: bool) -> Result<()> {
+    let config = Config::load()?;
+    let session = config.session_builder().resolve()?;
+    let input = AocClient::from_config(session, &config).get_input(puzzle)?;
+    let (year, day) = (puzzle.year().get(), puzzle.day().get());
+    match output {
+        Some(path) => {
+            std::fs::write(&path, input).map_err(|source| Error::Io {
+                path: path.clone(),
+                source,
+            })?;
+            if json {
+                println!("{}", json!({"year": year, "day": day, "path": path}));
+            }
+        }
+        None if json => println!("{}", json!({"year": year, "day": day, "input": input})),
+        None => print!("{input}"),
+    }
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use std::time::Duration;
+
+use aoc_session::{aoc_session_from_login, Result, LOGIN_URL};
+use serde_json::json;
+
+use super::unix_secs;
+
+/// How long to wait for the login.
+const TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Open the login page in the default browser, wait for the session cookie to show up in a
+/// cookie store, and print its value, or `{"session": ..., "expires": ...}` with `json`.
+pub(crate) fn login(json: bool) -> Result<()> {
+    eprintln!(
+        "Log in to Advent of Code at {LOGIN_URL} in your browser. Waiting for the session \
+         cookie for up to {} minutes...",
+        TIMEOUT.as_secs() / 60
+    );
+    let session = aoc_session_from_login(TIMEOUT)?;
+    if json {
+        let expires = session.expires_at().map(unix_secs);
+        println!(
+            "{}",
+            json!({ "session": session.expose_secret(), "expires": expires })
+        );
+    } else {
+        println!("{}", session.expose_secret());
+    }
+    Ok(())
+}
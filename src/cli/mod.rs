@@ -0,0 +1,142 @@
+//! The subcommands of the executable.
+
+mod agent;
+mod compare;
+mod completions;
+mod doctor;
+mod export;
+mod git_credential;
+mod input;
+mod login;
+mod native_host;
+mod print;
+mod profiles;
+mod status;
+mod submit;
+mod token;
+
+use std::time::{Duration, SystemTime};
+
+use aoc_session::Error;
+use serde_json::json;
+
+pub(crate) use agent::agent;
+pub(crate) use compare::compare;
+pub(crate) use completions::{completions, Shell};
+pub(crate) use doctor::doctor;
+pub(crate) use export::{export, Format};
+pub(crate) use git_credential::git_credential;
+pub(crate) use input::input;
+pub(crate) use login::login;
+pub(crate) use native_host::native_host;
+pub(crate) use print::{print, Output};
+pub(crate) use profiles::profiles;
+pub(crate) use status::status;
+pub(crate) use submit::submit;
+pub(crate) use token::{clear, set};
+
+/// The exit code for an invalid command line, per `sysexits.h`.
+pub(crate) const USAGE_EXIT_CODE: u8 = 64;
+
+/// The exit code for the error, as listed in the usage, so that scripts can tell the failures
+/// apart. The codes are stable.
+pub(crate) fn exit_code(error: &Error) -> u8 {
+    match error {
+        Error::NoSessionCookieFound
+        | Error::EnvVarNotSet
+        | Error::BrowsersFailed(_)
+        | Error::InCi { .. } => 2,
+        #[cfg(feature = "keyring")]
+        Error::KeyringEntryNotFound => 2,
+        Error::AgentNotRunning => 2,
+        Error::SessionRejected => 3,
+        Error::Transport(_) | Error::HttpStatus { .. } => 4,
+        Error::CooldownActive { .. } => 5,
+        _ => 1,
+    }
+}
+
+/// The error as `{"error": {"code": ..., "message": ...}}`, for `--json`.
+pub(crate) fn error_json(error: &Error) -> String {
+    json!({"error": {"code": error.code(), "message": error.to_string()}}).to_string()
+}
+
+/// The time in seconds since the Unix epoch, as times are given in JSON.
+pub(crate) fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Format the time as `YYYY-MM-DD HH:MM UTC`.
+pub(crate) fn format_time(time: SystemTime) -> String {
+    let secs = unix_secs(time);
+    let (year, month, day) = civil_from_days(secs / 86_400);
+    let (hour, minute) = (secs % 86_400 / 3600, secs % 3600 / 60);
+    format!("{year:04}-{month:02}-{day:02} {hour:02}:{minute:02} UTC")
+}
+
+/// Format the duration in the largest whole unit, e.g. `25 days` or `3 hours`.
+pub(crate) fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (count, unit) = match secs {
+        0..=119 => (secs, "second"),
+        120..=7199 => (secs / 60, "minute"),
+        7200..=172_799 => (secs / 3600, "hour"),
+        _ => (secs / 86_400, "day"),
+    };
+    let plural = if count == 1 { "" } else { "s" };
+    format!("{count} {unit}{plural}")
+}
+
+/// The date of the given number of days since 1970-01-01, per the `civil_from_days` algorithm
+/// of Howard Hinnant.
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn times_are_formatted() {
+        let at = |secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+        assert_eq!(format_time(at(0)), "1970-01-01 00:00 UTC");
+        assert_eq!(format_time(at(1_448_946_000)), "2015-12-01 05:00 UTC");
+        assert_eq!(format_time(at(1_709_164_800)), "2024-02-29 00:00 UTC");
+        assert_eq!(format_duration(Duration::from_secs(1)), "1 second");
+        assert_eq!(format_duration(Duration::from_secs(600)), "10 minutes");
+        assert_eq!(format_duration(Duration::from_secs(30 * 86_400)), "30 days");
+    }
+
+    #[test]
+    fn exit_codes() {
+        assert_eq!(exit_code(&Error::NoSessionCookieFound), 2);
+        assert_eq!(exit_code(&Error::SessionRejected), 3);
+        assert_eq!(exit_code(&Error::Transport("timeout".to_string())), 4);
+        assert_eq!(exit_code(&Error::NoConfigDir), 1);
+    }
+
+    #[test]
+    fn errors_as_json() {
+        assert_eq!(
+            error_json(&Error::NoSessionCookieFound),
+            r#"{"error":{"code":"no_session_cookie_found","message":"No session cookie found"}}"#
+        );
+    }
+}
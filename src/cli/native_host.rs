@@ -0,0 +1,152 @@
+use std::io::{Read, Write};
+
+use aoc_session::{AocSession, Error, Result};
+use serde_json::{json, Value};
+
+use super::token;
+
+/// The largest message accepted from the browser. Messages are tiny; this only guards against a
+/// garbled length.
+const MAX_MESSAGE_LEN: usize = 1024 * 1024;
+
+/// Serve a browser extension over native messaging until the browser closes the standard input:
+/// the extension pushes the session cookie value it reads from the browser, which is stored as
+/// `set` stores it, for the browsers whose cookie store can't be read from outside.
+///
+/// Each message is a JSON object preceded by its length in 4 bytes of native byte order, in both
+/// directions. The extension sends:
+///
+/// - `{"type": "ping"}`, answered with `{"ok": true, "version": "<version>"}`;
+/// - `{"type": "session", "value": "<session cookie value>"}`, answered with
+///   `{"ok": true, "stored_in": "<place>"}`;
+/// - `{"type": "clear"}`, after logging out, answered with `{"ok": true}`.
+///
+/// Failures are answered with `{"ok": false, "error": {"code": ..., "message": ...}}`.
+pub(crate) fn native_host() -> Result<()> {
+    let mut stdin = std::io::stdin().lock();
+    let mut stdout = std::io::stdout().lock();
+    serve(&mut stdin, &mut stdout, &mut |message| {
+        handle(message, token::store)
+    })
+}
+
+fn serve(
+    input: &mut impl Read,
+    output: &mut impl Write,
+    handle: &mut impl FnMut(&Value) -> Value,
+) -> Result<()> {
+    let io = |source| Error::Io {
+        path: "<native messaging>".into(),
+        source,
+    };
+    while let Some(message) = read_message(input).map_err(io)? {
+        let reply = match serde_json::from_slice(&message) {
+            Ok(message) => handle(&message),
+            Err(e) => failure("invalid_message", &e.to_string()),
+        };
+        write_message(output, &reply).map_err(io)?;
+    }
+    Ok(())
+}
+
+/// The next message, or `None` once the browser closed the pipe.
+fn read_message(input: &mut impl Read) -> std::io::Result<Option<Vec<u8>>> {
+    let mut len = [0; 4];
+    match input.read_exact(&mut len) {
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        result => result?,
+    }
+    let len = u32::from_ne_bytes(len) as usize;
+    if len > MAX_MESSAGE_LEN {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("a message of {len} bytes is too long"),
+        ));
+    }
+    let mut message = vec![0; len];
+    input.read_exact(&mut message)?;
+    Ok(Some(message))
+}
+
+fn write_message(output: &mut impl Write, message: &Value) -> std::io::Result<()> {
+    let message = message.to_string();
+    let len = u32::try_from(message.len()).expect("replies are short");
+    output.write_all(&len.to_ne_bytes())?;
+    output.write_all(message.as_bytes())?;
+    output.flush()
+}
+
+/// The reply to a message, storing a pushed session with `store`.
+fn handle(message: &Value, store: impl FnOnce(&AocSession) -> Result<String>) -> Value {
+    let result = match message["type"].as_str() {
+        Some("ping") => Ok(json!({"ok": true, "version": env!("CARGO_PKG_VERSION")})),
+        Some("session") => match message["value"].as_str() {
+            Some(value) => AocSession::try_new(value)
+                .and_then(|session| store(&session))
+                .map(|place| json!({"ok": true, "stored_in": place})),
+            None => return failure("invalid_message", "the session has no string `value`"),
+        },
+        Some("clear") => token::remove().map(|()| json!({"ok": true})),
+        _ => return failure("invalid_message", "unknown message type"),
+    };
+    result.unwrap_or_else(|e| failure(e.code(), &e.to_string()))
+}
+
+fn failure(code: &str, message: &str) -> Value {
+    json!({"ok": false, "error": {"code": code, "message": message}})
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn framed(messages: &[&str]) -> Vec<u8> {
+        let mut input = Vec::new();
+        for message in messages {
+            input.extend_from_slice(&(message.len() as u32).to_ne_bytes());
+            input.extend_from_slice(message.as_bytes());
+        }
+        input
+    }
+
+    #[test]
+    fn messages_are_framed() {
+        let input = framed(&[r#"{"type":"ping"}"#, "not json"]);
+        let mut output = Vec::new();
+        let mut types = Vec::new();
+        serve(&mut input.as_slice(), &mut output, &mut |message| {
+            types.push(message["type"].clone());
+            json!({"ok": true})
+        })
+        .unwrap();
+        assert_eq!(types, [json!("ping")]);
+        let mut output = output.as_slice();
+        let replies: Vec<Value> = std::iter::from_fn(|| read_message(&mut output).unwrap())
+            .map(|reply| serde_json::from_slice(&reply).unwrap())
+            .collect();
+        assert_eq!(replies[0], json!({"ok": true}));
+        assert_eq!(replies[1]["error"]["code"], "invalid_message");
+        assert!(read_message(&mut &[0xff; 4][..]).is_err());
+    }
+
+    #[test]
+    fn sessions_are_stored() {
+        let value = "53616c7465645f5f".repeat(8);
+        let mut stored = None;
+        let reply = handle(&json!({"type": "session", "value": value}), |session| {
+            stored = Some(session.expose_secret().to_string());
+            Ok("the token file".to_string())
+        });
+        assert_eq!(reply, json!({"ok": true, "stored_in": "the token file"}));
+        assert_eq!(stored, Some(value));
+        let reply = handle(
+            &json!({"type": "session", "value": "session=1"}),
+            |_| unreachable!(),
+        );
+        assert_eq!(reply["error"]["code"], "malformed_token");
+        let reply = handle(&json!({"type": "ping"}), |_| unreachable!());
+        assert_eq!(reply["version"], env!("CARGO_PKG_VERSION"));
+        let reply = handle(&json!({"type": "cookies"}), |_| unreachable!());
+        assert_eq!(reply["ok"], false);
+    }
+}
@@ -0,0 +1,37 @@
+use aoc_session::{Config, Result};
+use serde_json::json;
+
+use super::unix_secs;
+
+/// How the session cookie value is printed.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) enum Output {
+    #[default]
+    Bare,
+    CookieHeader,
+    CookiesTxt,
+    Curl(String),
+    /// `{"session": ..., "source": ..., "expires": ...}`, for `--json`.
+    Json,
+}
+
+/// Print the session cookie value as requested.
+pub(crate) fn print(output: Output) -> Result<()> {
+    let (session, source) = Config::load()?.session_builder().resolve_with_source()?;
+    match output {
+        Output::Bare => println!("{}", session.expose_secret()),
+        Output::CookieHeader => println!("{}", session.to_cookie_value()),
+        Output::CookiesTxt => print!("{}", session.to_cookies_txt()),
+        Output::Curl(url) => println!("{}", session.to_curl_command(&url)),
+        Output::Json => {
+            let expires = session.expires_at().map(unix_secs);
+            let json = json!({
+                "session": session.expose_secret(),
+                "source": source.to_string(),
+                "expires": expires,
+            });
+            println!("{json}");
+        }
+    }
+    Ok(())
+}
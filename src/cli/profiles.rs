@@ -0,0 +1,27 @@
+use aoc_session::{account_profile, account_profiles, Result};
+use serde_json::json;
+
+/// Print the names of the account profiles, one per line, with the one in use marked by `*`, or
+/// `{"profiles": [...], "active": ...}` with `json`.
+pub(crate) fn profiles(json: bool) -> Result<()> {
+    let active = account_profile();
+    let mut profiles = account_profiles();
+    // A profile that was just picked has no directory until something is stored in it.
+    if let Some(name) = active.as_ref().filter(|name| !profiles.contains(name)) {
+        profiles.push(name.clone());
+        profiles.sort();
+    }
+    if json {
+        println!("{}", json!({ "profiles": profiles, "active": active }));
+        return Ok(());
+    }
+    for name in profiles {
+        let mark = if Some(&name) == active.as_ref() {
+            '*'
+        } else {
+            ' '
+        };
+        println!("{mark} {name}");
+    }
+    Ok(())
+}
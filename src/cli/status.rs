@@ -0,0 +1,51 @@
+use aoc_session::{AocClient, Config, Error, Result};
+use serde_json::json;
+
+use super::{format_duration, format_time, unix_secs};
+
+/// Show where the session cookie value comes from, when it expires, and whether Advent of Code
+/// still accepts it.
+///
+/// Fails if no value is found or if the validation fails, after printing what is known.
+pub(crate) fn status(json: bool) -> Result<()> {
+    let config = Config::load()?;
+    let (session, source) = config.session_builder().resolve_with_source()?;
+    let expires_at = session.expires_at();
+    let time_to_expiry = session.time_to_expiry();
+    let redacted = session.to_string();
+    let validation = AocClient::from_config(session, &config).whoami();
+    let valid = match &validation {
+        Ok(_) => Some(true),
+        Err(Error::SessionRejected) => Some(false),
+        Err(_) => None,
+    };
+    if json {
+        let json = json!({
+            "source": source.to_string(),
+            "session": redacted,
+            "expires": expires_at.map(unix_secs),
+            "valid": valid,
+            "account": validation.as_ref().ok().map(|account| &account.name),
+        });
+        println!("{json}");
+        return validation.map(drop);
+    }
+    println!("source:  {source}");
+    println!("session: {redacted}");
+    let expires = match (expires_at, time_to_expiry) {
+        (Some(_), Some(left)) if left.is_zero() => "expired".to_string(),
+        (Some(time), Some(left)) => format!("{} (in {})", format_time(time), format_duration(left)),
+        _ => "unknown".to_string(),
+    };
+    println!("expires: {expires}");
+    let valid = match valid {
+        Some(true) => "yes",
+        Some(false) => "no",
+        None => "unknown",
+    };
+    println!("valid:   {valid}");
+    if let Ok(account) = &validation {
+        println!("account: {account}");
+    }
+    validation.map(drop)
+}
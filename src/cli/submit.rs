@@ -0,0 +1,37 @@
+use std::io::Read;
+
+use aoc_session::{AocClient, Config, Error, PuzzleId, Result};
+
+/// Submit the answer, read from the standard input if not given, and print the outcome, as
+/// [`SubmitOutcome::to_json`](aoc_session::SubmitOutcome::to_json) with `json`.
+///
+/// Fails without contacting Advent of Code while a cooldown from an earlier submission runs.
+pub(crate) fn submit(
+    puzzle: PuzzleId,
+    level: u8,
+    answer: Option<String>,
+    json: bool,
+) -> Result<()> {
+    let answer = match answer {
+        Some(answer) => answer,
+        None => {
+            let mut answer = String::new();
+            std::io::stdin()
+                .read_to_string(&mut answer)
+                .map_err(|source| Error::Io {
+                    path: "<stdin>".into(),
+                    source,
+                })?;
+            answer
+        }
+    };
+    let config = Config::load()?;
+    let session = config.session_builder().resolve()?;
+    let outcome = AocClient::from_config(session, &config).submit(puzzle, level, answer)?;
+    if json {
+        println!("{}", outcome.to_json());
+    } else {
+        println!("{outcome}");
+    }
+    Ok(())
+}
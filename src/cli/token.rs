@@ -0,0 +1,66 @@
+use std::io::BufRead;
+
+use aoc_session::{forget_persisted_session, AocSession, Error, Result};
+use serde_json::json;
+
+/// Validate and store the session cookie value given as an argument or, failing that, on the
+/// first line of the standard input.
+///
+/// The value goes to the OS credential store when built with the `keyring` feature, and to the
+/// token file in the configuration directory otherwise. With `json`, where it went is printed as
+/// `{"stored_in": ...}`.
+pub(crate) fn set(token: Option<String>, json: bool) -> Result<()> {
+    let token = match token {
+        Some(token) => token,
+        None => {
+            let mut line = String::new();
+            std::io::stdin()
+                .lock()
+                .read_line(&mut line)
+                .map_err(|source| Error::Io {
+                    path: "<stdin>".into(),
+                    source,
+                })?;
+            line
+        }
+    };
+    let place = store(&AocSession::try_new(token)?)?;
+    if json {
+        println!("{}", json!({ "stored_in": place }));
+    } else {
+        eprintln!("Stored the session cookie value in {place}");
+    }
+    Ok(())
+}
+
+/// Store the session cookie value where [`set`] does, and return where that is.
+pub(crate) fn store(session: &AocSession) -> Result<String> {
+    #[cfg(feature = "keyring")]
+    let place = {
+        session.store_in_keyring()?;
+        "the OS credential store".to_string()
+    };
+    #[cfg(not(feature = "keyring"))]
+    let place = session.store_in_token_file()?.display().to_string();
+    forget_persisted_session()?;
+    Ok(place)
+}
+
+/// Remove the session cookie value stored by [`set`], along with the one remembered between
+/// runs. With `json`, `{"cleared": true}` is printed.
+pub(crate) fn clear(json: bool) -> Result<()> {
+    remove()?;
+    if json {
+        println!("{}", json!({ "cleared": true }));
+    }
+    Ok(())
+}
+
+/// Remove the session cookie value stored by [`store`], along with the one remembered between
+/// runs.
+pub(crate) fn remove() -> Result<()> {
+    aoc_session::delete_token_file()?;
+    #[cfg(feature = "keyring")]
+    aoc_session::delete_from_keyring()?;
+    forget_persisted_session()
+}
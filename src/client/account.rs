@@ -0,0 +1,82 @@
+//! The account a session is logged in to, as named in the header of every page of Advent of
+//! Code.
+
+use std::fmt;
+
+use super::html;
+
+/// The account a session is logged in to, as returned by
+/// [`AocClient::whoami`](crate::AocClient::whoami).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Account {
+    /// The name shown in the header of the site, e.g. the GitHub user name, or
+    /// `(anonymous user #123456)` for an anonymous account.
+    pub name: String,
+    /// The ID of the user, which the header only shows for anonymous accounts.
+    pub user_id: Option<u64>,
+    /// Whether the user supports Advent of Code, as shown by the `AoC++` badge.
+    pub supporter: bool,
+}
+
+impl Account {
+    /// The account named in the header of a logged-in page, which reads `<div class="user">name
+    /// <a class="supporter-badge">AoC++</a> <span class="star-count">42*</span></div>`.
+    pub(crate) fn from_page(page: &str) -> Option<Account> {
+        const START: &str = r#"<div class="user">"#;
+        let header = &page[page.find(START)? + START.len()..];
+        let header = &header[..header.find("</div>").unwrap_or(header.len())];
+        let name = html::text(&header[..header.find('<').unwrap_or(header.len())]);
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return None;
+        }
+        let user_id = name
+            .strip_prefix("(anonymous user #")
+            .and_then(|id| id.strip_suffix(')'))
+            .and_then(|id| id.parse().ok());
+        Some(Account {
+            name,
+            user_id,
+            supporter: header.contains("supporter-badge"),
+        })
+    }
+}
+
+impl fmt::Display for Account {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accounts_are_named() {
+        let page = r#"<header><div><h1 class="title-global"><a href="/">Advent of Code</a></h1>
+            <div class="user">Jane &amp; Co <a href="/2023/support" class="supporter-badge">AoC++</a>
+            <span class="star-count">50*</span></div></div></header>"#;
+        assert_eq!(
+            Account::from_page(page),
+            Some(Account {
+                name: "Jane & Co".to_string(),
+                user_id: None,
+                supporter: true,
+            })
+        );
+        let page = r#"<div class="user">(anonymous user #123456) <span class="star-count">2*</span></div>"#;
+        assert_eq!(
+            Account::from_page(page),
+            Some(Account {
+                name: "(anonymous user #123456)".to_string(),
+                user_id: Some(123456),
+                supporter: false,
+            })
+        );
+        assert_eq!(
+            Account::from_page(r#"<a href="/auth/login">[Log In]</a>"#),
+            None
+        );
+    }
+}
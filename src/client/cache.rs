@@ -0,0 +1,320 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use super::http::Response;
+use super::sha256;
+use crate::fs::{append, write_atomically};
+use crate::{Error, PuzzleId, PuzzleYear, Result};
+
+/// Puzzle inputs stored on disk as `<dir>/<year>/<day>.txt`, with their SHA-256 digests as
+/// `<dir>/<year>/<day>.sha256`, along with the puzzle descriptions as `<dir>/<year>/<day>.html`,
+/// private leaderboards as `<dir>/<year>/leaderboard-<id>.json`,
+/// the [`Validators`] of both as `<dir>/<year>/<day>.validators` and
+/// `<dir>/<year>/leaderboard-<id>.validators`, submission cooldowns as
+/// `<dir>/<year>/<day>.cooldown`, and the history of submitted answers as
+/// `<dir>/<year>/<day>.answers`.
+///
+/// Inputs never change once unlocked, so a cached input is never downloaded again unless a
+/// refresh is forced, per the [automation guidelines] of Advent of Code.
+///
+/// [automation guidelines]: https://www.reddit.com/r/adventofcode/wiki/faqs/automation
+#[derive(Debug, Clone)]
+pub(crate) struct InputCache {
+    dir: PathBuf,
+}
+
+impl InputCache {
+    pub(crate) fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    pub(crate) fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, puzzle: PuzzleId, extension: &str) -> PathBuf {
+        self.dir
+            .join(puzzle.year().to_string())
+            .join(format!("{}.{extension}", puzzle.day()))
+    }
+
+    pub(crate) fn read(&self, puzzle: PuzzleId) -> Result<Option<String>> {
+        read(self.path(puzzle, "txt"))
+    }
+
+    /// Cache the input along with its SHA-256 digest, which tells edits of the cached file apart.
+    pub(crate) fn write(&self, puzzle: PuzzleId, input: &str) -> Result<()> {
+        write_atomically(&self.path(puzzle, "txt"), input)?;
+        let digest = sha256::hex_digest(input.as_bytes());
+        write_atomically(&self.path(puzzle, "sha256"), &format!("{digest}\n"))
+    }
+
+    /// The SHA-256 digest of the input when it was cached, if recorded.
+    pub(crate) fn read_digest(&self, puzzle: PuzzleId) -> Result<Option<String>> {
+        Ok(read(self.path(puzzle, "sha256"))?.map(|digest| digest.trim().to_string()))
+    }
+
+    /// The HTML of the cached puzzle description, as written by [`InputCache::write_puzzle`].
+    pub(crate) fn read_puzzle(&self, puzzle: PuzzleId) -> Result<Option<String>> {
+        read(self.path(puzzle, "html"))
+    }
+
+    pub(crate) fn write_puzzle(&self, puzzle: PuzzleId, html: &str) -> Result<()> {
+        write_atomically(&self.path(puzzle, "html"), html)
+    }
+
+    /// The validators of the cached puzzle description. Missing or unreadable ones are empty.
+    pub(crate) fn read_puzzle_validators(&self, puzzle: PuzzleId) -> Validators {
+        read_validators(self.path(puzzle, "validators"))
+    }
+
+    pub(crate) fn write_puzzle_validators(
+        &self,
+        puzzle: PuzzleId,
+        validators: &Validators,
+    ) -> Result<()> {
+        write_validators(&self.path(puzzle, "validators"), validators)
+    }
+
+    fn leaderboard_path(&self, year: PuzzleYear, id: u64) -> PathBuf {
+        self.dir
+            .join(year.to_string())
+            .join(format!("leaderboard-{id}.json"))
+    }
+
+    /// The JSON of the cached private leaderboard, unless it was cached more than `max_age` ago.
+    pub(crate) fn read_leaderboard(
+        &self,
+        year: PuzzleYear,
+        id: u64,
+        max_age: Duration,
+    ) -> Result<Option<String>> {
+        let path = self.leaderboard_path(year, id);
+        let age = std::fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok());
+        match age {
+            Some(age) if age < max_age => read(path),
+            _ => Ok(None),
+        }
+    }
+
+    pub(crate) fn write_leaderboard(&self, year: PuzzleYear, id: u64, json: &str) -> Result<()> {
+        write_atomically(&self.leaderboard_path(year, id), json)
+    }
+
+    /// The validators of the cached private leaderboard. Missing or unreadable ones are empty.
+    pub(crate) fn read_leaderboard_validators(&self, year: PuzzleYear, id: u64) -> Validators {
+        read_validators(self.leaderboard_path(year, id).with_extension("validators"))
+    }
+
+    pub(crate) fn write_leaderboard_validators(
+        &self,
+        year: PuzzleYear,
+        id: u64,
+        validators: &Validators,
+    ) -> Result<()> {
+        let path = self.leaderboard_path(year, id).with_extension("validators");
+        write_validators(&path, validators)
+    }
+
+    /// When answers for the day may be submitted again, if a cooldown was recorded.
+    ///
+    /// The file holds the end of the cooldown in seconds since the Unix epoch; an unreadable
+    /// file is ignored rather than blocking submissions forever.
+    pub(crate) fn read_cooldown(&self, puzzle: PuzzleId) -> Result<Option<SystemTime>> {
+        let until = read(self.path(puzzle, "cooldown"))?
+            .and_then(|contents| contents.trim().parse().ok())
+            .map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs));
+        Ok(until)
+    }
+
+    pub(crate) fn write_cooldown(&self, puzzle: PuzzleId, until: SystemTime) -> Result<()> {
+        let secs = until
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        write_atomically(&self.path(puzzle, "cooldown"), &format!("{secs}\n"))
+    }
+
+    /// The submitted answers of the day, one JSON object per line.
+    pub(crate) fn read_answers(&self, puzzle: PuzzleId) -> Result<Option<String>> {
+        read(self.path(puzzle, "answers"))
+    }
+
+    pub(crate) fn append_answer(&self, puzzle: PuzzleId, line: &str) -> Result<()> {
+        append(&self.path(puzzle, "answers"), line)
+    }
+
+    pub(crate) fn clear_cooldown(&self, puzzle: PuzzleId) -> Result<()> {
+        let path = self.path(puzzle, "cooldown");
+        match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(Error::Io { path, source: e })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The `ETag` and `Last-Modified` headers of a cached response, sent back as `If-None-Match` and
+/// `If-Modified-Since` to download it again only if it changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Validators {
+    pub(crate) etag: Option<String>,
+    pub(crate) last_modified: Option<String>,
+}
+
+impl Validators {
+    pub(crate) fn of(response: &Response) -> Validators {
+        Validators {
+            etag: response.header("etag").map(str::to_string),
+            last_modified: response.header("last-modified").map(str::to_string),
+        }
+    }
+
+    /// The headers of a conditional request.
+    pub(crate) fn headers(&self) -> Vec<String> {
+        let etag = self
+            .etag
+            .iter()
+            .map(|etag| format!("If-None-Match: {etag}"));
+        let since = self
+            .last_modified
+            .iter()
+            .map(|time| format!("If-Modified-Since: {time}"));
+        etag.chain(since).collect()
+    }
+
+    /// The validators as stored, one `name: value` line each.
+    fn to_file(&self) -> String {
+        let etag = self.etag.iter().map(|etag| format!("etag: {etag}\n"));
+        let since = self
+            .last_modified
+            .iter()
+            .map(|time| format!("last-modified: {time}\n"));
+        etag.chain(since).collect()
+    }
+
+    fn from_file(contents: &str) -> Validators {
+        let mut validators = Validators::default();
+        for (name, value) in contents.lines().filter_map(|line| line.split_once(':')) {
+            let value = Some(value.trim().to_string());
+            match name.trim() {
+                "etag" => validators.etag = value,
+                "last-modified" => validators.last_modified = value,
+                _ => {}
+            }
+        }
+        validators
+    }
+}
+
+fn read_validators(path: PathBuf) -> Validators {
+    read(path)
+        .ok()
+        .flatten()
+        .map(|contents| Validators::from_file(&contents))
+        .unwrap_or_default()
+}
+
+fn write_validators(path: &Path, validators: &Validators) -> Result<()> {
+    if validators == &Validators::default() {
+        return match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(Error::Io {
+                path: path.to_path_buf(),
+                source: e,
+            }),
+            _ => Ok(()),
+        };
+    }
+    write_atomically(path, &validators.to_file())
+}
+
+fn read(path: PathBuf) -> Result<Option<String>> {
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(source) => Err(Error::Io { path, source }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn puzzle(year: u16, day: u8) -> PuzzleId {
+        PuzzleId::new(year, day).unwrap()
+    }
+
+    fn year(year: u16) -> PuzzleYear {
+        PuzzleYear::new(year).unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let cache = InputCache::new(std::env::temp_dir().join("aoc-session-input-cache-test"));
+        let _ = std::fs::remove_dir_all(cache.dir());
+        assert_eq!(cache.read(puzzle(2015, 1)).unwrap(), None);
+        cache.write(puzzle(2015, 1), "(()))\n").unwrap();
+        assert_eq!(
+            cache.read(puzzle(2015, 1)).unwrap().as_deref(),
+            Some("(()))\n")
+        );
+        assert!(cache.dir().join("2015").join("1.txt").is_file());
+        assert_eq!(
+            cache.read_digest(puzzle(2015, 1)).unwrap().as_deref(),
+            Some(sha256::hex_digest(b"(()))\n").as_str())
+        );
+    }
+
+    #[test]
+    fn validators_round_trip() {
+        let cache = InputCache::new(std::env::temp_dir().join("aoc-session-validators-test"));
+        let _ = std::fs::remove_dir_all(cache.dir());
+        assert_eq!(
+            cache.read_puzzle_validators(puzzle(2015, 1)),
+            Validators::default()
+        );
+        let validators = Validators {
+            etag: Some("W/\"5f-abc\"".to_string()),
+            last_modified: Some("Fri, 01 Dec 2023 05:00:00 GMT".to_string()),
+        };
+        cache
+            .write_leaderboard_validators(year(2015), 42, &validators)
+            .unwrap();
+        assert_eq!(
+            cache.read_leaderboard_validators(year(2015), 42),
+            validators
+        );
+        assert_eq!(
+            validators.headers(),
+            [
+                "If-None-Match: W/\"5f-abc\"",
+                "If-Modified-Since: Fri, 01 Dec 2023 05:00:00 GMT"
+            ]
+        );
+        cache
+            .write_leaderboard_validators(year(2015), 42, &Validators::default())
+            .unwrap();
+        assert_eq!(
+            cache.read_leaderboard_validators(year(2015), 42),
+            Validators::default()
+        );
+    }
+
+    #[test]
+    fn cooldown() {
+        let cache = InputCache::new(std::env::temp_dir().join("aoc-session-cooldown-test"));
+        let _ = std::fs::remove_dir_all(cache.dir());
+        assert_eq!(cache.read_cooldown(puzzle(2015, 1)).unwrap(), None);
+        let until = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_060);
+        cache.write_cooldown(puzzle(2015, 1), until).unwrap();
+        assert_eq!(cache.read_cooldown(puzzle(2015, 1)).unwrap(), Some(until));
+        assert_eq!(cache.read_cooldown(puzzle(2015, 2)).unwrap(), None);
+        cache.clear_cooldown(puzzle(2015, 1)).unwrap();
+        cache.clear_cooldown(puzzle(2015, 1)).unwrap();
+        assert_eq!(cache.read_cooldown(puzzle(2015, 1)).unwrap(), None);
+    }
+}
@@ -0,0 +1,134 @@
+//! Checking the session cookies of every browser against Advent of Code, to tell which account
+//! each belongs to.
+
+use super::AocClient;
+use crate::{aoc_sessions, AocSession, DiscoveredSession, Error, Result};
+
+/// Whether Advent of Code accepts a session, and which account it belongs to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccountStatus {
+    /// Advent of Code accepts the session, which belongs to the account with this name, as shown
+    /// in the header of the site, e.g. `(anonymous user #123456)`.
+    Account(String),
+    /// Advent of Code rejects the session, which has likely expired.
+    Rejected,
+    /// The session couldn't be checked, for the given reason.
+    Unknown(String),
+}
+
+/// A session cookie found in a browser, checked against Advent of Code.
+#[derive(Debug)]
+pub struct ComparedSession {
+    /// Where the session cookie was found.
+    pub found: DiscoveredSession,
+    /// Whether Advent of Code accepts it, and for which account.
+    pub status: AccountStatus,
+}
+
+/// The session cookies of every browser, checked against Advent of Code by
+/// [`compare_sessions`].
+#[derive(Debug)]
+pub struct SessionComparison {
+    /// The session cookies, in the order of [`aoc_sessions`].
+    pub sessions: Vec<ComparedSession>,
+}
+
+impl SessionComparison {
+    /// The names of the accounts the valid sessions belong to, without duplicates, in order.
+    pub fn accounts(&self) -> Vec<&str> {
+        let mut accounts = Vec::new();
+        for compared in &self.sessions {
+            if let AccountStatus::Account(name) = &compared.status {
+                if !accounts.contains(&name.as_str()) {
+                    accounts.push(name.as_str());
+                }
+            }
+        }
+        accounts
+    }
+
+    /// Whether the browsers are logged in to different accounts, in which case the session picked
+    /// by [`aoc_session`](crate::aoc_session) may not be the one meant.
+    pub fn mixes_accounts(&self) -> bool {
+        self.accounts().len() > 1
+    }
+}
+
+/// Find the session cookies of every browser (see [`aoc_sessions`]) and ask Advent of Code which
+/// account each belongs to, to catch browsers logged in to different accounts.
+///
+/// Each distinct session cookie value costs one request to adventofcode.com.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoc_session::compare_sessions;
+///
+/// let comparison = compare_sessions().unwrap();
+/// if comparison.mixes_accounts() {
+///     eprintln!("The browsers are logged in to {:?}", comparison.accounts());
+/// }
+/// ```
+pub fn compare_sessions() -> Result<SessionComparison> {
+    let mut checked: Vec<(AocSession, AccountStatus)> = Vec::new();
+    let mut sessions = Vec::new();
+    for found in aoc_sessions()? {
+        let known = checked
+            .iter()
+            .find(|(session, _)| *session == found.session);
+        let status = match known {
+            Some((_, status)) => status.clone(),
+            None => {
+                let status = check(&found.session);
+                checked.push((found.session.clone(), status.clone()));
+                status
+            }
+        };
+        sessions.push(ComparedSession { found, status });
+    }
+    Ok(SessionComparison { sessions })
+}
+
+/// Ask Advent of Code which account the session belongs to.
+fn check(session: &AocSession) -> AccountStatus {
+    let client = AocClient::builder(session.clone()).no_cache().build();
+    match client.whoami() {
+        Ok(account) => AccountStatus::Account(account.name),
+        Err(Error::SessionRejected) => AccountStatus::Rejected,
+        Err(e) => AccountStatus::Unknown(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::Browser;
+
+    #[test]
+    fn mixed_accounts_are_detected() {
+        let compared = |status| ComparedSession {
+            found: DiscoveredSession {
+                browser: Browser::Firefox,
+                profile: PathBuf::new(),
+                expires: None,
+                session: AocSession::new("25a16c74"),
+            },
+            status,
+        };
+        let mut comparison = SessionComparison {
+            sessions: vec![
+                compared(AccountStatus::Account("jane".to_string())),
+                compared(AccountStatus::Rejected),
+                compared(AccountStatus::Account("jane".to_string())),
+            ],
+        };
+        assert_eq!(comparison.accounts(), ["jane"]);
+        assert!(!comparison.mixes_accounts());
+        comparison
+            .sessions
+            .push(compared(AccountStatus::Account("john".to_string())));
+        assert!(comparison.mixes_accounts());
+    }
+}
@@ -0,0 +1,284 @@
+//! Just enough HTML handling for the pages of adventofcode.com, which are simple and stable.
+
+/// The inner HTML of every `<article>` element of the page.
+pub(crate) fn articles(html: &str) -> Vec<&str> {
+    let mut articles = Vec::new();
+    let mut rest = html;
+    while let Some(start) = rest.find("<article") {
+        let Some(open_end) = rest[start..].find('>') else {
+            break;
+        };
+        let inner = &rest[start + open_end + 1..];
+        let end = inner.find("</article>").unwrap_or(inner.len());
+        articles.push(&inner[..end]);
+        rest = &inner[end..];
+    }
+    articles
+}
+
+/// The text of every `<pre><code>` block of the HTML fragment.
+pub(crate) fn code_blocks(html: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut rest = html;
+    while let Some(start) = rest.find("<pre><code>") {
+        let inner = &rest[start + "<pre><code>".len()..];
+        let end = inner.find("</code></pre>").unwrap_or(inner.len());
+        blocks.push(text(&inner[..end]));
+        rest = &inner[end..];
+    }
+    blocks
+}
+
+/// The text of an HTML fragment, with tags removed and entities decoded.
+pub(crate) fn text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    decode_entities(&text)
+}
+
+/// A tag of an HTML fragment.
+pub(crate) struct Tag<'a> {
+    name: &'a str,
+    closing: bool,
+    /// The unparsed attributes.
+    attributes: &'a str,
+}
+
+impl<'a> Tag<'a> {
+    fn parse(tag: &'a str) -> Self {
+        let (closing, tag) = match tag.strip_prefix('/') {
+            Some(tag) => (true, tag),
+            None => (false, tag),
+        };
+        let tag = tag.trim_end_matches('/').trim();
+        let (name, attributes) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+        Tag {
+            name,
+            closing,
+            attributes,
+        }
+    }
+
+    /// The value of a double-quoted attribute, with entities decoded.
+    pub(crate) fn attribute(&self, name: &str) -> Option<String> {
+        let start = self.attributes.find(&format!("{name}=\""))? + name.len() + 2;
+        let value = &self.attributes[start..];
+        Some(decode_entities(&value[..value.find('"')?]))
+    }
+}
+
+/// The opening tags with the given name in the HTML fragment.
+pub(crate) fn start_tags<'a>(html: &'a str, name: &str) -> Vec<Tag<'a>> {
+    let mut tags = Vec::new();
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        let Some(end) = rest[start..].find('>') else {
+            break;
+        };
+        let tag = Tag::parse(&rest[start + 1..start + end]);
+        if tag.name == name && !tag.closing {
+            tags.push(tag);
+        }
+        rest = &rest[start + end + 1..];
+    }
+    tags
+}
+
+/// Convert an HTML fragment of a puzzle description to Markdown.
+///
+/// Only the handful of elements used on adventofcode.com are translated: headings, paragraphs,
+/// emphasis, inline and block code, lists, and links. Other tags are dropped, keeping their text.
+pub(crate) fn markdown(html: &str) -> String {
+    render(html, true)
+}
+
+/// Convert an HTML fragment of a puzzle description to plain text, laid out like
+/// [`markdown`] but without the markup.
+pub(crate) fn plain_text(html: &str) -> String {
+    render(html, false)
+}
+
+fn render(html: &str, markdown: bool) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut in_pre = false;
+    let mut in_code = false;
+    let mut links = Vec::new();
+    let mut rest = html;
+    // Markup that is only written in Markdown.
+    let markup = |out: &mut String, markup: &str| {
+        if markdown {
+            out.push_str(markup);
+        }
+    };
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            push_text(&mut out, rest, in_pre);
+            break;
+        };
+        push_text(&mut out, &rest[..start], in_pre);
+        let Some(end) = rest[start..].find('>') else {
+            break;
+        };
+        let tag = Tag::parse(&rest[start + 1..start + end]);
+        rest = &rest[start + end + 1..];
+        match (tag.name, tag.closing) {
+            ("h2", false) => {
+                out.push_str("\n\n");
+                markup(&mut out, "## ");
+            }
+            ("p" | "h2" | "ul", _) => out.push_str("\n\n"),
+            ("li", false) => out.push_str("\n- "),
+            ("pre", false) => {
+                in_pre = true;
+                out.push_str("\n\n");
+                markup(&mut out, "```\n");
+            }
+            ("pre", true) => {
+                in_pre = false;
+                if !out.ends_with('\n') {
+                    out.push('\n');
+                }
+                markup(&mut out, "```\n");
+                out.push('\n');
+            }
+            ("code", closing) if !in_pre => {
+                in_code = !closing;
+                markup(&mut out, "`");
+            }
+            ("em", _) if !in_pre && !in_code => markup(&mut out, "*"),
+            ("a", false) => {
+                links.push(tag.attribute("href"));
+                markup(&mut out, "[");
+            }
+            ("a", true) => match links.pop().flatten() {
+                Some(href) => markup(&mut out, &format!("]({href})")),
+                None => markup(&mut out, "]"),
+            },
+            _ => {}
+        }
+    }
+    collapse_blank_lines(out.trim())
+}
+
+fn push_text(out: &mut String, html: &str, in_pre: bool) {
+    let text = decode_entities(html);
+    if in_pre {
+        out.push_str(&text);
+    } else if text.trim().is_empty() && (out.is_empty() || out.ends_with('\n')) {
+        // Whitespace between blocks.
+    } else {
+        // Line breaks in the source are insignificant outside of `<pre>`.
+        out.push_str(&text.replace('\n', " "));
+    }
+}
+
+/// Collapse runs of blank lines into a single blank line, and end the text with a newline.
+fn collapse_blank_lines(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len() + 1);
+    let mut newlines = 0;
+    for c in text.chars() {
+        if c == '\n' {
+            newlines += 1;
+            if newlines > 2 {
+                continue;
+            }
+        } else {
+            newlines = 0;
+        }
+        collapsed.push(c);
+    }
+    collapsed.push('\n');
+    collapsed
+}
+
+/// Decode the HTML entities that appear on adventofcode.com.
+pub(crate) fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let entity = rest
+            .find(';')
+            .filter(|&end| end <= 10)
+            .and_then(|end| Some((decode_entity(&rest[1..end])?, end)));
+        match entity {
+            Some((c, end)) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn decode_entity(name: &str) -> Option<char> {
+    match name {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some('\u{a0}'),
+        _ => {
+            let code = name.strip_prefix('#')?;
+            let code = match code.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => code.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn article_text() {
+        let html = "<main><article class=\"day-desc\"><p>That&apos;s <em>not</em> \
+                    the right answer &amp; stuff&#33; &bogus;</p></article></main>";
+        let articles = articles(html);
+        assert_eq!(articles.len(), 1);
+        assert_eq!(
+            text(articles[0]),
+            "That's not the right answer & stuff! &bogus;"
+        );
+    }
+
+    #[test]
+    fn markdown_conversion() {
+        let html = "<h2>--- Day 1: Trebuchet?! ---</h2><p>Find <em>the</em> \
+                    <a href=\"/2023/day/1/input\" target=\"_blank\">sum</a>\nof \
+                    <code>1 &lt; 2</code>:</p>\n<pre><code>1abc2\n<em>pqr</em>3stu8vwx\n\
+                    </code></pre>\n<ul><li>one</li><li><code><em>two</em></code></li></ul>";
+        assert_eq!(
+            markdown(html),
+            "## --- Day 1: Trebuchet?! ---\n\n\
+             Find *the* [sum](/2023/day/1/input) of `1 < 2`:\n\n\
+             ```\n1abc2\npqr3stu8vwx\n```\n\n\
+             - one\n- `two`\n"
+        );
+        assert_eq!(
+            plain_text(html),
+            "--- Day 1: Trebuchet?! ---\n\n\
+             Find the sum of 1 < 2:\n\n\
+             1abc2\npqr3stu8vwx\n\n\
+             - one\n- two\n"
+        );
+    }
+}
@@ -0,0 +1,334 @@
+//! A minimal HTTP backend driving the system `curl` executable.
+//!
+//! The request is passed to `curl` as a config file on its standard input, so the session
+//! cookie never shows up in the process list.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use super::proxy::Proxy;
+use crate::{Error, Result};
+
+/// The status line separator appended to the response body with `--write-out`.
+const STATUS_MARKER: &str = "\n--aoc-session-status:";
+
+/// The exit codes of `curl` for failures that may not happen again: a failed connection (7), a
+/// timeout (28), an empty reply (52), and failures to send (55) or receive (56) data.
+const TRANSIENT_EXIT_CODES: [i32; 5] = [7, 28, 52, 55, 56];
+
+/// A request to be performed by `curl`.
+pub(crate) struct Request<'a> {
+    pub(crate) url: &'a str,
+    pub(crate) headers: Vec<String>,
+    pub(crate) user_agent: &'a str,
+    pub(crate) timeout: Option<Duration>,
+    /// URL-encoded form data. If present, the request is a `POST`.
+    pub(crate) form: Vec<(&'a str, &'a str)>,
+    /// The proxy to go through. Without one, `curl` is told to ignore the proxy environment
+    /// variables, which were already taken into account.
+    pub(crate) proxy: Option<Proxy>,
+}
+
+/// The headers may hold the session cookie, so they're wiped with the `zeroize` feature.
+#[cfg(feature = "zeroize")]
+impl Drop for Request<'_> {
+    fn drop(&mut self) {
+        self.headers.iter_mut().for_each(crate::secret::wipe);
+        if let Some(credentials) = self.proxy.as_mut().and_then(|p| p.credentials.as_mut()) {
+            crate::secret::wipe(credentials);
+        }
+    }
+}
+
+/// A response received by `curl`.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct Response {
+    pub(crate) status: u16,
+    /// The headers of the final response, with lowercase names.
+    pub(crate) headers: Vec<(String, String)>,
+    pub(crate) body: String,
+}
+
+/// The outcome of sending a request once.
+pub(crate) enum Attempt {
+    Response(Response),
+    /// A failure that may not happen again, e.g. a timeout.
+    Transient(Error),
+    Failed(Error),
+}
+
+impl Response {
+    /// The value of the header with the given lowercase name, if present.
+    pub(crate) fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Whether the server failed in a way that may not happen again, e.g. under load.
+    pub(crate) fn is_transient_failure(&self) -> bool {
+        (500..600).contains(&self.status)
+    }
+
+    /// Return the body if the status is successful.
+    pub(crate) fn ok(self, url: &str) -> Result<String> {
+        if (200..300).contains(&self.status) {
+            Ok(self.body)
+        } else {
+            Err(Error::HttpStatus {
+                url: url.to_string(),
+                status: self.status,
+            })
+        }
+    }
+}
+
+impl Request<'_> {
+    /// The `curl` config that describes the request.
+    ///
+    /// The values are written straight into the config, without temporary copies of the
+    /// session cookie.
+    fn config(&self) -> String {
+        // Reserve enough for the worst case of escaping, so that the buffer is never
+        // reallocated and left behind unwiped.
+        let values = [self.url, self.user_agent]
+            .into_iter()
+            .chain(self.headers.iter().map(String::as_str))
+            .chain(self.form.iter().flat_map(|(key, value)| [*key, *value]))
+            .chain(self.proxy.iter().map(|proxy| proxy.url.as_str()))
+            .chain(
+                self.proxy
+                    .iter()
+                    .filter_map(|proxy| proxy.credentials.as_deref()),
+            )
+            .map(str::len)
+            .sum::<usize>();
+        let lines = 5 + self.headers.len() + self.form.len();
+        let mut config = String::with_capacity(2 * values + 64 * lines);
+        push_option(&mut config, "url", &[self.url]);
+        push_option(&mut config, "user-agent", &[self.user_agent]);
+        if let Some(timeout) = self.timeout {
+            config.push_str(&format!("max-time = {}\n", timeout.as_secs_f64()));
+        }
+        match &self.proxy {
+            Some(proxy) => {
+                push_option(&mut config, "proxy", &[&proxy.url]);
+                if let Some(credentials) = &proxy.credentials {
+                    push_option(&mut config, "proxy-user", &[credentials]);
+                }
+            }
+            None => push_option(&mut config, "noproxy", &["*"]),
+        }
+        for header in &self.headers {
+            push_option(&mut config, "header", &[header]);
+        }
+        for (key, value) in &self.form {
+            push_option(&mut config, "data-urlencode", &[key, "=", value]);
+        }
+        config
+    }
+
+    pub(crate) fn send(&self) -> Result<Response> {
+        match self.attempt() {
+            Attempt::Response(response) => Ok(response),
+            Attempt::Transient(e) | Attempt::Failed(e) => Err(e),
+        }
+    }
+
+    /// Send the request once, telling transient failures apart.
+    pub(crate) fn attempt(&self) -> Attempt {
+        match self.run_curl() {
+            Ok(response) => Attempt::Response(response),
+            Err((e, Some(code))) if TRANSIENT_EXIT_CODES.contains(&code) => Attempt::Transient(e),
+            Err((e, _)) => Attempt::Failed(e),
+        }
+    }
+
+    /// Run `curl`, returning its exit code along with the error if it fails.
+    fn run_curl(&self) -> core::result::Result<Response, (Error, Option<i32>)> {
+        let failed = |e| (e, None);
+        if cfg!(target_family = "wasm") {
+            return Err(failed(Error::Transport(
+                "there is no curl to run on WebAssembly".to_string(),
+            )));
+        }
+        let header_file = HeaderFile::create().map_err(failed)?;
+        let mut child = Command::new("curl")
+            .args(["--silent", "--show-error", "--config", "-"])
+            .args(["--write-out", &format!("{STATUS_MARKER}%{{http_code}}")])
+            .arg("--dump-header")
+            .arg(&header_file.path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| failed(Error::Transport(format!("failed to run curl: {e}"))))?;
+        let mut config = self.config();
+        let written = child
+            .stdin
+            .take()
+            .expect("stdin is piped")
+            .write_all(config.as_bytes());
+        crate::secret::wipe(&mut config);
+        written.map_err(|e| {
+            failed(Error::Transport(format!(
+                "failed to pass the request to curl: {e}"
+            )))
+        })?;
+        let output = child
+            .wait_with_output()
+            .map_err(|e| failed(Error::Transport(format!("failed to wait for curl: {e}"))))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let error = Error::Transport(stderr.trim().to_string());
+            return Err((error, output.status.code()));
+        }
+        let mut response =
+            parse_output(&String::from_utf8_lossy(&output.stdout)).map_err(failed)?;
+        response.headers = header_file.read();
+        Ok(response)
+    }
+}
+
+/// The file that `curl` dumps the response headers into, removed on drop. Since it may hold
+/// cookies, it's kept in a directory only accessible by the current user on Unix.
+struct HeaderFile {
+    path: PathBuf,
+    _dir: crate::fs::PrivateTempDir,
+}
+
+impl HeaderFile {
+    fn create() -> Result<HeaderFile> {
+        let dir = crate::fs::PrivateTempDir::create().map_err(|source| Error::Io {
+            path: std::env::temp_dir(),
+            source,
+        })?;
+        Ok(HeaderFile {
+            path: dir.path().join("headers"),
+            _dir: dir,
+        })
+    }
+
+    fn read(&self) -> Vec<(String, String)> {
+        read_headers(&self.path)
+    }
+}
+
+fn read_headers(path: &Path) -> Vec<(String, String)> {
+    std::fs::read_to_string(path)
+        .map(|dump| parse_headers(&dump))
+        .unwrap_or_default()
+}
+
+/// The headers of the last response in a header dump, which also holds those of any interim
+/// response, e.g. `100 Continue` or the reply of a proxy to `CONNECT`.
+fn parse_headers(dump: &str) -> Vec<(String, String)> {
+    let last = dump
+        .replace("\r\n", "\n")
+        .split("\n\n")
+        .filter(|block| !block.trim().is_empty())
+        .last()
+        .unwrap_or_default()
+        .to_string();
+    last.lines()
+        .skip(1)
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect()
+}
+
+/// Append a `curl` config line setting the option to the concatenated parts, quoted.
+fn push_option(config: &mut String, option: &str, parts: &[&str]) {
+    config.push_str(option);
+    config.push_str(" = \"");
+    for c in parts.iter().flat_map(|part| part.chars()) {
+        match c {
+            '\\' => config.push_str("\\\\"),
+            '"' => config.push_str("\\\""),
+            '\n' => config.push_str("\\n"),
+            '\r' => config.push_str("\\r"),
+            '\t' => config.push_str("\\t"),
+            c => config.push(c),
+        }
+    }
+    config.push_str("\"\n");
+}
+
+fn parse_output(stdout: &str) -> Result<Response> {
+    let (body, status) = stdout
+        .rsplit_once(STATUS_MARKER)
+        .ok_or_else(|| Error::Transport("curl did not report a status code".to_string()))?;
+    let status = status
+        .trim()
+        .parse()
+        .map_err(|_| Error::Transport(format!("curl reported a bad status code: {status:?}")))?;
+    Ok(Response {
+        status,
+        headers: Vec::new(),
+        body: body.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_is_quoted() {
+        let request = Request {
+            url: "https://adventofcode.com/2023/day/1/answer",
+            headers: vec!["Cookie: session=25a16c74".to_string()],
+            user_agent: "agent \"007\"",
+            timeout: Some(Duration::from_millis(2500)),
+            form: vec![("level", "1"), ("answer", "a\\b")],
+            proxy: Some(Proxy {
+                url: "http://proxy:3128".to_string(),
+                credentials: Some("me:secret".to_string()),
+            }),
+        };
+        assert_eq!(
+            request.config(),
+            "url = \"https://adventofcode.com/2023/day/1/answer\"\n\
+             user-agent = \"agent \\\"007\\\"\"\n\
+             max-time = 2.5\n\
+             proxy = \"http://proxy:3128\"\n\
+             proxy-user = \"me:secret\"\n\
+             header = \"Cookie: session=25a16c74\"\n\
+             data-urlencode = \"level=1\"\n\
+             data-urlencode = \"answer=a\\\\b\"\n"
+        );
+    }
+
+    #[test]
+    fn status_is_split_from_body() {
+        let response = parse_output("1000\n2000\n\n--aoc-session-status:200").unwrap();
+        assert_eq!(
+            response,
+            Response {
+                status: 200,
+                headers: Vec::new(),
+                body: "1000\n2000\n".to_string()
+            }
+        );
+        assert!(parse_output("no status").is_err());
+    }
+
+    #[test]
+    fn headers_of_the_last_response_are_kept() {
+        let dump = "HTTP/1.1 200 Connection established\r\n\r\n\
+                    HTTP/2 304\r\nETag: \"abc\"\r\nlast-modified: Fri, 01 Dec 2023 05:00:00 GMT\r\n\r\n";
+        let response = Response {
+            headers: parse_headers(dump),
+            ..Response::default()
+        };
+        assert_eq!(response.header("etag"), Some("\"abc\""));
+        assert_eq!(
+            response.header("last-modified"),
+            Some("Fri, 01 Dec 2023 05:00:00 GMT")
+        );
+        assert!(parse_headers("").is_empty());
+    }
+}
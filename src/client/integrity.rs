@@ -0,0 +1,38 @@
+//! Checking cached puzzle inputs against Advent of Code, e.g. after copying the cache between
+//! machines.
+
+use std::fmt;
+
+/// How a cached puzzle input compares with the one Advent of Code serves, as returned by
+/// [`AocClient::verify_input`](crate::AocClient::verify_input).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputIntegrity {
+    /// The cached input is the one Advent of Code serves.
+    Intact,
+    /// The input isn't cached.
+    NotCached,
+    /// The cached input was changed since it was downloaded: it matches neither Advent of Code
+    /// nor the SHA-256 digest recorded along with it.
+    Edited,
+    /// The cached input differs from the one Advent of Code serves, yet it's as it was cached,
+    /// e.g. because it was cut short or downloaded with the session of another account.
+    Corrupted,
+}
+
+impl InputIntegrity {
+    /// Whether the cached input can be trusted.
+    pub fn is_intact(&self) -> bool {
+        *self == InputIntegrity::Intact
+    }
+}
+
+impl fmt::Display for InputIntegrity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            InputIntegrity::Intact => "the cached input is intact",
+            InputIntegrity::NotCached => "the input isn't cached",
+            InputIntegrity::Edited => "the cached input was edited",
+            InputIntegrity::Corrupted => "the cached input differs from Advent of Code",
+        })
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime};
+
+use serde_json::Value;
+
+/// How often the JSON of a private leaderboard may be fetched, per the note on its page.
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// A private leaderboard, as fetched by
+/// [`AocClient::private_leaderboard`](crate::AocClient::private_leaderboard).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Leaderboard {
+    /// The ID of the member who owns the leaderboard, which is also the ID of the leaderboard.
+    pub owner_id: u64,
+    /// The year of the event.
+    pub year: u16,
+    /// The members, by decreasing local score.
+    pub members: Vec<Member>,
+}
+
+/// A member of a private [`Leaderboard`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    pub id: u64,
+    /// The display name, unless the member is anonymous.
+    pub name: Option<String>,
+    pub stars: u32,
+    pub local_score: u64,
+    pub global_score: u64,
+    /// When the member got their last star, if they got any.
+    pub last_star: Option<SystemTime>,
+    /// When the member solved the puzzles, by day.
+    pub days: BTreeMap<u8, DayCompletion>,
+}
+
+/// When a [`Member`] got the stars of a day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DayCompletion {
+    pub part_one: SystemTime,
+    pub part_two: Option<SystemTime>,
+}
+
+impl Member {
+    /// The display name, or `(anonymous user #<id>)` as shown on the leaderboard page.
+    pub fn display_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("(anonymous user #{})", self.id),
+        }
+    }
+}
+
+/// Parse the JSON of a private leaderboard.
+pub(crate) fn parse(json: &str) -> Result<Leaderboard, String> {
+    let root: Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    let year = root
+        .get("event")
+        .and_then(Value::as_str)
+        .and_then(|event| event.parse().ok())
+        .ok_or("missing `event`")?;
+    let owner_id = root
+        .get("owner_id")
+        .and_then(Value::as_u64)
+        .ok_or("missing `owner_id`")?;
+    let mut members = root
+        .get("members")
+        .and_then(Value::as_object)
+        .ok_or("missing `members`")?
+        .values()
+        .map(member)
+        .collect::<Result<Vec<_>, _>>()?;
+    members.sort_by(|a, b| b.local_score.cmp(&a.local_score).then(a.id.cmp(&b.id)));
+    Ok(Leaderboard {
+        owner_id,
+        year,
+        members,
+    })
+}
+
+fn member(member: &Value) -> Result<Member, String> {
+    let number = |key: &str| {
+        member
+            .get(key)
+            .and_then(Value::as_u64)
+            .ok_or_else(|| format!("missing `{key}` of a member"))
+    };
+    let days = member
+        .get("completion_day_level")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .filter_map(|(day, levels)| {
+            let star = |level: &str| timestamp(levels.get(level)?.get("get_star_ts")?);
+            let completion = DayCompletion {
+                part_one: star("1")?,
+                part_two: star("2"),
+            };
+            Some((day.parse().ok()?, completion))
+        })
+        .collect();
+    Ok(Member {
+        id: number("id")?,
+        name: member
+            .get("name")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        stars: u32::try_from(number("stars")?).map_err(|e| e.to_string())?,
+        local_score: number("local_score")?,
+        global_score: number("global_score")?,
+        last_star: member
+            .get("last_star_ts")
+            .and_then(timestamp)
+            .filter(|&time| time != SystemTime::UNIX_EPOCH),
+        days,
+    })
+}
+
+/// A timestamp in seconds since the Unix epoch.
+fn timestamp(value: &Value) -> Option<SystemTime> {
+    Some(SystemTime::UNIX_EPOCH + Duration::from_secs(value.as_u64()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_leaderboard() {
+        let json = r#"{
+            "owner_id": 1, "event": "2023", "day1_ts": 1701406800, "num_days": 25,
+            "members": {
+                "1": {"id": 1, "name": "owner", "stars": 1, "local_score": 2,
+                      "global_score": 0, "last_star_ts": 1701407000,
+                      "completion_day_level": {"1": {"1": {"get_star_ts": 1701407000, "star_index": 0}}}},
+                "2": {"id": 2, "name": null, "stars": 0, "local_score": 0,
+                      "global_score": 0, "last_star_ts": 0, "completion_day_level": {}},
+                "3": {"id": 3, "name": "fast", "stars": 2, "local_score": 6,
+                      "global_score": 100, "last_star_ts": 1701406900,
+                      "completion_day_level": {"1": {
+                          "1": {"get_star_ts": 1701406850, "star_index": 1},
+                          "2": {"get_star_ts": 1701406900, "star_index": 2}}}}
+            }
+        }"#;
+        let leaderboard = parse(json).unwrap();
+        assert_eq!((leaderboard.owner_id, leaderboard.year), (1, 2023));
+        let ids: Vec<_> = leaderboard.members.iter().map(|m| m.id).collect();
+        assert_eq!(ids, [3, 1, 2]);
+        let fast = &leaderboard.members[0];
+        assert_eq!(
+            fast.days[&1].part_two,
+            Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1701406900))
+        );
+        let anonymous = &leaderboard.members[2];
+        assert_eq!(anonymous.display_name(), "(anonymous user #2)");
+        assert_eq!(anonymous.last_star, None);
+        assert!(parse("{}").is_err());
+    }
+}
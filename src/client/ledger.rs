@@ -0,0 +1,244 @@
+use std::time::{Duration, SystemTime};
+
+use serde_json::{json, Value};
+
+use super::{Hint, SubmitOutcome};
+
+/// An answer submitted with [`AocClient::submit`](crate::AocClient::submit), as recorded in the
+/// answer history returned by [`AocClient::answer_history`](crate::AocClient::answer_history).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submission {
+    /// When the answer was submitted, to the second.
+    pub time: SystemTime,
+    /// The level (1 or 2) the answer was for.
+    pub level: u8,
+    pub answer: String,
+    pub outcome: SubmitOutcome,
+}
+
+impl Submission {
+    /// Whether Advent of Code checked the answer and found it wrong.
+    pub fn is_wrong(&self) -> bool {
+        matches!(self.outcome, SubmitOutcome::Incorrect { .. })
+    }
+
+    /// The submission as a line of JSON.
+    pub(crate) fn to_json_line(&self) -> String {
+        let secs = |time: SystemTime| {
+            time.duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs()
+        };
+        let mut record = json!({
+            "time": secs(self.time),
+            "level": self.level,
+            "answer": self.answer,
+        });
+        record["outcome"] = self.outcome.to_json_value();
+        let mut line = record.to_string();
+        line.push('\n');
+        line
+    }
+
+    /// Parse a line written by [`Submission::to_json_line`].
+    pub(crate) fn from_json_line(line: &str) -> Option<Submission> {
+        let record: Value = serde_json::from_str(line).ok()?;
+        let outcome = &record["outcome"];
+        let wait = outcome["wait"].as_u64().map(Duration::from_secs);
+        let outcome = match outcome["result"].as_str()? {
+            "correct" => SubmitOutcome::Correct,
+            "incorrect" => SubmitOutcome::Incorrect {
+                hint: match outcome["hint"].as_str() {
+                    Some("too_high") => Some(Hint::TooHigh),
+                    Some("too_low") => Some(Hint::TooLow),
+                    _ => None,
+                },
+                wait,
+            },
+            "rate_limited" => SubmitOutcome::RateLimited { wait: wait? },
+            "already_completed" => SubmitOutcome::AlreadyCompleted,
+            _ => return None,
+        };
+        Some(Submission {
+            time: SystemTime::UNIX_EPOCH + Duration::from_secs(record["time"].as_u64()?),
+            level: u8::try_from(record["level"].as_u64()?).ok()?,
+            answer: record["answer"].as_str()?.to_string(),
+            outcome,
+        })
+    }
+}
+
+impl SubmitOutcome {
+    /// The outcome as a JSON object, as recorded in the answer history, e.g.
+    /// `{"hint":"too_low","result":"incorrect","wait":60}`.
+    ///
+    /// `hint` and `wait` are `null` when Advent of Code didn't give them.
+    pub fn to_json(&self) -> String {
+        self.to_json_value().to_string()
+    }
+
+    fn to_json_value(&self) -> Value {
+        match *self {
+            SubmitOutcome::Correct => json!({"result": "correct"}),
+            SubmitOutcome::Incorrect { hint, wait } => json!({
+                "result": "incorrect",
+                "hint": hint.map(|hint| match hint {
+                    Hint::TooHigh => "too_high",
+                    Hint::TooLow => "too_low",
+                }),
+                "wait": wait.map(|wait| wait.as_secs()),
+            }),
+            SubmitOutcome::RateLimited { wait } => {
+                json!({"result": "rate_limited", "wait": wait.as_secs()})
+            }
+            SubmitOutcome::AlreadyCompleted => json!({"result": "already_completed"}),
+        }
+    }
+}
+
+/// The range a numeric answer must be in, as narrowed by the answers that Advent of Code found
+/// too high or too low.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoc_session::{aoc_session, AocClient, PuzzleId};
+///
+/// let client = AocClient::new(aoc_session().unwrap());
+/// let bounds = client.answer_bounds(PuzzleId::new(2023, 1).unwrap(), 1).unwrap();
+/// if bounds.rules_out("54304") {
+///     println!("No need to try that one");
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct AnswerBounds {
+    /// The greatest answer found too low; the answer is above it.
+    pub too_low: Option<i128>,
+    /// The least answer found too high; the answer is below it.
+    pub too_high: Option<i128>,
+}
+
+impl AnswerBounds {
+    /// The bounds of the given level, from the answer history of its day.
+    pub fn from_history<'a>(history: impl IntoIterator<Item = &'a Submission>, level: u8) -> Self {
+        let mut bounds = AnswerBounds::default();
+        for submission in history.into_iter().filter(|s| s.level == level) {
+            let SubmitOutcome::Incorrect { hint, .. } = submission.outcome else {
+                continue;
+            };
+            let Ok(answer) = submission.answer.parse::<i128>() else {
+                continue;
+            };
+            match hint {
+                Some(Hint::TooLow) => bounds.too_low = bounds.too_low.max(Some(answer)),
+                Some(Hint::TooHigh) => {
+                    bounds.too_high = Some(bounds.too_high.map_or(answer, |high| high.min(answer)))
+                }
+                None => {}
+            }
+        }
+        bounds
+    }
+
+    /// Which way the answer is off, if the bounds show it's wrong. Answers that aren't integers
+    /// are never ruled out.
+    pub fn check(&self, answer: &str) -> Option<Hint> {
+        let answer: i128 = answer.trim().parse().ok()?;
+        if self.too_high.is_some_and(|high| answer >= high) {
+            Some(Hint::TooHigh)
+        } else if self.too_low.is_some_and(|low| answer <= low) {
+            Some(Hint::TooLow)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the bounds show the answer is wrong.
+    pub fn rules_out(&self, answer: &str) -> bool {
+        self.check(answer).is_some()
+    }
+}
+
+/// Parse the answer history of a day, skipping lines that can't be parsed, e.g. a line cut
+/// short by a crash.
+pub(crate) fn parse(history: &str) -> Vec<Submission> {
+    history
+        .lines()
+        .filter_map(Submission::from_json_line)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_701_406_900);
+        let submissions = [
+            SubmitOutcome::Incorrect {
+                hint: Some(Hint::TooHigh),
+                wait: Some(Duration::from_secs(60)),
+            },
+            SubmitOutcome::Incorrect {
+                hint: None,
+                wait: None,
+            },
+            SubmitOutcome::RateLimited {
+                wait: Duration::from_secs(35),
+            },
+            SubmitOutcome::Correct,
+            SubmitOutcome::AlreadyCompleted,
+        ]
+        .map(|outcome| Submission {
+            time,
+            level: 1,
+            answer: "54\"304".to_string(),
+            outcome,
+        });
+        let history: String = submissions
+            .iter()
+            .map(Submission::to_json_line)
+            .chain(["{\"time\": 1".to_string()])
+            .collect();
+        assert_eq!(parse(&history), submissions);
+        assert!(submissions[0].is_wrong());
+        assert!(!submissions[2].is_wrong());
+        assert_eq!(
+            submissions[0].outcome.to_json(),
+            r#"{"hint":"too_high","result":"incorrect","wait":60}"#
+        );
+    }
+
+    #[test]
+    fn bounds() {
+        let submission = |level, answer: &str, hint| Submission {
+            time: SystemTime::UNIX_EPOCH,
+            level,
+            answer: answer.to_string(),
+            outcome: SubmitOutcome::Incorrect { hint, wait: None },
+        };
+        let history = [
+            submission(1, "100", Some(Hint::TooHigh)),
+            submission(1, "20", Some(Hint::TooLow)),
+            submission(1, "80", Some(Hint::TooHigh)),
+            submission(1, "10", Some(Hint::TooLow)),
+            submission(1, "50", None),
+            submission(1, "abc", Some(Hint::TooHigh)),
+            submission(2, "30", Some(Hint::TooLow)),
+        ];
+        let bounds = AnswerBounds::from_history(&history, 1);
+        assert_eq!(
+            bounds,
+            AnswerBounds {
+                too_low: Some(20),
+                too_high: Some(80)
+            }
+        );
+        assert_eq!(bounds.check("80"), Some(Hint::TooHigh));
+        assert_eq!(bounds.check(" 20\n"), Some(Hint::TooLow));
+        assert_eq!(bounds.check("50"), None);
+        assert!(!bounds.rules_out("abc"));
+        assert_eq!(AnswerBounds::from_history(&history, 2).too_high, None);
+    }
+}
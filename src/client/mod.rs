@@ -0,0 +1,984 @@
+//! A client for the Advent of Code website, authenticated with an [`AocSession`].
+//!
+//! The client uses the system `curl` executable as its HTTP backend, so `curl` must be on the
+//! `PATH`.
+
+mod account;
+mod builder;
+mod cache;
+mod compare;
+mod html;
+mod http;
+mod integrity;
+mod leaderboard;
+mod ledger;
+mod proxy;
+mod puzzle;
+mod rate_limit;
+mod refresh;
+mod retry;
+mod sha256;
+mod stats;
+mod submit;
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use crate::session_cache::SessionCache;
+use crate::{AocSession, Config, Error, PuzzleId, PuzzleYear, Result};
+pub use account::Account;
+pub use builder::AocClientBuilder;
+use cache::{InputCache, Validators};
+pub use compare::{compare_sessions, AccountStatus, ComparedSession, SessionComparison};
+pub use integrity::InputIntegrity;
+pub use leaderboard::{DayCompletion, Leaderboard, Member};
+pub use ledger::{AnswerBounds, Submission};
+use proxy::ProxySettings;
+pub use puzzle::Puzzle;
+use rate_limit::RateLimiter;
+pub use refresh::ClientEvent;
+use refresh::Refresh;
+pub use retry::RetryPolicy;
+pub use stats::{DayTimes, PartTime, PersonalTimes, Progress};
+pub use submit::{Hint, SubmitOutcome};
+
+/// The URL of the Advent of Code website, which clients send their requests to unless given
+/// another one with [`AocClientBuilder::base_url`].
+pub const BASE_URL: &str = "https://adventofcode.com";
+
+pub(crate) use crate::DEFAULT_USER_AGENT;
+
+/// A client for the Advent of Code website.
+///
+/// Requests are spaced out by at least a second (see [`AocClient::with_min_interval`]), and the
+/// client backs off exponentially while adventofcode.com answers with `429 Too Many Requests` or
+/// a server error.
+///
+/// When Advent of Code rejects the session, e.g. because it expired, a client built with
+/// [`AocClientBuilder::refresh_with`] or from a [`Config`] looks the session up again, once per
+/// rejection, and sends the request again if it found a new one.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoc_session::{aoc_session, AocClient, PuzzleId};
+///
+/// let client = AocClient::new(aoc_session().unwrap());
+/// let input = client.get_input(PuzzleId::new(2023, 1).unwrap()).unwrap();
+/// println!("{input}");
+/// ```
+#[derive(Debug)]
+pub struct AocClient {
+    session: Mutex<AocSession>,
+    base_url: String,
+    cache: Option<InputCache>,
+    user_agent: String,
+    timeout: Option<Duration>,
+    proxy: ProxySettings,
+    offline: bool,
+    retry: RetryPolicy,
+    limiter: RateLimiter,
+    refresh: Refresh,
+}
+
+impl AocClient {
+    /// Create a client authenticated with the given session.
+    ///
+    /// Puzzle inputs are cached in the platform cache directory, e.g.
+    /// `~/.cache/aoc-session/<year>/<day>.txt` on Linux. Prefer [`AocClient::builder`] to
+    /// identify yourself in the `User-Agent` header.
+    pub fn new(session: AocSession) -> Self {
+        Self::builder(session).build()
+    }
+
+    /// Start building a client authenticated with the given session.
+    pub fn builder(session: AocSession) -> AocClientBuilder {
+        AocClientBuilder::new(session)
+    }
+
+    /// Create a client authenticated with the given session, honoring the cache and HTTP settings
+    /// of the configuration. A rejected session is replaced from the sources of
+    /// [`Config::session_builder`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use aoc_session::{AocClient, Config};
+    ///
+    /// let config = Config::load().unwrap();
+    /// let session = config.session_builder().resolve().unwrap();
+    /// let client = AocClient::from_config(session, &config);
+    /// ```
+    pub fn from_config(session: AocSession, config: &Config) -> Self {
+        Self::builder(session).config(config).build()
+    }
+
+    /// Create a client that never goes online, serving inputs, puzzles, and leaderboards from the
+    /// platform cache directory only, e.g. on a train. Anything that isn't cached fails with
+    /// [`Error::Offline`], as does anything that needs Advent of Code, like submitting an answer.
+    ///
+    /// Cached leaderboards are served however old they are. See
+    /// [`AocClientBuilder::offline`] to go offline with another cache directory.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use aoc_session::{AocClient, Error, PuzzleId};
+    ///
+    /// match AocClient::offline().get_input(PuzzleId::new(2023, 1).unwrap()) {
+    ///     Ok(input) => println!("{input}"),
+    ///     Err(Error::Offline(_)) => println!("Download the input before leaving"),
+    ///     Err(e) => println!("{e}"),
+    /// }
+    /// ```
+    pub fn offline() -> Self {
+        // No request is sent, so no session is needed.
+        Self::builder(AocSession::unchecked(String::new()))
+            .offline()
+            .build()
+    }
+
+    /// Whether the client serves everything from the cache, without going online.
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    /// Cache puzzle inputs in the given directory instead.
+    pub fn with_cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache = Some(InputCache::new(dir.into()));
+        self
+    }
+
+    /// Don't cache puzzle inputs at all.
+    pub fn without_cache(mut self) -> Self {
+        self.cache = None;
+        self
+    }
+
+    /// Wait at least this long between two requests instead of a second.
+    ///
+    /// Inputs and puzzles served from the cache don't count as requests.
+    pub fn with_min_interval(mut self, min_interval: Duration) -> Self {
+        self.limiter = RateLimiter::new(min_interval);
+        self
+    }
+
+    /// The minimum delay between two requests.
+    pub fn min_interval(&self) -> Duration {
+        self.limiter.min_interval()
+    }
+
+    /// The URL the requests are sent to, without a trailing slash.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// The `User-Agent` sent with every request.
+    pub fn user_agent(&self) -> &str {
+        &self.user_agent
+    }
+
+    /// The session the client is authenticated with, which changes when a rejected session is
+    /// replaced.
+    pub fn session(&self) -> AocSession {
+        self.current_session().clone()
+    }
+
+    fn current_session(&self) -> std::sync::MutexGuard<'_, AocSession> {
+        self.session.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The directory where puzzle inputs are cached, if any.
+    pub fn cache_dir(&self) -> Option<&Path> {
+        self.cache.as_ref().map(InputCache::dir)
+    }
+
+    /// Get the puzzle input of the given day.
+    ///
+    /// A cached input is returned as is. Otherwise the input is downloaded and cached, so that
+    /// each input is downloaded only once, as the Advent of Code automation guidelines require.
+    pub fn get_input(&self, puzzle: PuzzleId) -> Result<String> {
+        if let Some(input) = self
+            .cache
+            .as_ref()
+            .map(|c| c.read(puzzle))
+            .transpose()?
+            .flatten()
+        {
+            return Ok(input);
+        }
+        self.refresh_input(puzzle)
+    }
+
+    /// Download the puzzle input of the given day even if it's cached, and update the cache.
+    ///
+    /// This is an escape hatch for a corrupted cache; puzzle inputs never change.
+    pub fn refresh_input(&self, puzzle: PuzzleId) -> Result<String> {
+        let input = self.get(&format!("{}/input", path(puzzle)))?;
+        if let Some(cache) = &self.cache {
+            cache.write(puzzle, &input)?;
+        }
+        Ok(input)
+    }
+
+    /// Download the puzzle input of the given day and compare it with the cached one, to catch
+    /// cached inputs that were edited by accident or corrupted, e.g. when the cache is shared
+    /// across machines.
+    ///
+    /// The cache is left alone; call [`refresh_input`](AocClient::refresh_input) to replace a
+    /// cached input that isn't intact.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use aoc_session::{aoc_session, AocClient, PuzzleYear};
+    ///
+    /// let client = AocClient::new(aoc_session().unwrap());
+    /// for puzzle in PuzzleYear::new(2023).unwrap().puzzles() {
+    ///     let integrity = client.verify_input(puzzle).unwrap();
+    ///     if !integrity.is_intact() {
+    ///         println!("{puzzle}: {integrity}");
+    ///     }
+    /// }
+    /// ```
+    pub fn verify_input(&self, puzzle: PuzzleId) -> Result<InputIntegrity> {
+        let Some(cache) = &self.cache else {
+            return Ok(InputIntegrity::NotCached);
+        };
+        let Some(cached) = cache.read(puzzle)? else {
+            return Ok(InputIntegrity::NotCached);
+        };
+        let input = self.get(&format!("{}/input", path(puzzle)))?;
+        if cached == input {
+            return Ok(InputIntegrity::Intact);
+        }
+        let digest = cache.read_digest(puzzle)?;
+        if digest.is_some_and(|digest| digest != sha256::hex_digest(cached.as_bytes())) {
+            Ok(InputIntegrity::Edited)
+        } else {
+            Ok(InputIntegrity::Corrupted)
+        }
+    }
+
+    /// Block until the puzzle of the given day unlocks, at midnight US/Eastern (see
+    /// [`schedule::unlock_time`](crate::schedule::unlock_time)). Returns right away if it's
+    /// already unlocked.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use aoc_session::{aoc_session, AocClient, PuzzleId};
+    ///
+    /// let client = AocClient::new(aoc_session().unwrap());
+    /// let puzzle = PuzzleId::new(2023, 1).unwrap();
+    /// client.wait_for_unlock(puzzle);
+    /// let input = client.get_input(puzzle).unwrap();
+    /// ```
+    pub fn wait_for_unlock(&self, puzzle: PuzzleId) {
+        crate::schedule::sleep_until_unlock(puzzle.year().get(), puzzle.day().get());
+    }
+
+    /// Download the inputs of every unlocked day of the year into `dir`, as `<dir>/<day>.txt`,
+    /// and return their paths in order.
+    ///
+    /// The unlocked days are read from the calendar page of the year. Files that already exist
+    /// are left alone, and inputs are taken from the cache when possible, so running it again
+    /// only downloads the days unlocked since.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use aoc_session::{aoc_session, AocClient, PuzzleYear};
+    ///
+    /// let client = AocClient::new(aoc_session().unwrap());
+    /// let year = PuzzleYear::new(2023).unwrap();
+    /// let paths = client.download_year_inputs(year, "inputs/2023").unwrap();
+    /// println!("{} inputs ready", paths.len());
+    /// ```
+    pub fn download_year_inputs(
+        &self,
+        year: PuzzleYear,
+        dir: impl AsRef<Path>,
+    ) -> Result<Vec<PathBuf>> {
+        let days = self.progress(year)?.stars.into_keys();
+        let puzzles = days
+            .map(|day| PuzzleId::new(year.get(), day))
+            .collect::<Result<Vec<_>>>()?;
+        self.download_inputs(puzzles, dir.as_ref())
+    }
+
+    fn download_inputs(
+        &self,
+        puzzles: impl IntoIterator<Item = PuzzleId>,
+        dir: &Path,
+    ) -> Result<Vec<PathBuf>> {
+        puzzles
+            .into_iter()
+            .map(|puzzle| {
+                let path = dir.join(format!("{}.txt", puzzle.day()));
+                if !path.exists() {
+                    crate::fs::write_atomically(&path, &self.get_input(puzzle)?)?;
+                }
+                Ok(path)
+            })
+            .collect()
+    }
+
+    /// Get the description of the given puzzle, e.g. to read it offline or to put it in the
+    /// README of a solution.
+    ///
+    /// The description is cached along with the inputs. A cached description that lacks part
+    /// two is downloaded again, since part two appears once part one is solved.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use aoc_session::{aoc_session, AocClient, PuzzleId};
+    ///
+    /// let client = AocClient::new(aoc_session().unwrap());
+    /// let puzzle = client.get_puzzle(PuzzleId::new(2023, 1).unwrap()).unwrap();
+    /// std::fs::write("README.md", puzzle.markdown()).unwrap();
+    /// ```
+    pub fn get_puzzle(&self, puzzle: PuzzleId) -> Result<Puzzle> {
+        let cached = self
+            .cache
+            .as_ref()
+            .map(|c| c.read_puzzle(puzzle))
+            .transpose()?
+            .flatten()
+            .and_then(|html| Puzzle::from_page(puzzle, &html));
+        let cached = match cached {
+            Some(puzzle) if puzzle.is_complete() || self.offline => return Ok(puzzle),
+            cached => cached,
+        };
+        // An incomplete description is only downloaded again if the page changed.
+        let validators = match (&self.cache, &cached) {
+            (Some(cache), Some(_)) => cache.read_puzzle_validators(puzzle),
+            _ => Validators::default(),
+        };
+        let path = path(puzzle);
+        let (page, validators) = match (self.get_if_modified(&path, &validators)?, cached) {
+            (Some(modified), _) => modified,
+            (None, Some(cached)) => return Ok(cached),
+            (None, None) => self.get(&path).map(|page| (page, Validators::default()))?,
+        };
+        let description = Puzzle::from_page(puzzle, &page).ok_or_else(|| {
+            Error::UnexpectedResponse("the puzzle page has no description".to_string())
+        })?;
+        if let Some(cache) = &self.cache {
+            cache.write_puzzle(puzzle, &description.html())?;
+            cache.write_puzzle_validators(puzzle, &validators)?;
+        }
+        Ok(description)
+    }
+
+    /// Get the private leaderboard with the given ID, which is the ID of its owner, as shown in
+    /// its URL.
+    ///
+    /// Advent of Code asks not to fetch a leaderboard more often than every 15 minutes, so the
+    /// leaderboard is cached for that long. Without a cache directory, every call fetches it.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use aoc_session::{aoc_session, AocClient, PuzzleYear};
+    ///
+    /// let client = AocClient::new(aoc_session().unwrap());
+    /// let year = PuzzleYear::new(2023).unwrap();
+    /// let leaderboard = client.private_leaderboard(year, 123456).unwrap();
+    /// for member in &leaderboard.members {
+    ///     println!("{} {}", member.local_score, member.display_name());
+    /// }
+    /// ```
+    pub fn private_leaderboard(&self, year: PuzzleYear, id: u64) -> Result<Leaderboard> {
+        let cached = self
+            .cache
+            .as_ref()
+            .map(|c| {
+                let max_age = if self.offline {
+                    Duration::MAX
+                } else {
+                    leaderboard::POLL_INTERVAL
+                };
+                c.read_leaderboard(year, id, max_age)
+            })
+            .transpose()?
+            .flatten();
+        if let Some(json) = cached {
+            return leaderboard::parse(&json).map_err(Error::UnexpectedResponse);
+        }
+        // A stale leaderboard is only downloaded again if it changed.
+        let stale = self
+            .cache
+            .as_ref()
+            .map(|c| c.read_leaderboard(year, id, Duration::MAX))
+            .transpose()?
+            .flatten();
+        let validators = match (&self.cache, &stale) {
+            (Some(cache), Some(_)) => cache.read_leaderboard_validators(year, id),
+            _ => Validators::default(),
+        };
+        let path = format!("/{year}/leaderboard/private/view/{id}.json");
+        let (json, validators) = match (self.get_if_modified(&path, &validators)?, stale) {
+            (Some(modified), _) => modified,
+            (None, Some(json)) => (json, validators),
+            (None, None) => self.get(&path).map(|json| (json, Validators::default()))?,
+        };
+        // Inaccessible leaderboards are redirected away from, which fails with a status error.
+        let leaderboard = leaderboard::parse(&json).map_err(Error::UnexpectedResponse)?;
+        if let Some(cache) = &self.cache {
+            // Rewritten even if unchanged, so that it counts as fresh for another 15 minutes.
+            cache.write_leaderboard(year, id, &json)?;
+            cache.write_leaderboard_validators(year, id, &validators)?;
+        }
+        Ok(leaderboard)
+    }
+
+    /// Get the stars earned on each unlocked day of the year, from its calendar page.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use aoc_session::{aoc_session, AocClient, PuzzleYear};
+    ///
+    /// let client = AocClient::new(aoc_session().unwrap());
+    /// for day in client.progress(PuzzleYear::new(2023).unwrap()).unwrap().unsolved() {
+    ///     println!("Day {day} still needs solving");
+    /// }
+    /// ```
+    pub fn progress(&self, year: PuzzleYear) -> Result<Progress> {
+        let page = self.get(&format!("/{year}"))?;
+        stats::parse_progress(year.get(), &page).map_err(Error::UnexpectedResponse)
+    }
+
+    /// Get the personal statistics of the year: when each part was solved, relative to the
+    /// release of the puzzle, along with the ranks and scores.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use aoc_session::{aoc_session, AocClient, PuzzleYear};
+    ///
+    /// let client = AocClient::new(aoc_session().unwrap());
+    /// let stats = client.personal_times(PuzzleYear::new(2023).unwrap()).unwrap();
+    /// for (day, times) in &stats.days {
+    ///     if let Some(time) = times.part_two.and_then(|part| part.time) {
+    ///         println!("Day {day}: {}s", time.as_secs());
+    ///     }
+    /// }
+    /// ```
+    pub fn personal_times(&self, year: PuzzleYear) -> Result<PersonalTimes> {
+        let page = self.get(&format!("/{year}/leaderboard/self"))?;
+        stats::parse_personal_times(year.get(), &page).map_err(Error::UnexpectedResponse)
+    }
+
+    /// Submit the answer for the given level (1 or 2) of the given day.
+    ///
+    /// When Advent of Code asks to wait before answering again, the end of the wait is
+    /// remembered in the cache directory, and submissions for the day fail with
+    /// [`Error::CooldownActive`] until then, without contacting Advent of Code.
+    ///
+    /// Every answer is also recorded in the [answer history](AocClient::answer_history) of the
+    /// day. An answer already found wrong for the level isn't submitted again: its recorded
+    /// outcome is returned instead, without the wait. Likewise, a numeric answer outside of the
+    /// [bounds](AocClient::answer_bounds) set by answers found too high or too low is rejected
+    /// right away.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use aoc_session::{aoc_session, AocClient, PuzzleId, SubmitOutcome};
+    ///
+    /// let client = AocClient::new(aoc_session().unwrap());
+    /// match client.submit(PuzzleId::new(2023, 1).unwrap(), 1, 54304).unwrap() {
+    ///     SubmitOutcome::Correct => println!("Got a star!"),
+    ///     outcome => println!("{outcome}"),
+    /// }
+    /// ```
+    pub fn submit(
+        &self,
+        puzzle: PuzzleId,
+        level: u8,
+        answer: impl ToString,
+    ) -> Result<SubmitOutcome> {
+        if let Some(cache) = &self.cache {
+            let remaining = cache
+                .read_cooldown(puzzle)?
+                .and_then(|until| until.duration_since(SystemTime::now()).ok());
+            if let Some(remaining) = remaining {
+                return Err(Error::CooldownActive { remaining });
+            }
+        }
+        let answer = answer.to_string();
+        let answer = answer.trim();
+        let history = self.answer_history(puzzle)?;
+        if let Some(hint) = AnswerBounds::from_history(&history, level).check(answer) {
+            return Ok(SubmitOutcome::Incorrect {
+                hint: Some(hint),
+                wait: None,
+            });
+        }
+        let known_wrong = history.into_iter().find(|submission| {
+            submission.level == level && submission.answer == answer && submission.is_wrong()
+        });
+        if let Some(Submission {
+            outcome: SubmitOutcome::Incorrect { hint, .. },
+            ..
+        }) = known_wrong
+        {
+            return Ok(SubmitOutcome::Incorrect { hint, wait: None });
+        }
+        let url = format!("{}{}/answer", self.base_url, path(puzzle));
+        let level_field = level.to_string();
+        let mut request = self.request(&url);
+        request.form = vec![("level", &level_field), ("answer", answer)];
+        let page = self.send(&request)?.ok(&url)?;
+        let outcome = submit::parse(&page).map_err(Error::UnexpectedResponse)?;
+        if let Some(cache) = &self.cache {
+            let submission = Submission {
+                time: SystemTime::now(),
+                level,
+                answer: answer.to_string(),
+                outcome: outcome.clone(),
+            };
+            cache.append_answer(puzzle, &submission.to_json_line())?;
+            match outcome {
+                SubmitOutcome::Incorrect {
+                    wait: Some(wait), ..
+                }
+                | SubmitOutcome::RateLimited { wait } => {
+                    cache.write_cooldown(puzzle, SystemTime::now() + wait)?
+                }
+                _ => cache.clear_cooldown(puzzle)?,
+            }
+        }
+        Ok(outcome)
+    }
+
+    /// The answers submitted for the given day with [`AocClient::submit`], oldest first.
+    ///
+    /// The history is kept in the cache directory; without one, it's always empty.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use aoc_session::{aoc_session, AocClient, PuzzleId};
+    ///
+    /// let client = AocClient::new(aoc_session().unwrap());
+    /// for submission in client.answer_history(PuzzleId::new(2023, 1).unwrap()).unwrap() {
+    ///     println!("{}: {}", submission.answer, submission.outcome);
+    /// }
+    /// ```
+    pub fn answer_history(&self, puzzle: PuzzleId) -> Result<Vec<Submission>> {
+        let history = match &self.cache {
+            Some(cache) => cache.read_answers(puzzle)?,
+            None => None,
+        };
+        Ok(history.as_deref().map(ledger::parse).unwrap_or_default())
+    }
+
+    /// The range the answer of the given level must be in, from the answers of the
+    /// [history](AocClient::answer_history) that were found too high or too low.
+    pub fn answer_bounds(&self, puzzle: PuzzleId, level: u8) -> Result<AnswerBounds> {
+        Ok(AnswerBounds::from_history(
+            &self.answer_history(puzzle)?,
+            level,
+        ))
+    }
+
+    /// Check that Advent of Code still accepts the session.
+    ///
+    /// See [`AocSession::validate`].
+    pub fn validate_session(&self) -> Result<()> {
+        self.front_page().map(drop)
+    }
+
+    /// The account the session is logged in to, e.g. to check that the session picked among
+    /// several browsers is the right one.
+    ///
+    /// Fails with [`Error::SessionRejected`] if Advent of Code doesn't accept the session.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use aoc_session::{aoc_session, AocClient};
+    ///
+    /// let client = AocClient::new(aoc_session().unwrap());
+    /// println!("Logged in as {}", client.whoami().unwrap());
+    /// ```
+    pub fn whoami(&self) -> Result<Account> {
+        Account::from_page(&self.front_page()?).ok_or_else(|| {
+            Error::UnexpectedResponse("the page doesn't name the account".to_string())
+        })
+    }
+
+    /// The front page of Advent of Code, if it's logged in.
+    fn front_page(&self) -> Result<String> {
+        let url = format!("{}/", self.base_url);
+        let result = self
+            .send(&self.request(&url))
+            .and_then(|response| check_front_page(response, &url));
+        forget_if_rejected(&self.session(), self.domain(), result)
+    }
+
+    fn get(&self, path: &str) -> Result<String> {
+        let url = format!("{}{path}", self.base_url);
+        let response = self.send(&self.request(&url))?;
+        response.ok(&url)
+    }
+
+    /// Get the page unless it's unchanged since it was served with the validators, in which case
+    /// `None` is returned. The page comes with its own validators.
+    fn get_if_modified(
+        &self,
+        path: &str,
+        validators: &Validators,
+    ) -> Result<Option<(String, Validators)>> {
+        let url = format!("{}{path}", self.base_url);
+        let mut request = self.request(&url);
+        request.headers.extend(validators.headers());
+        let response = self.send(&request)?;
+        if response.status == 304 {
+            return Ok(None);
+        }
+        let validators = Validators::of(&response);
+        response.ok(&url).map(|page| Some((page, validators)))
+    }
+
+    /// Send the request once the rate limiter allows it, and again with a new session if Advent
+    /// of Code rejected the session and a new one is found.
+    fn send(&self, request: &http::Request) -> Result<http::Response> {
+        let response = self.send_once(request)?;
+        if !refresh::is_rejection(&response) {
+            return Ok(response);
+        }
+        match self.refresh_session(&request.headers)? {
+            Some(header) => self.send_once(&http::Request {
+                url: request.url,
+                // The cookie comes first, before any conditional headers.
+                headers: std::iter::once(header)
+                    .chain(request.headers.iter().skip(1).cloned())
+                    .collect(),
+                user_agent: request.user_agent,
+                timeout: request.timeout,
+                form: request.form.clone(),
+                proxy: request.proxy.clone(),
+            }),
+            None => Ok(response),
+        }
+    }
+
+    /// Send the request once the rate limiter allows it, and again after a transient failure as
+    /// the retry policy allows. Submissions, which are `POST`s, are only sent once.
+    fn send_once(&self, request: &http::Request) -> Result<http::Response> {
+        if self.offline {
+            return Err(Error::Offline(request.url.to_string()));
+        }
+        let max_attempts = if request.form.is_empty() {
+            self.retry.max_attempts().max(1)
+        } else {
+            1
+        };
+        let mut attempt = 1;
+        loop {
+            self.limiter.acquire();
+            let last = attempt >= max_attempts;
+            match request.attempt() {
+                http::Attempt::Response(response) => {
+                    self.limiter.record(response.status);
+                    if last || !response.is_transient_failure() {
+                        return Ok(response);
+                    }
+                }
+                http::Attempt::Transient(_) if !last => {}
+                http::Attempt::Transient(e) | http::Attempt::Failed(e) => return Err(e),
+            }
+            std::thread::sleep(self.retry.jittered_delay(attempt));
+            attempt += 1;
+        }
+    }
+
+    /// The host of the base URL, whose session cookie the client sends.
+    fn domain(&self) -> &str {
+        crate::config::host(&self.base_url).unwrap_or(crate::source::DOMAIN)
+    }
+
+    /// Replace the session that the request was rejected with, and return the cookie header of
+    /// the new one, if any.
+    fn refresh_session(&self, sent: &[String]) -> Result<Option<String>> {
+        let Some(provider) = &self.refresh.provider else {
+            return Ok(None);
+        };
+        // The lock is held during the lookup, so concurrent rejections only look up once.
+        let mut session = self.current_session();
+        let header = session.to_cookie_header();
+        if !sent.contains(&header) {
+            // Another request already replaced it.
+            return Ok(Some(header));
+        }
+        // Otherwise the cache of `AocSessionBuilder::persist` would hand the same one back.
+        if let Some(cache) = SessionCache::for_domain(self.domain()) {
+            cache.forget(&session)?;
+        }
+        match provider.provide() {
+            Ok(new) if new != *session => {
+                *session = new;
+                self.refresh.emit(ClientEvent::SessionRefreshed);
+                Ok(Some(session.to_cookie_header()))
+            }
+            Ok(_) => {
+                self.refresh
+                    .emit(ClientEvent::SessionRefreshFailed(Error::SessionRejected));
+                Ok(None)
+            }
+            Err(e) => {
+                self.refresh.emit(ClientEvent::SessionRefreshFailed(e));
+                Ok(None)
+            }
+        }
+    }
+
+    fn request<'a>(&'a self, url: &'a str) -> http::Request<'a> {
+        http::Request {
+            url,
+            headers: vec![self.current_session().to_cookie_header()],
+            user_agent: &self.user_agent,
+            timeout: self.timeout,
+            form: Vec::new(),
+            proxy: self.proxy.for_url(url),
+        }
+    }
+}
+
+impl AocSession {
+    /// Check that Advent of Code still accepts the session, e.g. that it's not expired. The
+    /// request goes through the proxy named by the environment variables, if any (see
+    /// [`AocClientBuilder::proxy`]).
+    ///
+    /// Returns [`Error::SessionRejected`] if Advent of Code treats the request as anonymous,
+    /// and another error if the check itself fails, e.g. because of the network. A rejected
+    /// session is also forgotten by the cache of
+    /// [`AocSessionBuilder::persist`](crate::AocSessionBuilder::persist).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use aoc_session::{aoc_session, Error};
+    ///
+    /// match aoc_session().unwrap().validate() {
+    ///     Ok(()) => println!("The session is valid"),
+    ///     Err(Error::SessionRejected) => println!("Log in to Advent of Code again"),
+    ///     Err(e) => println!("Couldn't check the session: {e}"),
+    /// }
+    /// ```
+    pub fn validate(&self) -> Result<()> {
+        let url = format!("{BASE_URL}/");
+        let result = validate(http::Request {
+            url: &url,
+            headers: vec![self.to_cookie_header()],
+            user_agent: DEFAULT_USER_AGENT,
+            timeout: None,
+            form: Vec::new(),
+            proxy: ProxySettings::default().for_url(&url),
+        });
+        forget_if_rejected(self, crate::source::DOMAIN, result)
+    }
+}
+
+fn validate(request: http::Request) -> Result<()> {
+    check_front_page(request.send()?, request.url).map(drop)
+}
+
+/// Remove a rejected session from the cache of
+/// [`AocSessionBuilder::persist`](crate::AocSessionBuilder::persist), so that the next run
+/// looks for a fresh one.
+fn forget_if_rejected<T>(session: &AocSession, domain: &str, result: Result<T>) -> Result<T> {
+    if let Err(Error::SessionRejected) = result {
+        if let Some(cache) = SessionCache::for_domain(domain) {
+            cache.forget(session)?;
+        }
+    }
+    result
+}
+
+/// The page, if it's logged in.
+fn check_front_page(response: http::Response, url: &str) -> Result<String> {
+    // Malformed session cookies are rejected outright.
+    if response.status == 400 {
+        return Err(Error::SessionRejected);
+    }
+    let page = response.ok(url)?;
+    if page.contains("/auth/logout") {
+        Ok(page)
+    } else if page.contains("/auth/login") {
+        Err(Error::SessionRejected)
+    } else {
+        Err(Error::UnexpectedResponse(
+            "the page has neither a login nor a logout link".to_string(),
+        ))
+    }
+}
+
+/// The path of the page of the puzzle, e.g. `/2023/day/1`.
+fn path(puzzle: PuzzleId) -> String {
+    format!("/{}/day/{}", puzzle.year(), puzzle.day())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn puzzle(year: u16, day: u8) -> PuzzleId {
+        PuzzleId::new(year, day).unwrap()
+    }
+
+    #[test]
+    fn cached_input_is_not_downloaded() {
+        let dir = std::env::temp_dir().join("aoc-session-client-cache-test");
+        InputCache::new(dir.clone())
+            .write(puzzle(2015, 2), "2x3x4\n")
+            .unwrap();
+        // The session is bogus, so a download would fail.
+        let client = AocClient::new(AocSession::new("0")).with_cache_dir(dir);
+        assert_eq!(client.get_input(puzzle(2015, 2)).unwrap(), "2x3x4\n");
+    }
+
+    #[test]
+    fn known_wrong_answers_are_not_resubmitted() {
+        let dir = std::env::temp_dir().join("aoc-session-ledger-test");
+        let _ = std::fs::remove_dir_all(&dir);
+        let wrong = Submission {
+            time: SystemTime::UNIX_EPOCH,
+            level: 1,
+            answer: "42".to_string(),
+            outcome: SubmitOutcome::Incorrect {
+                hint: Some(Hint::TooLow),
+                wait: Some(Duration::from_secs(60)),
+            },
+        };
+        InputCache::new(dir.clone())
+            .append_answer(puzzle(2015, 1), &wrong.to_json_line())
+            .unwrap();
+        // The session is bogus, so a submission would fail.
+        let client = AocClient::new(AocSession::new("0")).with_cache_dir(dir);
+        assert_eq!(client.answer_history(puzzle(2015, 1)).unwrap(), [wrong]);
+        assert_eq!(
+            client.submit(puzzle(2015, 1), 1, " 42\n").unwrap(),
+            SubmitOutcome::Incorrect {
+                hint: Some(Hint::TooLow),
+                wait: None
+            }
+        );
+        assert_eq!(
+            client.submit(puzzle(2015, 1), 1, 41).unwrap(),
+            SubmitOutcome::Incorrect {
+                hint: Some(Hint::TooLow),
+                wait: None
+            }
+        );
+    }
+
+    #[test]
+    fn offline_client_only_serves_the_cache() {
+        let dir = std::env::temp_dir().join("aoc-session-offline-test");
+        let _ = std::fs::remove_dir_all(&dir);
+        InputCache::new(dir.clone())
+            .write(puzzle(2015, 3), "^>v<\n")
+            .unwrap();
+        let client = AocClient::builder(AocSession::new("25a16c74"))
+            .cache_dir(&dir)
+            .offline()
+            .build();
+        assert!(client.is_offline());
+        assert_eq!(client.get_input(puzzle(2015, 3)).unwrap(), "^>v<\n");
+        assert!(matches!(
+            client.get_input(puzzle(2015, 4)),
+            Err(Error::Offline(url)) if url.ends_with("/2015/day/4/input")
+        ));
+        assert!(matches!(
+            client.verify_input(puzzle(2015, 3)),
+            Err(Error::Offline(_))
+        ));
+        assert!(matches!(client.whoami(), Err(Error::Offline(_))));
+    }
+
+    #[test]
+    fn downloaded_inputs_are_kept() {
+        let cache_dir = std::env::temp_dir().join("aoc-session-bulk-cache-test");
+        let dir = std::env::temp_dir().join("aoc-session-bulk-test");
+        let _ = std::fs::remove_dir_all(&dir);
+        InputCache::new(cache_dir.clone())
+            .write(puzzle(2015, 1), "(()\n")
+            .unwrap();
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("2.txt"), "1x1x1\n").unwrap();
+        // The session is bogus, so a download would fail.
+        let client = AocClient::new(AocSession::new("0")).with_cache_dir(cache_dir);
+        let paths = client
+            .download_inputs([puzzle(2015, 1), puzzle(2015, 2)], &dir)
+            .unwrap();
+        assert_eq!(paths, [dir.join("1.txt"), dir.join("2.txt")]);
+        assert_eq!(std::fs::read_to_string(&paths[0]).unwrap(), "(()\n");
+        assert_eq!(std::fs::read_to_string(&paths[1]).unwrap(), "1x1x1\n");
+    }
+
+    #[test]
+    fn rejected_session_is_replaced_once() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+        use std::sync::Arc;
+
+        let lookups = Arc::new(AtomicU32::new(0));
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let client = {
+            let lookups = Arc::clone(&lookups);
+            let events = Arc::clone(&events);
+            AocClient::builder(AocSession::new("25a16c74"))
+                .refresh_with(move || {
+                    lookups.fetch_add(1, Ordering::SeqCst);
+                    Ok(AocSession::new("53616c74"))
+                })
+                .on_event(move |event| events.lock().unwrap().push(format!("{event:?}")))
+                .build()
+        };
+        let old = vec![AocSession::new("25a16c74").to_cookie_header()];
+        let new = AocSession::new("53616c74").to_cookie_header();
+        assert_eq!(client.refresh_session(&old).unwrap(), Some(new.clone()));
+        assert_eq!(client.session().expose_secret(), "53616c74");
+        // A request sent with the old session before the refresh doesn't look it up again.
+        assert_eq!(client.refresh_session(&old).unwrap(), Some(new.clone()));
+        assert_eq!(lookups.load(Ordering::SeqCst), 1);
+        // The sources only hold the rejected session.
+        assert_eq!(client.refresh_session(&[new]).unwrap(), None);
+        assert_eq!(
+            *events.lock().unwrap(),
+            ["SessionRefreshed", "SessionRefreshFailed(SessionRejected)"]
+        );
+        let client = AocClient::new(AocSession::new("25a16c74"));
+        assert_eq!(client.refresh_session(&old).unwrap(), None);
+    }
+
+    #[test]
+    fn front_page_tells_whether_logged_in() {
+        let page = |status, body: &str| http::Response {
+            status,
+            body: body.to_string(),
+            ..http::Response::default()
+        };
+        let logged_in = r#"<a href="/2023/auth/logout">[Log Out]</a>"#;
+        let logged_out = r#"<a href="/2023/auth/login">[Log In]</a>"#;
+        assert!(check_front_page(page(200, logged_in), BASE_URL).is_ok());
+        assert!(matches!(
+            check_front_page(page(200, logged_out), BASE_URL),
+            Err(Error::SessionRejected)
+        ));
+        assert!(matches!(
+            check_front_page(page(400, ""), BASE_URL),
+            Err(Error::SessionRejected)
+        ));
+        assert!(matches!(
+            check_front_page(page(503, ""), BASE_URL),
+            Err(Error::HttpStatus { status: 503, .. })
+        ));
+    }
+}
@@ -0,0 +1,146 @@
+use super::html;
+use crate::PuzzleId;
+
+/// The description of a puzzle, as fetched by
+/// [`AocClient::get_puzzle`](crate::AocClient::get_puzzle).
+///
+/// The description of part two is only included once part one is solved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    id: PuzzleId,
+    /// The inner HTML of the `<article>` elements, one per part.
+    parts: Vec<String>,
+}
+
+impl Puzzle {
+    pub(crate) fn new(id: PuzzleId, parts: Vec<String>) -> Self {
+        Self { id, parts }
+    }
+
+    /// Extract the puzzle description from the HTML of a puzzle page.
+    pub(crate) fn from_page(id: PuzzleId, page: &str) -> Option<Self> {
+        let parts: Vec<_> = html::articles(page)
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        (!parts.is_empty()).then(|| Self::new(id, parts))
+    }
+
+    /// The year and day of the puzzle.
+    pub fn id(&self) -> PuzzleId {
+        self.id
+    }
+
+    /// The year of the puzzle.
+    pub fn year(&self) -> u16 {
+        self.id.year().get()
+    }
+
+    /// The day of the puzzle.
+    pub fn day(&self) -> u8 {
+        self.id.day().get()
+    }
+
+    /// The number of parts whose description is included, i.e. 1 or 2.
+    pub fn parts(&self) -> usize {
+        self.parts.len()
+    }
+
+    /// Whether the descriptions of both parts are included. The last day only has one part.
+    pub fn is_complete(&self) -> bool {
+        self.parts.len() >= 2 || self.day() == crate::schedule::last_day(self.year())
+    }
+
+    /// The title of the puzzle, e.g. `Trebuchet?!`.
+    pub fn title(&self) -> Option<String> {
+        let part = self.parts.first()?;
+        let start = part.find("<h2")?;
+        let end = part[start..].find("</h2>")? + start;
+        let heading = html::text(&part[start..end]);
+        let heading = heading.trim().trim_matches('-').trim();
+        let title = heading.split_once(": ").map_or(heading, |(_, title)| title);
+        Some(title.to_string())
+    }
+
+    /// The blocks of preformatted code in the description, which usually hold the example
+    /// inputs, e.g. to wire them up as test cases.
+    ///
+    /// Not every block is an example input; some show intermediate states or expected outputs.
+    /// Emphasized parts, which often mark the answer, are kept as plain text.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use aoc_session::{aoc_session, AocClient, PuzzleId};
+    ///
+    /// let client = AocClient::new(aoc_session().unwrap());
+    /// let puzzle = client.get_puzzle(PuzzleId::new(2023, 1).unwrap()).unwrap();
+    /// let example = &puzzle.examples()[0];
+    /// ```
+    pub fn examples(&self) -> Vec<String> {
+        self.parts
+            .iter()
+            .flat_map(|part| html::code_blocks(part))
+            .collect()
+    }
+
+    /// The description as HTML, with one `<article>` element per part.
+    pub fn html(&self) -> String {
+        self.parts
+            .iter()
+            .map(|part| format!("<article class=\"day-desc\">{part}</article>\n"))
+            .collect()
+    }
+
+    /// The description as Markdown.
+    pub fn markdown(&self) -> String {
+        self.render(html::markdown)
+    }
+
+    /// The description as plain text.
+    pub fn text(&self) -> String {
+        self.render(html::plain_text)
+    }
+
+    fn render(&self, render: fn(&str) -> String) -> String {
+        self.parts
+            .iter()
+            .map(|part| render(part))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = "<main>\n<article class=\"day-desc\"><h2>--- Day 1: Trebuchet?! ---</h2>\
+                        <p>Part <em>one</em>:</p><pre><code>1abc2\n<em>a&lt;b</em>\n</code></pre></article>\n<p>Your puzzle answer was \
+                        <code>42</code>.</p><article class=\"day-desc\">\
+                        <h2 id=\"part2\">--- Part Two ---</h2><p>Part two, with <code>code</code>:</p>\
+                        <pre><code>two1nine\n</code></pre></article></main>";
+
+    #[test]
+    fn puzzle_from_page() {
+        let puzzle = Puzzle::from_page(PuzzleId::new(2023, 1).unwrap(), PAGE).unwrap();
+        assert_eq!(puzzle.parts(), 2);
+        assert!(puzzle.is_complete());
+        assert_eq!(puzzle.title().as_deref(), Some("Trebuchet?!"));
+        assert_eq!(
+            puzzle.markdown(),
+            "## --- Day 1: Trebuchet?! ---\n\nPart *one*:\n\n```\n1abc2\na<b\n```\n\n\
+             ## --- Part Two ---\n\nPart two, with `code`:\n\n```\ntwo1nine\n```\n"
+        );
+        assert_eq!(
+            puzzle.text(),
+            "--- Day 1: Trebuchet?! ---\n\nPart one:\n\n1abc2\na<b\n\n\
+             --- Part Two ---\n\nPart two, with code:\n\ntwo1nine\n"
+        );
+        assert_eq!(
+            Puzzle::from_page(PuzzleId::new(2023, 1).unwrap(), &puzzle.html()).unwrap(),
+            puzzle
+        );
+        assert!(Puzzle::from_page(PuzzleId::new(2023, 1).unwrap(), "<main></main>").is_none());
+    }
+}
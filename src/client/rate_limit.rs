@@ -0,0 +1,101 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The default minimum delay between two requests of a client.
+pub(crate) const DEFAULT_MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The longest delay the backoff grows to.
+const MAX_BACKOFF: Duration = Duration::from_secs(5 * 60);
+
+/// Spaces out the requests of a client, and backs off exponentially while adventofcode.com
+/// answers with `429 Too Many Requests` or a server error.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    min_interval: Duration,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// When the next request may be sent.
+    next: Option<Instant>,
+    /// The number of failed responses in a row.
+    failures: u32,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(min_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            state: Mutex::default(),
+        }
+    }
+
+    pub(crate) fn min_interval(&self) -> Duration {
+        self.min_interval
+    }
+
+    /// Block until a request may be sent, and reserve the slot for it.
+    ///
+    /// The slot is reserved before sleeping, so that concurrent requests line up one interval
+    /// apart.
+    pub(crate) fn acquire(&self) {
+        let now = Instant::now();
+        let start = {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            let start = state.next.map_or(now, |next| next.max(now));
+            state.next = Some(start + self.min_interval);
+            start
+        };
+        std::thread::sleep(start - now);
+    }
+
+    /// Record the status of a response, backing off if it asks to slow down.
+    pub(crate) fn record(&self, status: u16) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if status == 429 || (500..600).contains(&status) {
+            state.failures += 1;
+            let delay = self.backoff(state.failures);
+            let next = Instant::now() + delay;
+            state.next = Some(state.next.map_or(next, |current| current.max(next)));
+        } else {
+            state.failures = 0;
+        }
+    }
+
+    /// The delay after the given number of failures in a row: the minimum interval, doubled for
+    /// each failure, and at least a second.
+    fn backoff(&self, failures: u32) -> Duration {
+        let base = self.min_interval.max(Duration::from_secs(1));
+        base.saturating_mul(1 << failures.min(16)).min(MAX_BACKOFF)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_are_spaced_out() {
+        let limiter = RateLimiter::new(Duration::from_millis(50));
+        let start = Instant::now();
+        limiter.acquire();
+        limiter.acquire();
+        limiter.acquire();
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn backoff_doubles_until_success() {
+        let limiter = RateLimiter::new(Duration::from_secs(2));
+        assert_eq!(limiter.backoff(1), Duration::from_secs(4));
+        assert_eq!(limiter.backoff(3), Duration::from_secs(16));
+        assert_eq!(limiter.backoff(30), MAX_BACKOFF);
+        limiter.record(503);
+        limiter.record(429);
+        assert_eq!(limiter.state.lock().unwrap().failures, 2);
+        limiter.record(200);
+        assert_eq!(limiter.state.lock().unwrap().failures, 0);
+        assert!(limiter.state.lock().unwrap().next.unwrap() > Instant::now());
+    }
+}
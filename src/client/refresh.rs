@@ -0,0 +1,54 @@
+//! Looking the session up again when Advent of Code rejects it halfway through a run, e.g. when
+//! it expires in the middle of December and the browser already holds a new one.
+
+use std::fmt;
+use std::sync::Arc;
+
+use super::http::Response;
+use crate::{Error, SessionProvider};
+
+/// What an [`AocClient`](crate::AocClient) did on its own, reported to the callback given to
+/// [`AocClientBuilder::on_event`](crate::AocClientBuilder::on_event).
+#[derive(Debug)]
+pub enum ClientEvent {
+    /// Advent of Code rejected the session, so a new one was looked up, and the request was sent
+    /// again with it.
+    SessionRefreshed,
+    /// Advent of Code rejected the session, and no new one could be looked up. The error is
+    /// [`Error::SessionRejected`] if the sources only hold the rejected session.
+    SessionRefreshFailed(Error),
+}
+
+/// The callback given to [`AocClientBuilder::on_event`](crate::AocClientBuilder::on_event).
+pub(crate) type EventHandler = Arc<dyn Fn(&ClientEvent) + Send + Sync>;
+
+/// Where a rejected session is replaced from, if anywhere, and who to tell.
+#[derive(Clone, Default)]
+pub(crate) struct Refresh {
+    pub(crate) provider: Option<Arc<dyn SessionProvider + Send + Sync>>,
+    pub(crate) on_event: Option<EventHandler>,
+}
+
+impl Refresh {
+    pub(crate) fn emit(&self, event: ClientEvent) {
+        if let Some(on_event) = &self.on_event {
+            on_event(&event);
+        }
+    }
+}
+
+impl fmt::Debug for Refresh {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Refresh")
+            .field("provider", &self.provider.is_some())
+            .field("on_event", &self.on_event.is_some())
+            .finish()
+    }
+}
+
+/// Whether Advent of Code turned the request down for want of a valid session: the input of a
+/// day is refused with `400 Bad Request`, and most other pages redirect to the login page.
+/// `304 Not Modified` only answers a conditional request.
+pub(crate) fn is_rejection(response: &Response) -> bool {
+    response.status == 400 || ((300..400).contains(&response.status) && response.status != 304)
+}
@@ -0,0 +1,109 @@
+//! Sending a request again when Advent of Code is briefly overwhelmed, e.g. in the minute a puzzle
+//! unlocks.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// How often, and how patiently, an [`AocClient`](crate::AocClient) sends a request again after a
+/// transient failure: a server error (`5xx`), a timeout, or a dropped connection.
+///
+/// The delay before each retry doubles from [`initial_backoff`](Self::initial_backoff) up to
+/// [`max_backoff`](Self::max_backoff), and is picked at random between half and all of it, so
+/// that many clients don't retry in lockstep. The spacing of the requests (see
+/// [`AocClient::with_min_interval`](crate::AocClient::with_min_interval)) still applies.
+///
+/// Answers are never submitted twice, since a failed submission may have been recorded.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use aoc_session::{aoc_session, AocClient, RetryPolicy};
+///
+/// let client = AocClient::builder(aoc_session().unwrap())
+///     .retry_policy(RetryPolicy::new(5).initial_backoff(Duration::from_secs(2)))
+///     .build();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Send each request at most `max_attempts` times in all, waiting a second before the first
+    /// retry and at most half a minute before the others.
+    pub const fn new(max_attempts: u32) -> Self {
+        RetryPolicy {
+            max_attempts,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+
+    /// Send each request once, without retrying.
+    pub const fn never() -> Self {
+        RetryPolicy::new(1)
+    }
+
+    /// Wait about this long before the first retry.
+    pub const fn initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    /// Wait at most this long before any retry.
+    pub const fn max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+
+    /// The most times a request is sent.
+    pub const fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// The delay before the given retry, counting from 1, with `jitter` between 0 and 1 picking
+    /// the point between half and all of the exponential backoff.
+    pub(crate) fn delay(&self, retry: u32, jitter: f64) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(1 << retry.saturating_sub(1).min(16))
+            .min(self.max_backoff);
+        backoff.mul_f64(0.5 + jitter.clamp(0.0, 1.0) / 2.0)
+    }
+
+    /// The delay before the given retry, with random jitter.
+    pub(crate) fn jittered_delay(&self, retry: u32) -> Duration {
+        // A freshly keyed hasher is random enough to spread clients apart.
+        let random = RandomState::new().build_hasher().finish();
+        self.delay(retry, random as f64 / u64::MAX as f64)
+    }
+}
+
+/// Three attempts, a second apart at first.
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::new(3)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delays_double_within_bounds() {
+        let policy = RetryPolicy::new(10).max_backoff(Duration::from_secs(5));
+        assert_eq!(policy.delay(1, 1.0), Duration::from_secs(1));
+        assert_eq!(policy.delay(1, 0.0), Duration::from_millis(500));
+        assert_eq!(policy.delay(3, 1.0), Duration::from_secs(4));
+        assert_eq!(policy.delay(4, 1.0), Duration::from_secs(5));
+        assert_eq!(policy.delay(40, 1.0), Duration::from_secs(5));
+        let delay = policy.jittered_delay(2);
+        assert!(Duration::from_secs(1) <= delay && delay <= Duration::from_secs(2));
+    }
+}
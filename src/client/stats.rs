@@ -0,0 +1,191 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use super::html;
+
+/// The personal statistics of a year, as fetched by
+/// [`AocClient::personal_times`](crate::AocClient::personal_times).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PersonalTimes {
+    pub year: u16,
+    /// The statistics of the days where at least one part is solved, by day.
+    pub days: BTreeMap<u8, DayTimes>,
+}
+
+/// The personal statistics of a day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DayTimes {
+    pub part_one: Option<PartTime>,
+    pub part_two: Option<PartTime>,
+}
+
+/// How fast a part was solved, counted from the release of the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartTime {
+    /// The time it took to solve the part, or `None` if it took more than 24 hours, which
+    /// Advent of Code doesn't break down.
+    pub time: Option<Duration>,
+    pub rank: u32,
+    pub score: u32,
+}
+
+/// The stars earned in a year, as fetched by [`AocClient::progress`](crate::AocClient::progress).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Progress {
+    pub year: u16,
+    /// The number of stars (0, 1, or 2) of every unlocked day, by day.
+    pub stars: BTreeMap<u8, u8>,
+}
+
+impl Progress {
+    /// The number of stars of the day; 0 if the day is locked.
+    pub fn stars(&self, day: u8) -> u8 {
+        self.stars.get(&day).copied().unwrap_or(0)
+    }
+
+    /// The number of stars earned in the year.
+    pub fn total(&self) -> u32 {
+        self.stars.values().map(|&stars| u32::from(stars)).sum()
+    }
+
+    /// The unlocked days that lack a star.
+    pub fn unsolved(&self) -> impl Iterator<Item = u8> + '_ {
+        self.stars
+            .iter()
+            .filter(|(_, &stars)| stars < 2)
+            .map(|(&day, _)| day)
+    }
+}
+
+/// Parse the calendar page of a year, `/<year>`, where unlocked days are links classed by
+/// their stars.
+pub(crate) fn parse_progress(year: u16, page: &str) -> Result<Progress, String> {
+    let prefix = format!("/{year}/day/");
+    let stars: BTreeMap<u8, u8> = html::start_tags(page, "a")
+        .into_iter()
+        .filter_map(|tag| {
+            let day = tag.attribute("href")?.strip_prefix(&prefix)?.parse().ok()?;
+            let class = tag.attribute("class")?;
+            let classes: Vec<_> = class.split_whitespace().collect();
+            if !classes
+                .iter()
+                .any(|class| class.starts_with("calendar-day"))
+            {
+                return None;
+            }
+            let stars = if classes.contains(&"calendar-verycomplete") {
+                2
+            } else if classes.contains(&"calendar-complete") {
+                1
+            } else {
+                0
+            };
+            Some((day, stars))
+        })
+        .collect();
+    if stars.is_empty() && !page.contains("class=\"calendar") {
+        return Err("the page has no calendar".to_string());
+    }
+    Ok(Progress { year, stars })
+}
+
+/// Parse the personal statistics page, `/<year>/leaderboard/self`.
+pub(crate) fn parse_personal_times(year: u16, page: &str) -> Result<PersonalTimes, String> {
+    let start = page
+        .find("<pre")
+        .ok_or("the page has no statistics table")?;
+    let end = page[start..]
+        .find("</pre>")
+        .map_or(page.len(), |end| start + end);
+    let days = html::text(&page[start..end])
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let day = fields.next()?.parse().ok()?;
+            let part_one = part_time(&mut fields);
+            let part_two = part_time(&mut fields);
+            Some((day, DayTimes { part_one, part_two }))
+        })
+        .collect();
+    Ok(PersonalTimes { year, days })
+}
+
+/// Parse the time, rank and score of a part, which are all `-` if the part isn't solved.
+fn part_time<'a>(fields: &mut impl Iterator<Item = &'a str>) -> Option<PartTime> {
+    let time = fields.next()?;
+    let rank = fields.next()?.parse().ok();
+    let score = fields.next()?.parse().ok();
+    let time = match time {
+        ">24h" => None,
+        time => Some(hms(time)?),
+    };
+    Some(PartTime {
+        time,
+        rank: rank?,
+        score: score?,
+    })
+}
+
+/// Parse a duration written as `HH:MM:SS`.
+fn hms(time: &str) -> Option<Duration> {
+    let mut secs = 0;
+    for field in time.split(':') {
+        secs = secs * 60 + field.parse::<u64>().ok()?;
+    }
+    Some(Duration::from_secs(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress() {
+        let page = "<main><pre class=\"calendar\">\
+            <a aria-label=\"Day 1, two stars\" href=\"/2023/day/1\" \
+            class=\"calendar-day1 calendar-verycomplete\">...</a>\
+            <a aria-label=\"Day 2, one star\" href=\"/2023/day/2\" \
+            class=\"calendar-day2 calendar-complete\">...</a>\
+            <a aria-label=\"Day 3\" href=\"/2023/day/3\" class=\"calendar-day3\">...</a>\
+            <span class=\"calendar-day4\">...</span>\
+            <a href=\"/2023/about\">[About]</a></pre></main>";
+        let progress = parse_progress(2023, page).unwrap();
+        assert_eq!(progress.stars, BTreeMap::from([(1, 2), (2, 1), (3, 0)]));
+        assert_eq!(progress.stars(4), 0);
+        assert_eq!(progress.total(), 3);
+        assert_eq!(progress.unsolved().collect::<Vec<_>>(), [2, 3]);
+        assert!(parse_progress(2023, "<main></main>").is_err());
+    }
+
+    #[test]
+    fn personal_times() {
+        let page = "<main><article><p>These are your personal leaderboard statistics.</p>\n\
+            <pre>      <span class=\"leaderboard-daydesc-first\">--------Part 1--------</span>   \
+            <span class=\"leaderboard-daydesc-both\">--------Part 2--------</span>\n\
+            Day   <span class=\"leaderboard-daydesc-first\">    Time   Rank  Score</span>   \
+            <span class=\"leaderboard-daydesc-both\">    Time   Rank  Score</span>\n\
+              3       &gt;24h  41234      0          -      -      -\n\
+              2   00:10:05   1234      0   01:02:03   2345      0\n\
+              1   00:00:59     42     59   00:02:00     99      2\n\
+            </pre>\n</article></main>";
+        let stats = parse_personal_times(2023, page).unwrap();
+        assert_eq!(stats.days.len(), 3);
+        assert_eq!(
+            stats.days[&3],
+            DayTimes {
+                part_one: Some(PartTime {
+                    time: None,
+                    rank: 41234,
+                    score: 0
+                }),
+                part_two: None,
+            }
+        );
+        assert_eq!(
+            stats.days[&2].part_two.unwrap().time,
+            Some(Duration::from_secs(3723))
+        );
+        assert_eq!(stats.days[&1].part_one.unwrap().score, 59);
+        assert!(parse_personal_times(2023, "<main></main>").is_err());
+    }
+}
@@ -0,0 +1,187 @@
+use core::fmt;
+use std::time::Duration;
+
+use super::html;
+
+/// Which way a wrong answer is off, as hinted by Advent of Code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Hint {
+    TooHigh,
+    TooLow,
+}
+
+/// The outcome of submitting an answer with [`AocClient::submit`](crate::AocClient::submit).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubmitOutcome {
+    /// The answer is right and the star is earned.
+    Correct,
+    /// The answer is wrong.
+    Incorrect {
+        /// Whether the answer is too high or too low, if Advent of Code said so.
+        hint: Option<Hint>,
+        /// How long to wait before submitting again, if Advent of Code said so.
+        wait: Option<Duration>,
+    },
+    /// An answer was submitted too recently; nothing was checked.
+    RateLimited {
+        /// How long is left to wait before submitting again.
+        wait: Duration,
+    },
+    /// The level was already completed, or is not unlocked yet; nothing was checked.
+    AlreadyCompleted,
+}
+
+impl fmt::Display for SubmitOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubmitOutcome::Correct => f.write_str("That's the right answer!"),
+            SubmitOutcome::Incorrect { hint, wait } => {
+                f.write_str("That's not the right answer")?;
+                match hint {
+                    Some(Hint::TooHigh) => f.write_str("; your answer is too high")?,
+                    Some(Hint::TooLow) => f.write_str("; your answer is too low")?,
+                    None => {}
+                }
+                if let Some(wait) = wait {
+                    write!(f, ". Wait {}s before trying again", wait.as_secs())?;
+                }
+                Ok(())
+            }
+            SubmitOutcome::RateLimited { wait } => write!(
+                f,
+                "You gave an answer too recently. Wait {}s before trying again",
+                wait.as_secs()
+            ),
+            SubmitOutcome::AlreadyCompleted => {
+                f.write_str("This level is already completed or not unlocked yet")
+            }
+        }
+    }
+}
+
+/// Parse the page returned by Advent of Code after submitting an answer.
+///
+/// Returns the text of the page if it's not recognized.
+pub(crate) fn parse(page: &str) -> Result<SubmitOutcome, String> {
+    let text = html::articles(page)
+        .into_iter()
+        .map(html::text)
+        .collect::<Vec<_>>()
+        .join("\n");
+    // AoC separates sentences with two spaces and wraps lines arbitrarily.
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.contains("That's the right answer") {
+        Ok(SubmitOutcome::Correct)
+    } else if text.contains("That's not the right answer") {
+        let hint = if text.contains("too high") {
+            Some(Hint::TooHigh)
+        } else if text.contains("too low") {
+            Some(Hint::TooLow)
+        } else {
+            None
+        };
+        let wait = after(&text, "wait ").and_then(parse_minutes);
+        Ok(SubmitOutcome::Incorrect { hint, wait })
+    } else if text.contains("You gave an answer too recently") {
+        let wait = after(&text, "You have ")
+            .and_then(parse_left_to_wait)
+            .unwrap_or(Duration::from_secs(60));
+        Ok(SubmitOutcome::RateLimited { wait })
+    } else if text.contains("You don't seem to be solving the right level") {
+        Ok(SubmitOutcome::AlreadyCompleted)
+    } else {
+        Err(text)
+    }
+}
+
+fn after<'a>(text: &'a str, prefix: &str) -> Option<&'a str> {
+    text.find(prefix).map(|i| &text[i + prefix.len()..])
+}
+
+/// Parse e.g. `one minute before trying again` or `5 minutes before trying again`.
+fn parse_minutes(text: &str) -> Option<Duration> {
+    let mut words = text.split(' ');
+    let count = words.next()?;
+    if !words.next()?.starts_with("minute") {
+        return None;
+    }
+    const NUMBERS: [&str; 10] = [
+        "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    ];
+    let count = match NUMBERS.iter().position(|&n| n == count) {
+        Some(n) => n as u64,
+        None => count.parse().ok()?,
+    };
+    Some(Duration::from_secs(count * 60))
+}
+
+/// Parse e.g. `1m 35s left to wait` or `35s left to wait`.
+fn parse_left_to_wait(text: &str) -> Option<Duration> {
+    let (amounts, _) = text.split_once(" left to wait")?;
+    let mut secs = 0;
+    for amount in amounts.split(' ') {
+        let (number, unit) = amount.split_at(amount.find(|c: char| !c.is_ascii_digit())?);
+        let number: u64 = number.parse().ok()?;
+        secs += match unit {
+            "h" => number * 3600,
+            "m" => number * 60,
+            "s" => number,
+            _ => return None,
+        };
+    }
+    Some(Duration::from_secs(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(article: &str) -> String {
+        format!("<html><body><main><article><p>{article}</p></article></main></body></html>")
+    }
+
+    #[test]
+    fn outcomes() {
+        assert_eq!(
+            parse(&page("That's the right answer!  You are <span class=\"day-success\">one gold star</span> closer.")),
+            Ok(SubmitOutcome::Correct)
+        );
+        assert_eq!(
+            parse(&page(
+                "That's not the right answer; your answer is too high.  If you're stuck, \
+                         make sure you're using the full input data; there are also some general \
+                         tips on the <a href=\"/2023/about\">about page</a>.  Please wait one \
+                         minute before trying again. <a href=\"/2023/day/1\">[Return to Day 1]</a>"
+            )),
+            Ok(SubmitOutcome::Incorrect {
+                hint: Some(Hint::TooHigh),
+                wait: Some(Duration::from_secs(60))
+            })
+        );
+        assert_eq!(
+            parse(&page(
+                "That's not the right answer.  Please wait 5 minutes before trying again."
+            )),
+            Ok(SubmitOutcome::Incorrect {
+                hint: None,
+                wait: Some(Duration::from_secs(300))
+            })
+        );
+        assert_eq!(
+            parse(&page(
+                "You gave an answer too recently; you have to wait after submitting an \
+                         answer before trying again.  You have 1m 35s left to wait."
+            )),
+            Ok(SubmitOutcome::RateLimited {
+                wait: Duration::from_secs(95)
+            })
+        );
+        assert_eq!(
+            parse(&page(
+                "You don't seem to be solving the right level.  Did you already complete it?"
+            )),
+            Ok(SubmitOutcome::AlreadyCompleted)
+        );
+        assert_eq!(parse(&page("Huh?")), Err("Huh?".to_string()));
+    }
+}
@@ -0,0 +1,671 @@
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use toml_edit::{Document, Item, TableLike};
+
+use crate::{
+    AocSessionBuilder, Browser, Error, FirefoxProfile, Result, SecretManager, SelectionStrategy,
+    Source,
+};
+
+/// Settings shared by the library and the executable, read from a TOML file.
+///
+/// The file lives at `config.toml` in the configuration directory, e.g.
+/// `~/.config/aoc-session/config.toml` on Linux and macOS, next to the `token` file. If it doesn't
+/// exist, `~/.config/aoc/config.toml` is read instead. All settings are optional:
+///
+/// ```toml
+/// # The session cookie value, if you'd rather not scan the browsers.
+/// token = "25a16c7465645f5f..."
+/// # The browser whose session wins when several browsers hold one.
+/// browser = "firefox"
+/// # The Firefox profile to read before the other browsers, by name or by path.
+/// firefox_profile = "work"
+/// # Skip the browsers that may prompt for the macOS Keychain password, i.e. those based on Chromium.
+/// allow_keychain_prompt = false
+/// # With the `remote` feature, the machine to ask over SSH when no browser here has the cookie.
+/// ssh = "me@laptop"
+/// # With the `cdp` feature, the port of a Chrome started with `--remote-debugging-port`, asked
+/// # after the cookie stores of the browsers.
+/// cdp_port = 9222
+/// # The only sources to consult, in this order, instead of all of them.
+/// sources = ["env", "keyring", "firefox"]
+/// # The browsers whose cookie stores are never read.
+/// exclude_browsers = ["chrome"]
+///
+/// # With the `secret-managers` feature, the items of password managers that hold the session
+/// # cookie value, consulted in this order before the browsers.
+/// [secret_managers]
+/// op = "op://Private/Advent of Code/password"
+/// bw = "Advent of Code"
+/// pass = "aoc/session"
+///
+/// [cache]
+/// # Where puzzle inputs are cached.
+/// dir = "/home/me/aoc/inputs"
+/// # Remember the session cookie value between runs for this long, in seconds.
+/// session_ttl = 86400
+///
+/// [http]
+/// # A mirror of Advent of Code to use instead, along with its session cookie.
+/// base_url = "https://aoc.example.edu"
+/// # How to contact you, sent in the User-Agent header along with the name of this crate.
+/// user_agent = "github.com/me/aoc by me@example.com"
+/// # The proxy to reach it through, instead of the one named by HTTPS_PROXY.
+/// proxy = "http://proxy.example.com:3128"
+/// # In seconds.
+/// timeout = 30
+/// # The minimum delay between two requests, in seconds.
+/// min_interval = 5
+/// # How many times to send a request that failed with a server error or a timeout.
+/// max_attempts = 5
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Config {
+    /// The session cookie value.
+    pub token: Option<String>,
+    /// The browser whose session is preferred (see [`SelectionStrategy::PreferBrowser`]).
+    pub browser: Option<Browser>,
+    /// The Firefox profile consulted before the other browsers.
+    pub firefox_profile: Option<FirefoxProfile>,
+    /// Whether the browsers that may prompt for the password of the macOS Keychain are read (see
+    /// [`AocSessionBuilder::allow_keychain_prompt`]).
+    pub allow_keychain_prompt: Option<bool>,
+    /// The SSH destination that runs `aoc-session` when no browser here has a session cookie
+    /// (see [`aoc_session_from_ssh`](crate::aoc_session_from_ssh)). Only used with the `remote`
+    /// feature.
+    pub ssh: Option<String>,
+    /// The port of a running Chrome to ask through the Chrome DevTools Protocol when the cookie
+    /// stores of the browsers have no session cookie (see
+    /// [`aoc_session_from_cdp`](crate::aoc_session_from_cdp)). Only used with the `cdp` feature.
+    pub cdp_port: Option<u16>,
+    /// The items of password managers that hold the session cookie value, consulted before the
+    /// browsers (see [`aoc_session_from_secret_manager`](crate::aoc_session_from_secret_manager)).
+    /// Only used with the `secret-managers` feature.
+    pub secret_managers: Vec<SecretManager>,
+    /// The only sources to consult, in this order, by the names listed in [`SOURCE_NAMES`] or
+    /// the name of a browser. All of them are consulted by default.
+    pub sources: Option<Vec<String>>,
+    /// The browsers whose cookie stores are never read (see
+    /// [`AocSessionBuilder::exclude_browser`]).
+    pub exclude_browsers: Vec<Browser>,
+    /// The directory where puzzle inputs are cached.
+    pub cache_dir: Option<PathBuf>,
+    /// How long the session cookie value is remembered between runs (see
+    /// [`AocSessionBuilder::persist`]). It's not remembered by default.
+    pub session_ttl: Option<Duration>,
+    /// How to contact the user, sent in the `User-Agent` header to adventofcode.com (see
+    /// [`AocClientBuilder::user_agent`](crate::AocClientBuilder::user_agent)). The
+    /// `AOC_USER_AGENT` environment variable takes precedence.
+    pub user_agent: Option<String>,
+    /// The URL of a mirror of Advent of Code, or a proxy in front of it, that requests are sent
+    /// to instead (see [`AocClientBuilder::base_url`](crate::AocClientBuilder::base_url)). The
+    /// browsers are searched for the session cookie of its host.
+    pub base_url: Option<String>,
+    /// The proxy that requests to adventofcode.com go through (see
+    /// [`AocClientBuilder::proxy`](crate::AocClientBuilder::proxy)).
+    pub proxy: Option<String>,
+    /// The timeout of requests to adventofcode.com.
+    pub timeout: Option<Duration>,
+    /// The minimum delay between two requests to adventofcode.com (see
+    /// [`AocClient::with_min_interval`](crate::AocClient::with_min_interval)).
+    pub min_interval: Option<Duration>,
+    /// How many times a request is sent after server errors and timeouts (see
+    /// [`RetryPolicy::new`](crate::RetryPolicy::new)).
+    pub max_attempts: Option<u32>,
+}
+
+/// The names of the sources in the `sources` setting of the configuration file, besides the
+/// names of the browsers, which stand for [`Source::Browser`]:
+///
+/// - `env`: [`Source::Env`];
+/// - `agent`: [`Source::Agent`], with the `agent` feature;
+/// - `dotenv`: [`Source::DotEnv`], with the `dotenv` feature;
+/// - `session_files`: [`Source::SessionFiles`], or the token file of the
+///   [account profile](crate::set_account_profile);
+/// - `config`: [`Source::Config`];
+/// - `keyring`: [`Source::Keyring`], with the `keyring` feature;
+/// - `firefox_profile`: [`Source::FirefoxProfile`], if `firefox_profile` is set;
+/// - `secret_managers`: a [`Source::SecretManager`] per item of `[secret_managers]`, with the
+///   `secret-managers` feature;
+/// - `browsers`: [`Source::Browsers`];
+/// - `wsl`: [`Source::Wsl`], with the `wsl` feature;
+/// - `cdp`: [`Source::Cdp`] on `cdp_port`, or port 9222, with the `cdp` feature;
+/// - `ssh`: [`Source::Ssh`], if `ssh` is set, with the `remote` feature.
+///
+/// The sources whose feature is off or whose setting is missing are left out.
+pub const SOURCE_NAMES: [&str; 12] = [
+    "env",
+    "agent",
+    "dotenv",
+    "session_files",
+    "config",
+    "keyring",
+    "firefox_profile",
+    "secret_managers",
+    "browsers",
+    "wsl",
+    "cdp",
+    "ssh",
+];
+
+impl Config {
+    /// The location of the configuration file, if the configuration directory is known:
+    /// `config.toml` in it, unless only `~/.config/aoc/config.toml` exists. Account profiles
+    /// don't fall back to the latter.
+    pub fn path() -> Option<PathBuf> {
+        let path = crate::dirs::config_dir()?.join("config.toml");
+        if path.exists() || crate::account_profile().is_some() {
+            return Some(path);
+        }
+        match crate::dirs::aoc_config_dir().map(|dir| dir.join("config.toml")) {
+            Some(fallback) if fallback.exists() => Some(fallback),
+            _ => Some(path),
+        }
+    }
+
+    /// Load the configuration file at [`Config::path`], with settings overridden by environment
+    /// variables. A missing file yields the default configuration.
+    pub fn load() -> Result<Config> {
+        let config = match Config::path() {
+            Some(path) if path.exists() => Config::from_path(&path)?,
+            _ => Config::default(),
+        };
+        Ok(config.with_env(|name| std::env::var(name).ok()))
+    }
+
+    fn with_env(mut self, var: impl Fn(&str) -> Option<String>) -> Self {
+        if let Some(user_agent) = var("AOC_USER_AGENT").filter(|ua| !ua.trim().is_empty()) {
+            self.user_agent = Some(user_agent);
+        }
+        self
+    }
+
+    /// Load the configuration file at the given path.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Config> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path).map_err(|source| Error::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        contents.parse().map_err(|e| match e {
+            Error::InvalidConfig(message) => {
+                Error::InvalidConfig(format!("{}: {message}", path.display()))
+            }
+            e => e,
+        })
+    }
+
+    /// The chain of sources [`aoc_session`](crate::aoc_session) consults under this
+    /// configuration.
+    ///
+    /// Under an [account profile](crate::set_account_profile), the chain only holds the sources
+    /// that belong to the profile: its token file, its configuration file, the credential store,
+    /// and the Firefox profile, password manager items, and SSH destination it names.
+    pub fn session_builder(&self) -> AocSessionBuilder {
+        let in_profile = crate::account_profile().is_some();
+        let builder = match &self.sources {
+            Some(names) => names
+                .iter()
+                .fold(AocSessionBuilder::new(), |builder, name| {
+                    self.named_source(builder, name, in_profile)
+                }),
+            None => self.all_sources(in_profile),
+        };
+        let builder = self
+            .exclude_browsers
+            .iter()
+            .fold(builder, |builder, &browser| {
+                builder.exclude_browser(browser)
+            });
+        let builder = match self.base_url.as_deref().and_then(host) {
+            Some(host) => builder.domain(host),
+            None => builder,
+        };
+        let builder = match self.allow_keychain_prompt {
+            Some(allow) => builder.allow_keychain_prompt(allow),
+            None => builder,
+        };
+        let builder = match self.session_ttl {
+            Some(ttl) => builder.persist(ttl),
+            None => builder,
+        };
+        match self.browser {
+            Some(browser) => builder.selection(SelectionStrategy::PreferBrowser(browser)),
+            None => builder,
+        }
+    }
+
+    /// The chain of sources when the `sources` setting doesn't restrict it.
+    fn all_sources(&self, in_profile: bool) -> AocSessionBuilder {
+        let names: &[&str] = if in_profile {
+            &[
+                "session_files",
+                "config",
+                "keyring",
+                "firefox_profile",
+                "secret_managers",
+                "cdp",
+                "ssh",
+            ]
+        } else {
+            &SOURCE_NAMES
+        };
+        names
+            .iter()
+            .filter(|&&name| name != "cdp" || self.cdp_port.is_some())
+            .fold(AocSessionBuilder::new(), |builder, name| {
+                self.named_source(builder, name, in_profile)
+            })
+    }
+
+    /// Append the sources named in the `sources` setting to the chain.
+    fn named_source(
+        &self,
+        builder: AocSessionBuilder,
+        name: &str,
+        in_profile: bool,
+    ) -> AocSessionBuilder {
+        match name {
+            "env" => builder.env(),
+            #[cfg(feature = "agent")]
+            "agent" => builder.agent(),
+            #[cfg(feature = "dotenv")]
+            "dotenv" => builder.dotenv(),
+            "session_files" if in_profile => match crate::token_file_path() {
+                Some(path) => builder.file(path),
+                None => builder,
+            },
+            "session_files" => builder.session_files(),
+            "config" => builder.source(Source::Config),
+            #[cfg(feature = "keyring")]
+            "keyring" => builder.keyring(),
+            "firefox_profile" => match &self.firefox_profile {
+                Some(profile) => builder.firefox_profile(profile.clone()),
+                None => builder,
+            },
+            #[cfg(feature = "secret-managers")]
+            "secret_managers" => self
+                .secret_managers
+                .iter()
+                .cloned()
+                .fold(builder, AocSessionBuilder::secret_manager),
+            "browsers" => builder.browsers(),
+            #[cfg(feature = "wsl")]
+            "wsl" => builder.source(Source::Wsl),
+            #[cfg(feature = "cdp")]
+            "cdp" => builder.cdp(self.cdp_port.unwrap_or(crate::DEFAULT_CDP_PORT)),
+            #[cfg(feature = "remote")]
+            "ssh" => match &self.ssh {
+                Some(destination) => builder.ssh(destination.clone()),
+                None => builder,
+            },
+            name => match name.parse() {
+                Ok(browser) => builder.browser(browser),
+                Err(_) => builder,
+            },
+        }
+    }
+}
+
+impl FromStr for Config {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let document: Document = s
+            .parse()
+            .map_err(|e: toml_edit::TomlError| Error::InvalidConfig(e.to_string()))?;
+        let root = document.as_table();
+        let cache = table(root, "cache")?;
+        let http = table(root, "http")?;
+        let secret_managers = match table(root, "secret_managers")? {
+            Some(items) => items
+                .iter()
+                .map(|(key, _)| {
+                    let item = string(items, key)?.unwrap_or_default();
+                    SecretManager::from_key(key, item).ok_or_else(|| {
+                        Error::InvalidConfig(format!(
+                            "unknown password manager `{key}`; expected `op`, `bw`, or `pass`"
+                        ))
+                    })
+                })
+                .collect::<Result<_>>()?,
+            None => Vec::new(),
+        };
+        Ok(Config {
+            token: string(root, "token")?
+                .map(crate::secret::trim)
+                .filter(|token| !token.is_empty()),
+            browser: string(root, "browser")?
+                .map(|browser| browser.parse())
+                .transpose()?,
+            firefox_profile: string(root, "firefox_profile")?.map(|profile| {
+                if profile.contains(['/', '\\']) {
+                    FirefoxProfile::Path(PathBuf::from(profile))
+                } else {
+                    FirefoxProfile::Named(profile)
+                }
+            }),
+            allow_keychain_prompt: boolean(root, "allow_keychain_prompt")?,
+            ssh: string(root, "ssh")?,
+            cdp_port: port(root, "cdp_port")?,
+            secret_managers,
+            sources: strings(root, "sources")?
+                .map(|names| names.into_iter().map(source_name).collect())
+                .transpose()?,
+            exclude_browsers: strings(root, "exclude_browsers")?
+                .unwrap_or_default()
+                .iter()
+                .map(|browser| browser.parse())
+                .collect::<Result<_>>()?,
+            cache_dir: cache
+                .map(|cache| string(cache, "dir"))
+                .transpose()?
+                .flatten()
+                .map(PathBuf::from),
+            session_ttl: cache
+                .map(|cache| seconds(cache, "session_ttl"))
+                .transpose()?
+                .flatten(),
+            base_url: http
+                .map(|http| string(http, "base_url"))
+                .transpose()?
+                .flatten(),
+            user_agent: http
+                .map(|http| string(http, "user_agent"))
+                .transpose()?
+                .flatten(),
+            proxy: http
+                .map(|http| string(http, "proxy"))
+                .transpose()?
+                .flatten(),
+            timeout: http
+                .map(|http| seconds(http, "timeout"))
+                .transpose()?
+                .flatten(),
+            min_interval: http
+                .map(|http| seconds(http, "min_interval"))
+                .transpose()?
+                .flatten(),
+            max_attempts: http
+                .map(|http| count(http, "max_attempts"))
+                .transpose()?
+                .flatten(),
+        })
+    }
+}
+
+/// The host of the URL, e.g. `aoc.example.edu` in `https://aoc.example.edu:8443/2023`.
+pub(crate) fn host(url: &str) -> Option<&str> {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = &rest[..rest.find('/').unwrap_or(rest.len())];
+    let host = authority.rsplit('@').next()?;
+    let host = &host[..host.find(':').unwrap_or(host.len())];
+    (!host.is_empty()).then_some(host)
+}
+
+fn table<'a>(parent: &'a dyn TableLike, key: &str) -> Result<Option<&'a dyn TableLike>> {
+    parent
+        .get(key)
+        .map(|item| {
+            item.as_table_like()
+                .ok_or_else(|| Error::InvalidConfig(format!("`{key}` must be a table")))
+        })
+        .transpose()
+}
+
+fn string(table: &dyn TableLike, key: &str) -> Result<Option<String>> {
+    table
+        .get(key)
+        .map(|item| {
+            item.as_str()
+                .map(str::to_string)
+                .ok_or_else(|| Error::InvalidConfig(format!("`{key}` must be a string")))
+        })
+        .transpose()
+}
+
+fn strings(table: &dyn TableLike, key: &str) -> Result<Option<Vec<String>>> {
+    table
+        .get(key)
+        .map(|item| {
+            item.as_array()
+                .and_then(|array| {
+                    array
+                        .iter()
+                        .map(|value| value.as_str().map(str::to_string))
+                        .collect()
+                })
+                .ok_or_else(|| Error::InvalidConfig(format!("`{key}` must be an array of strings")))
+        })
+        .transpose()
+}
+
+/// The name, if it's one of [`SOURCE_NAMES`] or the name of a browser.
+fn source_name(name: String) -> Result<String> {
+    if SOURCE_NAMES.contains(&name.as_str()) || name.parse::<Browser>().is_ok() {
+        Ok(name)
+    } else {
+        Err(Error::InvalidConfig(format!(
+            "unknown source `{name}` in `sources`; expected one of {} or a browser",
+            SOURCE_NAMES.join(", ")
+        )))
+    }
+}
+
+fn boolean(table: &dyn TableLike, key: &str) -> Result<Option<bool>> {
+    table
+        .get(key)
+        .map(|item| {
+            item.as_bool()
+                .ok_or_else(|| Error::InvalidConfig(format!("`{key}` must be true or false")))
+        })
+        .transpose()
+}
+
+fn port(table: &dyn TableLike, key: &str) -> Result<Option<u16>> {
+    table
+        .get(key)
+        .map(|item: &Item| {
+            item.as_integer()
+                .and_then(|port| u16::try_from(port).ok())
+                .filter(|&port| port > 0)
+                .ok_or_else(|| Error::InvalidConfig(format!("`{key}` must be a port number")))
+        })
+        .transpose()
+}
+
+fn count(table: &dyn TableLike, key: &str) -> Result<Option<u32>> {
+    table
+        .get(key)
+        .map(|item: &Item| {
+            item.as_integer()
+                .and_then(|count| u32::try_from(count).ok())
+                .filter(|&count| count > 0)
+                .ok_or_else(|| Error::InvalidConfig(format!("`{key}` must be a positive integer")))
+        })
+        .transpose()
+}
+
+fn seconds(table: &dyn TableLike, key: &str) -> Result<Option<Duration>> {
+    table
+        .get(key)
+        .map(|item: &Item| {
+            item.as_integer()
+                .and_then(|secs| u64::try_from(secs).ok())
+                .map(Duration::from_secs)
+                .ok_or_else(|| {
+                    Error::InvalidConfig(format!("`{key}` must be a non-negative integer"))
+                })
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let config: Config = r#"
+            token = " 25a16c74 "
+            browser = "Firefox"
+            firefox_profile = "work"
+            allow_keychain_prompt = false
+            ssh = "me@laptop"
+            cdp_port = 9222
+            sources = ["env", "Firefox", "browsers"]
+            exclude_browsers = ["chrome", "Edge"]
+
+            [secret_managers]
+            pass = "aoc/session"
+            op = "Advent of Code"
+
+            [cache]
+            dir = "/tmp/inputs"
+            session_ttl = 3600
+
+            [http]
+            base_url = "https://aoc.example.edu"
+            user_agent = "me@example.com"
+            proxy = "http://proxy:3128"
+            timeout = 30
+            min_interval = 5
+            max_attempts = 4
+        "#
+        .parse()
+        .unwrap();
+        assert_eq!(
+            config,
+            Config {
+                token: Some("25a16c74".to_string()),
+                browser: Some(Browser::Firefox),
+                firefox_profile: Some(FirefoxProfile::Named("work".to_string())),
+                allow_keychain_prompt: Some(false),
+                ssh: Some("me@laptop".to_string()),
+                cdp_port: Some(9222),
+                secret_managers: vec![
+                    SecretManager::Pass("aoc/session".to_string()),
+                    SecretManager::OnePassword("Advent of Code".to_string()),
+                ],
+                sources: Some(vec![
+                    "env".to_string(),
+                    "Firefox".to_string(),
+                    "browsers".to_string(),
+                ]),
+                exclude_browsers: vec![Browser::Chrome, Browser::Edge],
+                cache_dir: Some(PathBuf::from("/tmp/inputs")),
+                session_ttl: Some(Duration::from_secs(3600)),
+                base_url: Some("https://aoc.example.edu".to_string()),
+                user_agent: Some("me@example.com".to_string()),
+                proxy: Some("http://proxy:3128".to_string()),
+                timeout: Some(Duration::from_secs(30)),
+                min_interval: Some(Duration::from_secs(5)),
+                max_attempts: Some(4),
+            }
+        );
+        assert_eq!("".parse::<Config>().unwrap(), Config::default());
+    }
+
+    #[test]
+    fn sources_are_restricted() {
+        let config: Config = r#"
+            sources = ["env", "firefox", "firefox_profile", "browsers"]
+            exclude_browsers = ["chrome"]
+        "#
+        .parse()
+        .unwrap();
+        assert_eq!(
+            config.session_builder().sources(),
+            [
+                Source::Env,
+                Source::Browser(Browser::Firefox),
+                Source::Browsers
+            ]
+        );
+        let all = Config::default().session_builder();
+        assert_eq!(all.sources().first(), Some(&Source::Env));
+        assert!(all.sources().contains(&Source::Browsers));
+    }
+
+    #[test]
+    fn hosts_of_base_urls() {
+        assert_eq!(host("https://aoc.example.edu"), Some("aoc.example.edu"));
+        assert_eq!(host("http://me@localhost:8080/aoc/"), Some("localhost"));
+        assert_eq!(host("aoc.example.edu/"), Some("aoc.example.edu"));
+        assert_eq!(host("https://"), None);
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(matches!(
+            "token = 1".parse::<Config>(),
+            Err(Error::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            "[http]\nmax_attempts = 0".parse::<Config>(),
+            Err(Error::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            "cdp_port = 65536".parse::<Config>(),
+            Err(Error::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            "http = 1".parse::<Config>(),
+            Err(Error::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            "[secret_managers]\nlastpass = \"aoc\"".parse::<Config>(),
+            Err(Error::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            "browser = \"lynx\"".parse::<Config>(),
+            Err(Error::UnknownBrowser(_))
+        ));
+        assert!(matches!(
+            "exclude_browsers = [\"lynx\"]".parse::<Config>(),
+            Err(Error::UnknownBrowser(_))
+        ));
+        assert!(matches!(
+            "sources = [\"clipboard\"]".parse::<Config>(),
+            Err(Error::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            "sources = \"env\"".parse::<Config>(),
+            Err(Error::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            "[".parse::<Config>(),
+            Err(Error::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn aoc_config_is_a_fallback() {
+        let primary = crate::dirs::config_dir().unwrap().join("config.toml");
+        let fallback = crate::dirs::aoc_config_dir().unwrap().join("config.toml");
+        assert_eq!(Config::path(), Some(primary.clone()));
+        std::fs::create_dir_all(fallback.parent().unwrap()).unwrap();
+        std::fs::write(&fallback, "").unwrap();
+        assert_eq!(Config::path(), Some(fallback.clone()));
+        std::fs::remove_file(&fallback).unwrap();
+        assert_eq!(Config::path(), Some(primary));
+    }
+
+    #[test]
+    fn env_overrides_file() {
+        let config: Config = "[http]\nuser_agent = \"file\"".parse().unwrap();
+        let env = |value: &'static str| {
+            move |name: &str| (name == "AOC_USER_AGENT").then(|| value.to_string())
+        };
+        assert_eq!(
+            config.clone().with_env(env("env")).user_agent.as_deref(),
+            Some("env")
+        );
+        assert_eq!(
+            config.with_env(env(" ")).user_agent.as_deref(),
+            Some("file")
+        );
+    }
+}
@@ -0,0 +1,467 @@
+//! Why the session cookie can or can't be found in each source and each browser.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::source::Exclusions;
+use crate::{Browser, Config, Error, SelectionStrategy, SessionSpec, Source};
+
+/// What [`diagnose`] found out about each source of the chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiagnosticsReport {
+    /// The sources in the order they are consulted, with [`Source::Browsers`] expanded into a
+    /// [`Source::Browser`] per browser.
+    pub sources: Vec<SourceDiagnosis>,
+}
+
+/// What [`diagnose`] found out about a source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceDiagnosis {
+    pub source: Source,
+    /// The cookie database, if the source is a browser whose database was located.
+    pub db_path: Option<PathBuf>,
+    /// How long consulting the source took.
+    pub elapsed: Duration,
+    pub status: SourceStatus,
+    /// What to do about a failure, if there's a likely fix.
+    pub hint: Option<String>,
+}
+
+/// The state of a source, from worst to best.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourceStatus {
+    /// The source can't be consulted on this platform, or its cargo feature is off.
+    Unsupported,
+    /// The source isn't there, e.g. an unset environment variable or a browser that isn't
+    /// installed.
+    Unavailable(ErrorDetails),
+    /// The source is there but couldn't be read.
+    Failed(ErrorDetails),
+    /// The source is there but holds no session cookie value.
+    NoSession,
+    /// The source holds a session cookie value.
+    Session {
+        /// When the cookie expires, if known.
+        expires: Option<SystemTime>,
+    },
+}
+
+/// An error of a source, as given by [`Error::code`] and its message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorDetails {
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl DiagnosticsReport {
+    /// Whether any source holds a session cookie value.
+    pub fn has_session(&self) -> bool {
+        self.sources.iter().any(SourceDiagnosis::has_session)
+    }
+}
+
+impl SourceDiagnosis {
+    /// Whether the source is there to be consulted, whether or not it holds a session cookie.
+    pub fn is_available(&self) -> bool {
+        matches!(
+            self.status,
+            SourceStatus::Failed(_) | SourceStatus::NoSession | SourceStatus::Session { .. }
+        )
+    }
+
+    /// Whether a session cookie value was found.
+    pub fn has_session(&self) -> bool {
+        matches!(self.status, SourceStatus::Session { .. })
+    }
+
+    /// When the session cookie expires, if one was found and its expiry is known.
+    pub fn expires_at(&self) -> Option<SystemTime> {
+        match self.status {
+            SourceStatus::Session { expires } => expires,
+            _ => None,
+        }
+    }
+
+    /// The error of the source, if it isn't available or failed.
+    pub fn error(&self) -> Option<&ErrorDetails> {
+        match &self.status {
+            SourceStatus::Unavailable(error) | SourceStatus::Failed(error) => Some(error),
+            _ => None,
+        }
+    }
+
+    fn failed(source: Source, elapsed: Duration, error: &Error) -> SourceDiagnosis {
+        let details = ErrorDetails {
+            code: error.code(),
+            message: error.to_string(),
+        };
+        let status = match error {
+            Error::NoSessionCookieFound => SourceStatus::NoSession,
+            Error::UnsupportedBrowser(_) => SourceStatus::Unsupported,
+            e if e.is_absence() => SourceStatus::Unavailable(details),
+            _ => SourceStatus::Failed(details),
+        };
+        SourceDiagnosis {
+            source,
+            db_path: None,
+            elapsed,
+            status,
+            hint: hint(error, crate::dirs::home_dir().as_deref()),
+        }
+    }
+}
+
+/// Consult every source of the chain that [`aoc_session`](crate::aoc_session) consults, and
+/// report for each one whether it's available, how long it took, whether it holds a session
+/// cookie and when that expires, or why it failed.
+///
+/// Unlike [`aoc_session`](crate::aoc_session), every source is consulted, even after a session
+/// cookie was found, and the browsers are checked in parallel. This is what `aoc-session doctor`
+/// prints; with the `serde` feature, the report serializes to JSON as `aoc-session doctor --json`
+/// prints it.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoc_session::diagnose;
+///
+/// for diagnosis in diagnose().sources {
+///     println!("{}: {:?}", diagnosis.source, diagnosis.status);
+///     if let Some(hint) = diagnosis.hint {
+///         println!("  hint: {hint}");
+///     }
+/// }
+/// ```
+pub fn diagnose() -> DiagnosticsReport {
+    // A broken configuration file is reported by `Source::Config`, which loads it again.
+    Config::load()
+        .unwrap_or_default()
+        .session_builder()
+        .diagnose()
+}
+
+/// Diagnose the chain of sources of an [`AocSessionBuilder`](crate::AocSessionBuilder).
+pub(crate) fn diagnose_chain(
+    sources: &[Source],
+    spec: &SessionSpec,
+    strategy: SelectionStrategy,
+    excluded: &Exclusions,
+) -> DiagnosticsReport {
+    let mut diagnoses = Vec::new();
+    for source in sources {
+        match source {
+            Source::Browsers if crate::ci::current().is_none() => {
+                let (allowed, denied): (Vec<_>, Vec<_>) = Browser::ALL
+                    .into_iter()
+                    .partition(|&browser| excluded.check(browser).is_ok());
+                let mut browsers: Vec<_> = in_parallel(&allowed, spec)
+                    .into_iter()
+                    .map(|(diagnosis, code)| from_browser(diagnosis, code))
+                    .chain(denied.into_iter().filter_map(|browser| {
+                        let error = excluded.check(browser).err()?;
+                        let source = Source::Browser(browser);
+                        Some(SourceDiagnosis::failed(source, Duration::ZERO, &error))
+                    }))
+                    .collect();
+                browsers.sort_by_key(|diagnosis| match diagnosis.source {
+                    Source::Browser(browser) => Browser::ALL.iter().position(|b| *b == browser),
+                    _ => None,
+                });
+                diagnoses.extend(browsers);
+            }
+            Source::Browser(browser) if excluded.check(*browser).is_ok() => {
+                let (diagnosis, code) = diagnose_browser(*browser, spec);
+                diagnoses.push(from_browser(diagnosis, code));
+            }
+            source => {
+                let start = Instant::now();
+                let result = source.locate(spec, strategy, excluded, &mut Vec::new());
+                let elapsed = start.elapsed();
+                diagnoses.push(match result {
+                    Ok((session, _)) => SourceDiagnosis {
+                        source: source.clone(),
+                        db_path: None,
+                        elapsed,
+                        status: SourceStatus::Session {
+                            expires: session.expires_at(),
+                        },
+                        hint: None,
+                    },
+                    Err(e) => SourceDiagnosis::failed(source.clone(), elapsed, &e),
+                });
+            }
+        }
+    }
+    DiagnosticsReport { sources: diagnoses }
+}
+
+/// The diagnosis of a browser as one of a source, given the code of its error.
+fn from_browser(diagnosis: BrowserDiagnosis, code: Option<&'static str>) -> SourceDiagnosis {
+    let details = |message| ErrorDetails {
+        code: code.unwrap_or("unknown"),
+        message,
+    };
+    let status = match diagnosis.status {
+        BrowserStatus::Unsupported => SourceStatus::Unsupported,
+        BrowserStatus::NotFound(message) => SourceStatus::Unavailable(details(message)),
+        BrowserStatus::Unreadable(message) => SourceStatus::Failed(details(message)),
+        BrowserStatus::NoSession => SourceStatus::NoSession,
+        BrowserStatus::Session { expires } => SourceStatus::Session { expires },
+    };
+    SourceDiagnosis {
+        source: Source::Browser(diagnosis.browser),
+        db_path: diagnosis.db_path,
+        elapsed: diagnosis.elapsed,
+        status,
+        hint: diagnosis.hint,
+    }
+}
+
+/// What [`diagnose_browsers`] found out about the cookie store of a browser.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrowserDiagnosis {
+    pub browser: Browser,
+    /// The cookie database, if it was located.
+    pub db_path: Option<PathBuf>,
+    /// How long locating and reading the cookie store took.
+    pub elapsed: Duration,
+    pub status: BrowserStatus,
+    /// What to do about a failure, if there's a likely fix.
+    pub hint: Option<String>,
+}
+
+/// The state of the cookie store of a browser, from worst to best.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BrowserStatus {
+    /// The browser can't be read on this platform, or its cargo feature is off.
+    Unsupported,
+    /// No cookie database was found, usually because the browser isn't installed.
+    NotFound(String),
+    /// The cookie database was found but couldn't be read.
+    Unreadable(String),
+    /// The cookie database holds no session cookie for adventofcode.com.
+    NoSession,
+    /// The cookie database holds a session cookie for adventofcode.com.
+    Session {
+        /// When the cookie expires, if the browser recorded it.
+        expires: Option<SystemTime>,
+    },
+}
+
+impl BrowserDiagnosis {
+    /// Whether a session cookie was found.
+    pub fn has_session(&self) -> bool {
+        matches!(self.status, BrowserStatus::Session { .. })
+    }
+}
+
+/// Check the cookie store of every browser, in the order of [`Browser::ALL`], and explain the
+/// failures.
+///
+/// This is what `aoc-session doctor` prints. The browsers are checked in parallel.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoc_session::diagnose_browsers;
+///
+/// for diagnosis in diagnose_browsers() {
+///     println!("{}: {:?}", diagnosis.browser, diagnosis.status);
+///     if let Some(hint) = diagnosis.hint {
+///         println!("  hint: {hint}");
+///     }
+/// }
+/// ```
+pub fn diagnose_browsers() -> Vec<BrowserDiagnosis> {
+    in_parallel(&Browser::ALL, &SessionSpec::ADVENT_OF_CODE)
+        .into_iter()
+        .map(|(diagnosis, _)| diagnosis)
+        .collect()
+}
+
+/// Diagnose the browsers in parallel, each with the code of its error, if any.
+fn in_parallel(
+    browsers: &[Browser],
+    spec: &SessionSpec,
+) -> Vec<(BrowserDiagnosis, Option<&'static str>)> {
+    let handles: Vec<_> = browsers
+        .iter()
+        .map(|&browser| {
+            let spec = spec.clone();
+            std::thread::spawn(move || diagnose_browser(browser, &spec))
+        })
+        .collect();
+    handles
+        .into_iter()
+        .zip(browsers)
+        .map(|(handle, &browser)| {
+            handle.join().unwrap_or_else(|_| {
+                let diagnosis = BrowserDiagnosis {
+                    browser,
+                    db_path: None,
+                    elapsed: Duration::ZERO,
+                    status: BrowserStatus::Unreadable(
+                        "the cookie store reader panicked".to_string(),
+                    ),
+                    hint: None,
+                };
+                (diagnosis, None)
+            })
+        })
+        .collect()
+}
+
+fn diagnose_browser(
+    browser: Browser,
+    spec: &SessionSpec,
+) -> (BrowserDiagnosis, Option<&'static str>) {
+    let start = Instant::now();
+    let mut db_path = None;
+    let mut error = None;
+    let status = if !browser.is_supported() {
+        BrowserStatus::Unsupported
+    } else {
+        match browser.find_store() {
+            Err(e) => {
+                let status = BrowserStatus::NotFound(e.to_string());
+                error = Some(e);
+                status
+            }
+            Ok(store) => {
+                db_path = Some(store.db_path.clone());
+                match store.sessions_for(spec) {
+                    Err(e) => {
+                        let status = BrowserStatus::Unreadable(e.to_string());
+                        error = Some(e);
+                        status
+                    }
+                    Ok(sessions) => match sessions.first() {
+                        Some(session) => BrowserStatus::Session {
+                            expires: session.expires_at(),
+                        },
+                        None => BrowserStatus::NoSession,
+                    },
+                }
+            }
+        }
+    };
+    let home = crate::dirs::home_dir();
+    let diagnosis = BrowserDiagnosis {
+        browser,
+        elapsed: start.elapsed(),
+        hint: error.as_ref().and_then(|e| hint(e, home.as_deref())),
+        db_path,
+        status,
+    };
+    (diagnosis, error.as_ref().map(Error::code))
+}
+
+/// A likely fix for the error, given the platform.
+fn hint(error: &Error, home: Option<&Path>) -> Option<String> {
+    match error {
+        Error::CookieStoreNotFound { browser, .. } => {
+            let dir = crate::sandbox::data_dirs(*browser, home?)
+                .into_iter()
+                .next()?;
+            Some(format!(
+                "{browser} is installed as a Snap or Flatpak, but {} holds no cookie database \
+                 yet; log in to adventofcode.com with it first",
+                dir.display()
+            ))
+        }
+        Error::Sandboxed { sandbox, .. } => Some(format!(
+            "run this outside of the {sandbox} sandbox, e.g. from a terminal that isn't part of \
+             an app, or set AOC_SESSION"
+        )),
+        Error::LockedDatabase { browser, .. } => Some(format!(
+            "the cookie database is locked while {browser} runs; close {browser} and try again"
+        )),
+        Error::PermissionDenied { path, .. } => Some(if cfg!(target_os = "macos") {
+            format!(
+                "grant read access to {}, e.g. Full Disk Access to the terminal",
+                path.display()
+            )
+        } else {
+            format!("grant read access to {}", path.display())
+        }),
+        Error::DecryptionFailed { browser, .. } if cfg!(target_os = "macos") => Some(format!(
+            "allow access to the \"{browser} Safe Storage\" item when the Keychain asks"
+        )),
+        Error::DecryptionFailed { browser, .. } if cfg!(target_os = "linux") => Some(format!(
+            "{browser} keeps the key of its cookies in the Secret Service; make sure a keyring \
+             daemon such as gnome-keyring is running and unlocked"
+        )),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hints() {
+        let locked = Error::LockedDatabase {
+            browser: Browser::Chrome,
+            path: PathBuf::from("/profile/Cookies"),
+        };
+        assert!(hint(&locked, None).unwrap().contains("close chrome"));
+        let denied = Error::PermissionDenied {
+            browser: Browser::Firefox,
+            path: PathBuf::from("/profile/cookies.sqlite"),
+        };
+        assert!(hint(&denied, None)
+            .unwrap()
+            .contains("/profile/cookies.sqlite"));
+        let unreadable = Error::CookieStoreUnreadable {
+            browser: Browser::Chrome,
+            path: PathBuf::from("/profile/Cookies"),
+            reason: "bogus".to_string(),
+        };
+        assert_eq!(hint(&unreadable, None), None);
+    }
+
+    #[test]
+    fn every_source_is_consulted() {
+        let dir = std::env::temp_dir().join("aoc-session-diagnose-test");
+        std::fs::create_dir_all(dir.join("directory")).unwrap();
+        std::fs::write(dir.join("full"), "25a16c74\n").unwrap();
+        std::fs::write(dir.join("empty"), "\n").unwrap();
+
+        let report = crate::AocSessionBuilder::new()
+            .file(dir.join("full"))
+            .file(dir.join("missing"))
+            .file(dir.join("directory"))
+            .file(dir.join("empty"))
+            .diagnose();
+        let statuses: Vec<_> = report.sources.iter().map(|d| &d.status).collect();
+        assert!(matches!(
+            statuses[0],
+            SourceStatus::Session { expires: None }
+        ));
+        assert!(matches!(statuses[1], SourceStatus::Unavailable(e) if e.code == "io"));
+        assert!(matches!(statuses[2], SourceStatus::Failed(e) if e.code == "io"));
+        assert_eq!(statuses[3], &SourceStatus::NoSession);
+        assert!(report.has_session());
+        assert!(!report.sources[1].is_available());
+        assert!(report.sources[2].is_available());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn snap_install_is_noticed() {
+        let home = std::env::temp_dir().join("aoc-session-snap-test");
+        std::fs::create_dir_all(home.join("snap/firefox/common/.mozilla/firefox")).unwrap();
+        let error = Error::CookieStoreNotFound {
+            browser: Browser::Firefox,
+            reason: "no profile".to_string(),
+        };
+        let hint = hint(&error, Some(&home)).unwrap();
+        assert!(hint.contains("snap/firefox"));
+        let error = Error::CookieStoreNotFound {
+            browser: Browser::Chrome,
+            reason: "no profile".to_string(),
+        };
+        assert_eq!(super::hint(&error, Some(&home)), None);
+    }
+}
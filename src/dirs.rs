@@ -0,0 +1,88 @@
+//! Platform-specific directories, following the XDG base directory specification on Linux.
+
+use std::env;
+use std::path::PathBuf;
+
+pub(crate) fn home_dir() -> Option<PathBuf> {
+    #[cfg(windows)]
+    let home = env::var_os("USERPROFILE");
+    #[cfg(not(windows))]
+    let home = env::var_os("HOME");
+    home.filter(|home| !home.is_empty()).map(PathBuf::from)
+}
+
+#[cfg_attr(windows, allow(dead_code))]
+fn xdg_dir(var: &str, fallback: &str) -> Option<PathBuf> {
+    env::var_os(var)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|home| home.join(fallback)))
+}
+
+/// The directory for the configuration files of this crate, e.g. `~/.config/aoc-session` on
+/// Linux and macOS, or `~/.config/aoc-session/profiles/<name>` in an account profile.
+pub(crate) fn config_dir() -> Option<PathBuf> {
+    shared_config_dir().map(crate::account_profile::scoped)
+}
+
+/// The configuration directory shared by all account profiles.
+pub(crate) fn shared_config_dir() -> Option<PathBuf> {
+    config_base().map(|base| base.join("aoc-session"))
+}
+
+/// The configuration directory that other Advent of Code tools share, e.g. `~/.config/aoc`.
+pub(crate) fn aoc_config_dir() -> Option<PathBuf> {
+    config_base().map(|base| base.join("aoc"))
+}
+
+fn config_base() -> Option<PathBuf> {
+    #[cfg(windows)]
+    let base = env::var_os("APPDATA").map(PathBuf::from);
+    #[cfg(not(windows))]
+    let base = xdg_dir("XDG_CONFIG_HOME", ".config");
+    #[cfg(test)]
+    let base = base.map(|_| env::temp_dir().join("aoc-session-test-config"));
+    base
+}
+
+/// The directory for the cache files of this crate, e.g. `~/.cache/aoc-session` on Linux, or
+/// `~/.cache/aoc-session/profiles/<name>` in an account profile.
+pub(crate) fn cache_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "macos")]
+    let base = home_dir().map(|home| home.join("Library/Caches"));
+    #[cfg(windows)]
+    let base = env::var_os("LOCALAPPDATA").map(PathBuf::from);
+    #[cfg(not(any(target_os = "macos", windows)))]
+    let base = xdg_dir("XDG_CACHE_HOME", ".cache");
+    // Tests never read or clear the cache of the developer running them, and likewise for the
+    // configuration and data directories.
+    #[cfg(test)]
+    let base = base.map(|_| env::temp_dir().join("aoc-session-test-cache"));
+    base.map(|base| crate::account_profile::scoped(base.join("aoc-session")))
+}
+
+/// The directory for the data files of this crate, shared by all account profiles, e.g.
+/// `~/.local/share/aoc-session` on Linux.
+#[cfg(feature = "encrypted-cache")]
+pub(crate) fn data_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "macos")]
+    let base = home_dir().map(|home| home.join("Library/Application Support"));
+    // The local application data directory also holds the cache directory.
+    #[cfg(windows)]
+    let base = env::var_os("LOCALAPPDATA").map(|dir| PathBuf::from(dir).join("data"));
+    #[cfg(not(any(target_os = "macos", windows)))]
+    let base = xdg_dir("XDG_DATA_HOME", ".local/share");
+    #[cfg(test)]
+    let base = base.map(|_| env::temp_dir().join("aoc-session-test-data"));
+    base.map(|base| base.join("aoc-session"))
+}
+
+/// The directory for the sockets of this crate, e.g. `/run/user/1000/aoc-session` on Linux. It
+/// falls back to the cache directory where `XDG_RUNTIME_DIR` isn't set, e.g. on macOS.
+#[cfg(all(unix, feature = "agent"))]
+pub(crate) fn runtime_dir() -> Option<PathBuf> {
+    env::var_os("XDG_RUNTIME_DIR")
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| crate::account_profile::scoped(PathBuf::from(dir).join("aoc-session")))
+        .or_else(cache_dir)
+}
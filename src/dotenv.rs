@@ -0,0 +1,89 @@
+//! Lookup of the session cookie value in `.env` files, without modifying the process environment.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::source::workspace_root;
+use crate::{AocSession, Error, Result};
+
+/// The `.env` files consulted by [`aoc_session_from_dotenv`], in order: the one in the current
+/// directory and the one in the workspace root.
+fn dotenv_paths() -> Vec<PathBuf> {
+    let Ok(cwd) = std::env::current_dir() else {
+        return Vec::new();
+    };
+    let mut paths = vec![cwd.join(".env")];
+    if let Some(root) = workspace_root(&cwd).filter(|root| *root != cwd) {
+        paths.push(root.join(".env"));
+    }
+    paths
+}
+
+/// Get the session cookie for Advent of Code from the variables listed in
+/// [`ENV_VARS`](crate::ENV_VARS) as defined in the `.env` file of the current directory or of the
+/// workspace root.
+///
+/// The process environment is left untouched.
+pub fn aoc_session_from_dotenv() -> Result<AocSession> {
+    for path in dotenv_paths() {
+        match from_dotenv_file(&path) {
+            Err(e) if e.is_absence() => continue,
+            result => return result,
+        }
+    }
+    Err(Error::EnvVarNotSet)
+}
+
+fn from_dotenv_file(path: &Path) -> Result<AocSession> {
+    let contents = std::fs::read_to_string(path).map_err(|source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let vars = parse(&contents);
+    crate::session_from_env_with(|name| vars.get(name).cloned())
+}
+
+/// Parse the `KEY=value` lines of a `.env` file. Comments, `export` prefixes, and quotes are
+/// handled; variable expansion and multiline values are not.
+fn parse(contents: &str) -> HashMap<String, String> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            if line.starts_with('#') {
+                return None;
+            }
+            let (key, value) = line.split_once('=')?;
+            let value = value.trim();
+            let value = match value.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let value = &value[1..];
+                    &value[..value.find(quote).unwrap_or(value.len())]
+                }
+                _ => value.split(" #").next().unwrap_or(value),
+            };
+            Some((key.trim().to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_dotenv() {
+        let vars = parse(
+            "# Advent of Code\n\
+             export AOC_SESSION=\"25a16c74\" # mine\n\
+             OTHER='a # b'\n\
+             PLAIN = value # comment\n\
+             not a variable\n",
+        );
+        assert_eq!(vars["AOC_SESSION"], "25a16c74");
+        assert_eq!(vars["OTHER"], "a # b");
+        assert_eq!(vars["PLAIN"], "value");
+        assert_eq!(vars.len(), 3);
+    }
+}
@@ -0,0 +1,122 @@
+//! Conversions of the session cookie value for tools other than Rust HTTP clients.
+
+use std::time::SystemTime;
+
+use crate::source::DOMAIN;
+use crate::AocSession;
+
+impl AocSession {
+    /// The arguments of a `curl` invocation that fetches the URL with the session cookie, e.g.
+    /// to be passed to [`std::process::Command::args`].
+    ///
+    /// Note that command-line arguments are visible to other users of the machine through the
+    /// process list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoc_session::AocSession;
+    ///
+    /// let session = AocSession::try_new("53616c7465645f5f".repeat(8)).unwrap();
+    /// let args = session.curl_args("https://adventofcode.com/2023/day/1/input");
+    /// assert_eq!(args[0], "--header");
+    /// ```
+    pub fn curl_args(&self, url: &str) -> Vec<String> {
+        vec![
+            "--header".to_string(),
+            self.to_cookie_header(),
+            url.to_string(),
+        ]
+    }
+
+    /// A `curl` command that fetches the URL with the session cookie, quoted for POSIX shells,
+    /// e.g. for Makefiles and shell scripts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoc_session::AocSession;
+    ///
+    /// let session = AocSession::try_new("53616c7465645f5f".repeat(8)).unwrap();
+    /// let command = session.to_curl_command("https://adventofcode.com/2023/day/1/input");
+    /// assert!(command.starts_with("curl --header 'Cookie: session=53616c74"));
+    /// ```
+    pub fn to_curl_command(&self, url: &str) -> String {
+        let mut command = String::from("curl");
+        for arg in self.curl_args(url) {
+            command.push(' ');
+            command.push_str(&shell_quote(&arg));
+        }
+        command
+    }
+
+    /// The session cookie in the Netscape `cookies.txt` format, as read by `curl --cookie`,
+    /// `wget --load-cookies`, `yt-dlp`, etc.
+    ///
+    /// The cookie applies to adventofcode.com and its subdomains over HTTPS. Its expiry is kept
+    /// if known; otherwise, it's written as a session cookie.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use aoc_session::aoc_session;
+    ///
+    /// let session = aoc_session().unwrap();
+    /// std::fs::write("cookies.txt", session.to_cookies_txt()).unwrap();
+    /// // $ curl --cookie cookies.txt https://adventofcode.com/2023/day/1/input
+    /// ```
+    pub fn to_cookies_txt(&self) -> String {
+        let expires = self
+            .expires_at()
+            .and_then(|time| time.duration_since(SystemTime::UNIX_EPOCH).ok())
+            .map_or(0, |secs| secs.as_secs());
+        // The fields are: domain, include subdomains, path, HTTPS only, expiry, name, value.
+        format!(
+            "# Netscape HTTP Cookie File\n\
+             .{DOMAIN}\tTRUE\t/\tTRUE\t{expires}\tsession\t{}\n",
+            self.value
+        )
+    }
+}
+
+/// Quote the argument for POSIX shells, unless it only has characters that need no quoting.
+fn shell_quote(arg: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || "-_./:=@%+,".contains(c);
+    if !arg.is_empty() && arg.chars().all(plain) {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curl_command() {
+        let session = AocSession::new("25a16c74");
+        assert_eq!(
+            session.to_curl_command("https://adventofcode.com/2023/day/1/input?a=1&b"),
+            "curl --header 'Cookie: session=25a16c74' \
+             'https://adventofcode.com/2023/day/1/input?a=1&b'"
+        );
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+        assert_eq!(shell_quote(""), "''");
+    }
+
+    #[test]
+    fn cookies_txt() {
+        let session = AocSession::new("25a16c74");
+        assert_eq!(
+            session.to_cookies_txt(),
+            "# Netscape HTTP Cookie File\n\
+             .adventofcode.com\tTRUE\t/\tTRUE\t0\tsession\t25a16c74\n"
+        );
+        let expires = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+        assert!(session
+            .with_expiry(Some(expires))
+            .to_cookies_txt()
+            .contains("\t1700000000\tsession\t"));
+    }
+}
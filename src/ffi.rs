@@ -0,0 +1,165 @@
+//! A C interface to [`aoc_session`](crate::aoc_session), with the `ffi` feature, for tools
+//! written in C, C++, Zig, and other languages that can call C.
+//!
+//! The declarations are in `include/aoc_session.h`. Build the library with
+//! `cargo rustc --release --features ffi --crate-type cdylib` (or `staticlib`) and link it:
+//!
+//! ```c
+//! #include <stdio.h>
+//! #include "aoc_session.h"
+//!
+//! int main(void) {
+//!     char *session = NULL;
+//!     if (aoc_session_get(&session) != AOC_SESSION_OK) {
+//!         fprintf(stderr, "%s\n", aoc_session_last_error());
+//!         return 1;
+//!     }
+//!     printf("%s\n", session);
+//!     aoc_session_free(session);
+//!     return 0;
+//! }
+//! ```
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CString};
+use std::panic::AssertUnwindSafe;
+
+use crate::Error;
+
+/// The session cookie value was found.
+pub const AOC_SESSION_OK: i32 = 0;
+/// No source holds a session cookie value.
+pub const AOC_SESSION_NOT_FOUND: i32 = 1;
+/// A null pointer was passed where a pointer was expected.
+pub const AOC_SESSION_NULL_ARGUMENT: i32 = 2;
+/// A source failed, e.g. a cookie database couldn't be decrypted.
+pub const AOC_SESSION_ERROR: i32 = 3;
+/// The library panicked, which is a bug.
+pub const AOC_SESSION_PANIC: i32 = 4;
+
+thread_local! {
+    /// The message of the last error on this thread, handed out by [`aoc_session_last_error`].
+    static LAST_ERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+
+/// Look the session cookie value up as [`aoc_session`](crate::aoc_session) does and store it in
+/// `*session` as a NUL-terminated string, which must be released with [`aoc_session_free`].
+///
+/// Returns [`AOC_SESSION_OK`] or one of the other `AOC_SESSION_*` codes, in which case
+/// `*session` is set to null and [`aoc_session_last_error`] describes the error.
+///
+/// # Safety
+///
+/// `session` must be null or point to writable memory for a pointer.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_session_get(session: *mut *mut c_char) -> i32 {
+    if session.is_null() {
+        set_last_error("session is a null pointer");
+        return AOC_SESSION_NULL_ARGUMENT;
+    }
+    // SAFETY: `session` isn't null, and the caller vouches that it's writable.
+    unsafe { *session = std::ptr::null_mut() };
+    let found = std::panic::catch_unwind(AssertUnwindSafe(crate::aoc_session));
+    match found {
+        // Values from files and browsers aren't format-checked, so a NUL byte may slip through.
+        Ok(Ok(found)) => match CString::new(found.expose_secret()) {
+            Ok(value) => {
+                set_last_error("");
+                // SAFETY: as above.
+                unsafe { *session = value.into_raw() };
+                AOC_SESSION_OK
+            }
+            Err(_) => {
+                set_last_error("the session cookie value contains a NUL byte");
+                AOC_SESSION_ERROR
+            }
+        },
+        Ok(Err(e)) => {
+            set_last_error(&e.to_string());
+            status(&e)
+        }
+        Err(_) => {
+            set_last_error("aoc-session panicked");
+            AOC_SESSION_PANIC
+        }
+    }
+}
+
+/// Release a session cookie value returned by [`aoc_session_get`], wiping it from memory with
+/// the `zeroize` feature. Does nothing with a null pointer.
+///
+/// # Safety
+///
+/// `session` must be null or a pointer set by [`aoc_session_get`] that wasn't released yet.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_session_free(session: *mut c_char) {
+    if session.is_null() {
+        return;
+    }
+    // SAFETY: the caller vouches that the pointer comes from `CString::into_raw` in
+    // `aoc_session_get` and is released only once.
+    let value = unsafe { CString::from_raw(session) };
+    if let Ok(mut value) = value.into_string() {
+        crate::secret::wipe(&mut value);
+    }
+}
+
+/// The message of the last error of [`aoc_session_get`] on the calling thread, or an empty
+/// string. It stays valid until the next call of [`aoc_session_get`] on the same thread, and
+/// must not be released.
+#[no_mangle]
+pub extern "C" fn aoc_session_last_error() -> *const c_char {
+    LAST_ERROR.with(|message| message.borrow().as_ptr())
+}
+
+fn set_last_error(message: &str) {
+    let message = CString::new(message.replace('\0', "")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = message);
+}
+
+/// The `AOC_SESSION_*` code of the error.
+fn status(error: &Error) -> i32 {
+    match error {
+        Error::InCi { .. } => AOC_SESSION_NOT_FOUND,
+        e if e.is_absence() => AOC_SESSION_NOT_FOUND,
+        _ => AOC_SESSION_ERROR,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CStr;
+
+    use super::*;
+
+    fn last_error() -> String {
+        // SAFETY: the pointer is valid until the next call on this thread.
+        unsafe { CStr::from_ptr(aoc_session_last_error()) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn null_arguments() {
+        assert_eq!(last_error(), "");
+        // SAFETY: null pointers are allowed.
+        assert_eq!(
+            unsafe { aoc_session_get(std::ptr::null_mut()) },
+            AOC_SESSION_NULL_ARGUMENT
+        );
+        assert_eq!(last_error(), "session is a null pointer");
+        // SAFETY: as above.
+        unsafe { aoc_session_free(std::ptr::null_mut()) };
+    }
+
+    #[test]
+    fn statuses() {
+        assert_eq!(status(&Error::NoSessionCookieFound), AOC_SESSION_NOT_FOUND);
+        assert_eq!(status(&Error::EnvVarNotSet), AOC_SESSION_NOT_FOUND);
+        assert_eq!(status(&Error::NoConfigDir), AOC_SESSION_ERROR);
+        assert_eq!(
+            status(&Error::BrowsersFailed(vec![Error::NoConfigDir])),
+            AOC_SESSION_ERROR
+        );
+    }
+}
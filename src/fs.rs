@@ -0,0 +1,207 @@
+//! File system helpers shared by the caches.
+
+use std::io::Write;
+use std::path::Path;
+#[cfg(any(
+    all(feature = "browser", not(target_family = "wasm")),
+    feature = "client"
+))]
+use std::path::PathBuf;
+
+use crate::{Error, Result};
+
+/// Write the file through a temporary sibling so that readers never see a partial file.
+#[cfg(feature = "client")]
+pub(crate) fn write_atomically(path: &Path, contents: &str) -> Result<()> {
+    write(path, contents, false)
+}
+
+/// Append to the file, creating it and its directory if needed.
+#[cfg(feature = "client")]
+pub(crate) fn append(path: &Path, contents: &str) -> Result<()> {
+    let io_error = |source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(io_error)?;
+    }
+    std::fs::OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(contents.as_bytes()))
+        .map_err(io_error)
+}
+
+/// Like [`write_atomically`], but the file is only readable by the current user on Unix.
+pub(crate) fn write_private(path: &Path, contents: &str) -> Result<()> {
+    write(path, contents, true)
+}
+
+fn write(path: &Path, contents: &str, private: bool) -> Result<()> {
+    let io_error = |source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(io_error)?;
+    }
+    let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    if private {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    #[cfg(not(unix))]
+    let _ = private;
+    options
+        .open(&tmp)
+        .and_then(|mut file| file.write_all(contents.as_bytes()))
+        .map_err(io_error)?;
+    std::fs::rename(&tmp, path).map_err(io_error)
+}
+
+/// A directory in the temporary directory, only accessible by the current user on Unix, removed
+/// with its contents on drop. It's always created anew, so nobody else can have planted files or
+/// links in it, as they could at a predictable path in a shared `/tmp`.
+#[cfg(any(
+    all(feature = "browser", not(target_family = "wasm")),
+    feature = "client"
+))]
+pub(crate) struct PrivateTempDir {
+    path: PathBuf,
+}
+
+#[cfg(any(
+    all(feature = "browser", not(target_family = "wasm")),
+    feature = "client"
+))]
+impl PrivateTempDir {
+    pub(crate) fn create() -> std::io::Result<PrivateTempDir> {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+        #[cfg_attr(not(unix), allow(unused_mut))]
+        let mut builder = std::fs::DirBuilder::new();
+        #[cfg(unix)]
+        std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+        let mut attempts = 0;
+        loop {
+            let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+            let path = std::env::temp_dir().join(format!("aoc-session-{}-{n}", std::process::id()));
+            match builder.create(&path) {
+                Ok(()) => return Ok(PrivateTempDir { path }),
+                // Left behind by a crashed process with the same ID, or planted.
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists && attempts < 100 => {
+                    attempts += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[cfg(any(
+    all(feature = "browser", not(target_family = "wasm")),
+    feature = "client"
+))]
+impl Drop for PrivateTempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+/// A copy of an SQLite database and its journals in a private temporary directory, removed on
+/// drop, for reading a database that its owner keeps locked.
+#[cfg(all(feature = "browser", not(target_family = "wasm")))]
+pub(crate) struct TempCopy {
+    path: PathBuf,
+    // Dropped after `path` is no longer needed, removing the copy.
+    _dir: PrivateTempDir,
+}
+
+#[cfg(all(feature = "browser", not(target_family = "wasm")))]
+impl TempCopy {
+    /// Copy the database at `path`, along with its `-wal` and `-journal` files, which hold the
+    /// writes not yet merged into it.
+    pub(crate) fn of_database(path: &Path) -> std::io::Result<TempCopy> {
+        let dir = PrivateTempDir::create()?;
+        let name = path.file_name().unwrap_or_default();
+        let copy = TempCopy {
+            path: dir.path().join(name),
+            _dir: dir,
+        };
+        std::fs::copy(path, &copy.path)?;
+        for suffix in ["-wal", "-journal"] {
+            let mut journal = path.as_os_str().to_owned();
+            journal.push(suffix);
+            let mut copied = copy.path.as_os_str().to_owned();
+            copied.push(suffix);
+            match std::fs::copy(&journal, &copied) {
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                result => drop(result?),
+            }
+        }
+        Ok(copy)
+    }
+
+    /// The path of the copied database.
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[cfg(all(
+    test,
+    any(
+        all(feature = "browser", not(target_family = "wasm")),
+        feature = "client"
+    )
+))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn private_temp_dirs_are_fresh() {
+        let dir = PrivateTempDir::create().unwrap();
+        let other = PrivateTempDir::create().unwrap();
+        assert_ne!(dir.path(), other.path());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(dir.path()).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o700);
+        }
+        std::fs::write(dir.path().join("headers"), "x").unwrap();
+        let path = dir.path().to_path_buf();
+        drop(dir);
+        assert!(!path.exists());
+    }
+
+    #[cfg(all(feature = "browser", not(target_family = "wasm")))]
+    #[test]
+    fn databases_are_copied_with_their_journals() {
+        let dir = std::env::temp_dir().join("aoc-session-temp-copy-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let db = dir.join("Cookies");
+        std::fs::write(&db, "db").unwrap();
+        std::fs::write(dir.join("Cookies-wal"), "wal").unwrap();
+        let copy = TempCopy::of_database(&db).unwrap();
+        assert_eq!(copy.path().file_name(), db.file_name());
+        assert_eq!(std::fs::read_to_string(copy.path()).unwrap(), "db");
+        let wal = copy.path().with_file_name("Cookies-wal");
+        assert_eq!(std::fs::read_to_string(&wal).unwrap(), "wal");
+        assert!(!copy.path().with_file_name("Cookies-journal").exists());
+        let copied = copy.path().to_path_buf();
+        drop(copy);
+        assert!(!copied.exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,126 @@
+//! Ready-made HTTP clients of other crates that send the session cookie to Advent of Code: a
+//! `reqwest` client with the `reqwest` feature, and a `ureq` agent with the `ureq` feature.
+
+use crate::{AocSession, DEFAULT_USER_AGENT};
+
+/// The site the session cookie is sent to.
+const URL: &str = "https://adventofcode.com/";
+
+impl AocSession {
+    /// The `Set-Cookie` line that scopes the session cookie to adventofcode.com.
+    fn set_cookie_line(&self) -> String {
+        let mut line = self.to_cookie_value();
+        line.push_str("; Domain=adventofcode.com; Path=/; Secure; HttpOnly");
+        line
+    }
+
+    /// A cookie jar of `reqwest` that holds the session cookie for adventofcode.com, with the
+    /// `reqwest` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// let jar = aoc_session::aoc_session().unwrap().cookie_jar();
+    /// let client = reqwest::Client::builder()
+    ///     .cookie_provider(Arc::new(jar))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    #[cfg(feature = "reqwest")]
+    pub fn cookie_jar(&self) -> reqwest::cookie::Jar {
+        let jar = reqwest::cookie::Jar::default();
+        let mut line = self.set_cookie_line();
+        jar.add_cookie_str(&line, &URL.parse().expect("the URL is valid"));
+        crate::secret::wipe(&mut line);
+        jar
+    }
+
+    /// A `reqwest` client that sends the session cookie to adventofcode.com, along with the
+    /// `User-Agent` of [`AocClient`](crate::AocClient), which identifies this crate as the AoC
+    /// maintainers ask of automated tools, with the `reqwest` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn run() -> reqwest::Result<()> {
+    /// let client = aoc_session::aoc_session().unwrap().reqwest_client()?;
+    /// let input = client
+    ///     .get("https://adventofcode.com/2024/day/1/input")
+    ///     .send()
+    ///     .await?
+    ///     .text()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "reqwest")]
+    pub fn reqwest_client(&self) -> reqwest::Result<reqwest::Client> {
+        reqwest::Client::builder()
+            .cookie_provider(std::sync::Arc::new(self.cookie_jar()))
+            .user_agent(DEFAULT_USER_AGENT)
+            .build()
+    }
+
+    /// A `ureq` agent that sends the session cookie to adventofcode.com, along with the
+    /// `User-Agent` of [`AocClient`](crate::AocClient), with the `ureq` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let agent = aoc_session::aoc_session().unwrap().ureq_agent().unwrap();
+    /// let input = agent
+    ///     .get("https://adventofcode.com/2024/day/1/input")
+    ///     .call()
+    ///     .unwrap()
+    ///     .body_mut()
+    ///     .read_to_string()
+    ///     .unwrap();
+    /// ```
+    #[cfg(feature = "ureq")]
+    pub fn ureq_agent(&self) -> Result<ureq::Agent, ureq::Error> {
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .user_agent(DEFAULT_USER_AGENT)
+            .build()
+            .into();
+        let uri = URL.parse().expect("the URL is valid");
+        let cookie = ureq::Cookie::parse(self.set_cookie_line(), &uri)?;
+        agent.cookie_jar_lock().insert(cookie, &uri)?;
+        Ok(agent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "reqwest")]
+    use reqwest::cookie::CookieStore;
+
+    use super::*;
+
+    #[cfg(feature = "reqwest")]
+    #[test]
+    fn jar_holds_the_session_for_adventofcode_com() {
+        let session = AocSession::new("25a16c74");
+        let jar = session.cookie_jar();
+        let cookies = jar.cookies(&URL.parse().unwrap()).unwrap();
+        assert_eq!(cookies, session.to_cookie_value().as_str());
+        assert!(jar
+            .cookies(&"https://example.com/".parse().unwrap())
+            .is_none());
+        assert!(jar
+            .cookies(&"http://adventofcode.com/".parse().unwrap())
+            .is_none());
+    }
+
+    #[cfg(feature = "ureq")]
+    #[test]
+    fn agent_holds_the_session_for_adventofcode_com() {
+        let session = AocSession::new("25a16c74");
+        let agent = session.ureq_agent().unwrap();
+        let jar = agent.cookie_jar_lock();
+        let cookie = jar.get("adventofcode.com", "/", "session").unwrap();
+        assert_eq!(cookie.value(), session.expose_secret());
+        assert_eq!(jar.iter().count(), 1);
+    }
+}
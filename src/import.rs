@@ -0,0 +1,164 @@
+//! Session cookies exported from browsers by hand, for browsers that can't be read directly.
+
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use crate::source::DOMAIN;
+use crate::{AocSession, Error, Result};
+
+/// Get the session cookie for Advent of Code from a cookie file in the Netscape `cookies.txt`
+/// format, as written by `curl --cookie-jar` and by many browser extensions.
+///
+/// If the file holds several session cookies for adventofcode.com, the one that expires last is
+/// returned.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoc_session::aoc_session_from_cookies_txt;
+///
+/// let session = aoc_session_from_cookies_txt("cookies.txt").unwrap();
+/// println!("My session ID: {session}");
+/// ```
+pub fn aoc_session_from_cookies_txt(path: impl AsRef<Path>) -> Result<AocSession> {
+    let path = path.as_ref();
+    let mut contents = read(path)?;
+    let session = parse_cookies_txt(&contents);
+    crate::secret::wipe(&mut contents);
+    session.ok_or(Error::NoSessionCookieFound)
+}
+
+/// Get the session cookie for Advent of Code from a HAR file, as saved by the network panel of
+/// the developer tools of most browsers after visiting adventofcode.com while logged in.
+///
+/// The session cookie sent with the latest request to adventofcode.com is returned.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoc_session::aoc_session_from_har;
+///
+/// let session = aoc_session_from_har("adventofcode.com.har").unwrap();
+/// println!("My session ID: {session}");
+/// ```
+#[cfg(feature = "har")]
+pub fn aoc_session_from_har(path: impl AsRef<Path>) -> Result<AocSession> {
+    let path = path.as_ref();
+    let mut contents = read(path)?;
+    let session = parse_har(&contents)
+        .map_err(|message| Error::InvalidHar(format!("{}: {message}", path.display())));
+    crate::secret::wipe(&mut contents);
+    session?.ok_or(Error::NoSessionCookieFound)
+}
+
+fn read(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path).map_err(|source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Whether the cookie domain covers adventofcode.com.
+fn is_aoc_domain(domain: &str) -> bool {
+    domain.trim_start_matches('.') == DOMAIN
+}
+
+fn parse_cookies_txt(contents: &str) -> Option<AocSession> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            // curl marks HttpOnly cookies with a prefix that otherwise looks like a comment.
+            let line = line.strip_prefix("#HttpOnly_").unwrap_or(line);
+            if line.starts_with('#') {
+                return None;
+            }
+            let fields: Vec<&str> = line.trim_end_matches('\r').split('\t').collect();
+            let [domain, _, _, _, expires, name, value] = fields[..] else {
+                return None;
+            };
+            if !is_aoc_domain(domain) || name != "session" || value.is_empty() {
+                return None;
+            }
+            // Session cookies have an expiry of 0.
+            let expires = expires
+                .parse()
+                .ok()
+                .filter(|&secs| secs != 0)
+                .map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs));
+            Some(AocSession::unchecked(value.to_string()).with_expiry(expires))
+        })
+        // `max_by_key` returns the last maximum, i.e. the last of equally old cookies.
+        .max_by_key(AocSession::expires_at)
+}
+
+#[cfg(feature = "har")]
+fn parse_har(contents: &str) -> std::result::Result<Option<AocSession>, String> {
+    use serde_json::Value;
+
+    let har: Value = serde_json::from_str(contents).map_err(|e| e.to_string())?;
+    let entries = har
+        .pointer("/log/entries")
+        .and_then(Value::as_array)
+        .ok_or("no `log.entries` array")?;
+    let session = entries
+        .iter()
+        .filter_map(|entry| entry.get("request"))
+        .filter(|request| {
+            request
+                .get("url")
+                .and_then(Value::as_str)
+                .and_then(|url| url.split_once("://"))
+                .map(|(_, rest)| rest.split(['/', ':', '?']).next().unwrap_or(rest))
+                .is_some_and(|host| host == DOMAIN || host.ends_with(&format!(".{DOMAIN}")))
+        })
+        .filter_map(|request| request.get("cookies")?.as_array())
+        .flatten()
+        .filter(|cookie| cookie.get("name").and_then(Value::as_str) == Some("session"))
+        .filter_map(|cookie| cookie.get("value")?.as_str())
+        // The entries are in chronological order.
+        .rfind(|value| !value.is_empty())
+        .map(|value| AocSession::unchecked(value.to_string()));
+    Ok(session)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cookies_txt() {
+        let contents = "# Netscape HTTP Cookie File\n\
+            .example.com\tTRUE\t/\tTRUE\t0\tsession\tff\n\
+            #HttpOnly_.adventofcode.com\tTRUE\t/\tTRUE\t1700000000\tsession\t25a16c74\n\
+            adventofcode.com\tFALSE\t/\tTRUE\t1600000000\tsession\t53616c74\n\
+            .adventofcode.com\tTRUE\t/\tTRUE\t0\tother\tee\n";
+        let session = parse_cookies_txt(contents).unwrap();
+        assert_eq!(session.as_str(), "25a16c74");
+        assert_eq!(
+            session.expires_at(),
+            Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000))
+        );
+        assert!(parse_cookies_txt("# Netscape HTTP Cookie File\n").is_none());
+    }
+
+    #[cfg(feature = "har")]
+    #[test]
+    fn har() {
+        let contents = r#"{"log": {"entries": [
+            {"request": {"url": "https://adventofcode.com/2023", "cookies": [
+                {"name": "session", "value": "53616c74"}
+            ]}},
+            {"request": {"url": "https://example.com/", "cookies": [
+                {"name": "session", "value": "ff"}
+            ]}},
+            {"request": {"url": "https://adventofcode.com/2023/day/1", "cookies": [
+                {"name": "_ga", "value": "GA1"},
+                {"name": "session", "value": "25a16c74"}
+            ]}}
+        ]}}"#;
+        assert_eq!(parse_har(contents).unwrap().unwrap().as_str(), "25a16c74");
+        assert!(matches!(parse_har(r#"{"log": {"entries": []}}"#), Ok(None)));
+        assert!(parse_har("{}").is_err());
+        assert!(parse_har("not json").is_err());
+    }
+}
@@ -0,0 +1,398 @@
+//! Storage of the session cookie value in the OS credential store: the Windows Credential
+//! Manager, the macOS Keychain, or the Secret Service (e.g. GNOME Keyring, KWallet) on Linux
+//! through `secret-tool` from libsecret.
+
+use crate::{AocSession, Error, Result};
+
+/// The service name the session cookie value is stored under.
+const SERVICE: &str = "aoc-session";
+/// The account name the session cookie value is stored under.
+#[cfg_attr(
+    not(any(target_os = "linux", target_os = "macos", windows)),
+    allow(dead_code)
+)]
+const ACCOUNT: &str = "session";
+/// The service name the key of the `encrypted-cache` feature is stored under.
+#[cfg(feature = "encrypted-cache")]
+const SEALING_KEY_SERVICE: &str = "aoc-session-sealing-key";
+
+/// The service name of the account profile in use, e.g. `aoc-session/work`.
+fn service() -> String {
+    match crate::account_profile() {
+        Some(name) => format!("{SERVICE}/{name}"),
+        None => SERVICE.to_string(),
+    }
+}
+
+impl AocSession {
+    /// Store the session cookie value in the OS credential store, replacing any stored value.
+    ///
+    /// Afterwards, [`aoc_session`](crate::aoc_session) finds it there without scanning the
+    /// browsers.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use aoc_session::{aoc_session_from, Browser};
+    ///
+    /// // Scan the slow browser cookie store once...
+    /// let session = aoc_session_from(Browser::Chrome).unwrap();
+    /// session.store_in_keyring().unwrap();
+    /// // ...and later runs will find the session cookie value in the keyring.
+    /// ```
+    pub fn store_in_keyring(&self) -> Result<()> {
+        store(&service(), &self.value)
+    }
+}
+
+/// Get the session cookie for Advent of Code from the OS credential store, where it was put by
+/// [`AocSession::store_in_keyring`].
+///
+/// Returns [`Error::KeyringEntryNotFound`] if no value is stored.
+pub fn aoc_session_from_keyring() -> Result<AocSession> {
+    load(&service()).map(AocSession::unchecked)
+}
+
+/// Remove the session cookie value from the OS credential store, if it's there.
+pub fn delete_from_keyring() -> Result<()> {
+    match delete(&service()) {
+        Err(Error::KeyringEntryNotFound) => Ok(()),
+        result => result,
+    }
+}
+
+/// The key that the token file and the session cache are sealed with, in hexadecimal.
+#[cfg(feature = "encrypted-cache")]
+pub(crate) fn load_sealing_key() -> Result<String> {
+    load(SEALING_KEY_SERVICE)
+}
+
+#[cfg(feature = "encrypted-cache")]
+pub(crate) fn store_sealing_key(key: &str) -> Result<()> {
+    store(SEALING_KEY_SERVICE, key)
+}
+
+/// Run `program`, or return `None` if it isn't installed, in which case there is no credential
+/// store to talk to.
+// Unit tests never touch the credential store of the developer running them: to them it's
+// empty, and storing fails.
+fn store(service: &str, value: &str) -> Result<()> {
+    if cfg!(test) {
+        return Err(Error::Keyring(
+            "unit tests don't store anything".to_string(),
+        ));
+    }
+    platform::store(service, value)
+}
+
+fn load(service: &str) -> Result<String> {
+    if cfg!(test) {
+        return Err(Error::KeyringEntryNotFound);
+    }
+    platform::load(service)
+}
+
+fn delete(service: &str) -> Result<()> {
+    if cfg!(test) {
+        return Err(Error::KeyringEntryNotFound);
+    }
+    platform::delete(service)
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn run(program: &str, args: &[&str], stdin: Option<&str>) -> Result<Option<std::process::Output>> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let mut child = match Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(Error::Keyring(format!("failed to run {program}: {e}"))),
+    };
+    let mut pipe = child.stdin.take().expect("stdin is piped");
+    if let Some(stdin) = stdin {
+        pipe.write_all(stdin.as_bytes())
+            .map_err(|e| Error::Keyring(format!("failed to write to {program}: {e}")))?;
+    }
+    drop(pipe);
+    child
+        .wait_with_output()
+        .map(Some)
+        .map_err(|e| Error::Keyring(format!("failed to wait for {program}: {e}")))
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn not_installed(program: &str) -> Error {
+    Error::Keyring(format!("{program} is not installed"))
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn failure(program: &str, output: &std::process::Output) -> Error {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Error::Keyring(format!("{program} failed: {}", stderr.trim()))
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{failure, not_installed, run, ACCOUNT};
+    use crate::{Error, Result};
+
+    fn attributes(service: &str) -> [&str; 4] {
+        ["service", service, "account", ACCOUNT]
+    }
+
+    pub(super) fn store(service: &str, value: &str) -> Result<()> {
+        let mut args = vec!["store", "--label=Advent of Code session"];
+        args.extend(attributes(service));
+        // The secret is read from stdin so that it doesn't show up in the process list.
+        let output =
+            run("secret-tool", &args, Some(value))?.ok_or_else(|| not_installed("secret-tool"))?;
+        if !output.status.success() {
+            return Err(failure("secret-tool", &output));
+        }
+        Ok(())
+    }
+
+    pub(super) fn load(service: &str) -> Result<String> {
+        let mut args = vec!["lookup"];
+        args.extend(attributes(service));
+        let output = run("secret-tool", &args, None)?.ok_or(Error::KeyringEntryNotFound)?;
+        let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        match (output.status.success(), value.is_empty()) {
+            (true, false) => Ok(value),
+            // `secret-tool` silently exits with 1 when nothing is stored.
+            (_, true) if output.stderr.is_empty() => Err(Error::KeyringEntryNotFound),
+            _ => Err(failure("secret-tool", &output)),
+        }
+    }
+
+    pub(super) fn delete(service: &str) -> Result<()> {
+        let mut args = vec!["clear"];
+        args.extend(attributes(service));
+        let output = run("secret-tool", &args, None)?.ok_or(Error::KeyringEntryNotFound)?;
+        if !output.status.success() {
+            return Err(failure("secret-tool", &output));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{failure, not_installed, run, ACCOUNT};
+    use crate::{Error, Result};
+
+    /// The exit code of `security` when the item could not be found.
+    const ITEM_NOT_FOUND: i32 = 44;
+
+    pub(super) fn store(service: &str, value: &str) -> Result<()> {
+        // A newline would end the command below and start another one.
+        if value.chars().any(char::is_control) {
+            return Err(Error::Keyring(
+                "the value contains control characters".to_string(),
+            ));
+        }
+        // In interactive mode, `security` reads the command from stdin, which keeps the secret
+        // out of the process list.
+        let mut command = format!(
+            "add-generic-password -U -s \"{service}\" -a \"{ACCOUNT}\" -w \"{}\"\n",
+            value.replace('\\', "\\\\").replace('"', "\\\"")
+        );
+        let output = run("security", &["-i"], Some(&command));
+        crate::secret::wipe(&mut command);
+        let output = output?.ok_or_else(|| not_installed("security"))?;
+        if !output.status.success() || !output.stderr.is_empty() {
+            return Err(failure("security", &output));
+        }
+        Ok(())
+    }
+
+    pub(super) fn load(service: &str) -> Result<String> {
+        let output = run(
+            "security",
+            &["find-generic-password", "-s", service, "-a", ACCOUNT, "-w"],
+            None,
+        )?
+        .ok_or(Error::KeyringEntryNotFound)?;
+        match output.status.code() {
+            Some(0) => Ok(String::from_utf8_lossy(&output.stdout).trim().to_string()),
+            Some(ITEM_NOT_FOUND) => Err(Error::KeyringEntryNotFound),
+            _ => Err(failure("security", &output)),
+        }
+    }
+
+    pub(super) fn delete(service: &str) -> Result<()> {
+        let output = run(
+            "security",
+            &["delete-generic-password", "-s", service, "-a", ACCOUNT],
+            None,
+        )?
+        .ok_or(Error::KeyringEntryNotFound)?;
+        match output.status.code() {
+            Some(0) => Ok(()),
+            Some(ITEM_NOT_FOUND) => Err(Error::KeyringEntryNotFound),
+            _ => Err(failure("security", &output)),
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    //! Bindings to the Credential Manager functions of `advapi32.dll`.
+
+    use std::ffi::{c_void, OsStr};
+    use std::os::windows::ffi::OsStrExt;
+    use std::ptr;
+
+    use super::ACCOUNT;
+    use crate::{Error, Result};
+
+    const CRED_TYPE_GENERIC: u32 = 1;
+    const CRED_PERSIST_LOCAL_MACHINE: u32 = 2;
+    const ERROR_NOT_FOUND: i32 = 1168;
+
+    #[repr(C)]
+    struct FileTime {
+        low_date_time: u32,
+        high_date_time: u32,
+    }
+
+    #[repr(C)]
+    struct Credential {
+        flags: u32,
+        kind: u32,
+        target_name: *mut u16,
+        comment: *mut u16,
+        last_written: FileTime,
+        credential_blob_size: u32,
+        credential_blob: *mut u8,
+        persist: u32,
+        attribute_count: u32,
+        attributes: *mut c_void,
+        target_alias: *mut u16,
+        user_name: *mut u16,
+    }
+
+    #[link(name = "advapi32")]
+    extern "system" {
+        fn CredReadW(
+            target_name: *const u16,
+            kind: u32,
+            flags: u32,
+            credential: *mut *mut Credential,
+        ) -> i32;
+        fn CredWriteW(credential: *const Credential, flags: u32) -> i32;
+        fn CredDeleteW(target_name: *const u16, kind: u32, flags: u32) -> i32;
+        fn CredFree(buffer: *const c_void);
+    }
+
+    fn wide(s: &str) -> Vec<u16> {
+        OsStr::new(s).encode_wide().chain(Some(0)).collect()
+    }
+
+    fn last_error(action: &str) -> Error {
+        let error = std::io::Error::last_os_error();
+        if error.raw_os_error() == Some(ERROR_NOT_FOUND) {
+            Error::KeyringEntryNotFound
+        } else {
+            Error::Keyring(format!("failed to {action} the credential: {error}"))
+        }
+    }
+
+    pub(super) fn store(service: &str, value: &str) -> Result<()> {
+        let mut target_name = wide(service);
+        let mut user_name = wide(ACCOUNT);
+        let mut blob = value.as_bytes().to_vec();
+        let credential = Credential {
+            flags: 0,
+            kind: CRED_TYPE_GENERIC,
+            target_name: target_name.as_mut_ptr(),
+            comment: ptr::null_mut(),
+            last_written: FileTime {
+                low_date_time: 0,
+                high_date_time: 0,
+            },
+            credential_blob_size: blob.len() as u32,
+            credential_blob: blob.as_mut_ptr(),
+            persist: CRED_PERSIST_LOCAL_MACHINE,
+            attribute_count: 0,
+            attributes: ptr::null_mut(),
+            target_alias: ptr::null_mut(),
+            user_name: user_name.as_mut_ptr(),
+        };
+        // SAFETY: all pointers in `credential` point to live buffers of the declared sizes.
+        if unsafe { CredWriteW(&credential, 0) } == 0 {
+            return Err(last_error("write"));
+        }
+        Ok(())
+    }
+
+    pub(super) fn load(service: &str) -> Result<String> {
+        let target_name = wide(service);
+        let mut credential = ptr::null_mut();
+        // SAFETY: `target_name` is NUL-terminated and `credential` is a valid out-pointer.
+        if unsafe { CredReadW(target_name.as_ptr(), CRED_TYPE_GENERIC, 0, &mut credential) } == 0 {
+            return Err(last_error("read"));
+        }
+        // SAFETY: on success, `credential` points to a credential allocated by the system, whose
+        // blob has the declared size. It's freed exactly once, right after being copied.
+        let value = unsafe {
+            let blob = std::slice::from_raw_parts(
+                (*credential).credential_blob,
+                (*credential).credential_blob_size as usize,
+            );
+            let value = String::from_utf8_lossy(blob).into_owned();
+            CredFree(credential as *const c_void);
+            value
+        };
+        Ok(value)
+    }
+
+    pub(super) fn delete(service: &str) -> Result<()> {
+        let target_name = wide(service);
+        // SAFETY: `target_name` is NUL-terminated.
+        if unsafe { CredDeleteW(target_name.as_ptr(), CRED_TYPE_GENERIC, 0) } == 0 {
+            return Err(last_error("delete"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod platform {
+    use crate::{Error, Result};
+
+    fn unsupported() -> Error {
+        Error::Keyring("no credential store is supported on this platform".to_string())
+    }
+
+    pub(super) fn store(_service: &str, _value: &str) -> Result<()> {
+        Err(unsupported())
+    }
+
+    pub(super) fn load(_service: &str) -> Result<String> {
+        Err(unsupported())
+    }
+
+    pub(super) fn delete(_service: &str) -> Result<()> {
+        Err(unsupported())
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_tool_is_not_an_error() {
+        assert!(run("aoc-session-no-such-tool", &[], None)
+            .unwrap()
+            .is_none());
+    }
+}
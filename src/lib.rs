@@ -2,26 +2,373 @@
 
 use core::fmt;
 use std::fmt::{Debug, Display};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
+
+mod account_profile;
+#[cfg(feature = "agent")]
+mod agent;
+#[cfg(feature = "async")]
+mod async_api;
+mod browser;
+pub mod build;
+mod builder;
+#[cfg(feature = "cdp")]
+mod cdp;
+mod ci;
+#[cfg(feature = "client")]
+mod client;
+mod config;
+mod diagnostics;
+mod dirs;
+#[cfg(feature = "dotenv")]
+mod dotenv;
+mod export;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod fs;
+#[cfg(any(feature = "reqwest", feature = "ureq"))]
+mod http_clients;
+mod import;
+#[cfg(feature = "keyring")]
+mod keyring;
+mod login;
+mod profile;
+mod provider;
+mod puzzle_id;
+#[cfg(feature = "remote")]
+mod remote;
+mod sandbox;
+pub mod schedule;
+mod sealed;
+mod secret;
+mod secret_manager;
+mod selection;
+#[cfg(feature = "serde")]
+mod serde_impls;
+mod session_cache;
+mod source;
+mod spec;
+mod trace;
+mod watch;
+#[cfg(feature = "wsl")]
+mod wsl;
+
+pub use account_profile::{
+    account_profile, account_profiles, set_account_profile, PROFILE_ENV_VAR,
+};
+#[cfg(feature = "agent")]
+pub use agent::{agent_socket_path, aoc_session_from_agent, run_agent, stop_agent};
+#[cfg(feature = "macros")]
+pub use aoc_session_macros::aoc_input;
+#[cfg(all(feature = "async", feature = "client"))]
+pub use async_api::AsyncAocClient;
+#[cfg(feature = "async")]
+pub use async_api::{aoc_session_async, watch_session_async, SessionStream};
+pub use browser::{aoc_session_from_cookie_db, Browser, DiscoveredSession};
+pub use builder::AocSessionBuilder;
+#[cfg(feature = "cdp")]
+pub use cdp::{aoc_session_from_cdp, DEFAULT_CDP_PORT};
+#[cfg(feature = "client")]
+pub use client::{
+    compare_sessions, Account, AccountStatus, AnswerBounds, AocClient, AocClientBuilder,
+    ClientEvent, ComparedSession, DayCompletion, DayTimes, Hint, InputIntegrity, Leaderboard,
+    Member, PartTime, PersonalTimes, Progress, Puzzle, RetryPolicy, SessionComparison, Submission,
+    SubmitOutcome,
+};
+pub use config::{Config, SOURCE_NAMES};
+pub use diagnostics::{
+    diagnose, diagnose_browsers, BrowserDiagnosis, BrowserStatus, DiagnosticsReport, ErrorDetails,
+    SourceDiagnosis, SourceStatus,
+};
+#[cfg(feature = "dotenv")]
+pub use dotenv::aoc_session_from_dotenv;
+pub use import::aoc_session_from_cookies_txt;
+#[cfg(feature = "har")]
+pub use import::aoc_session_from_har;
+#[cfg(feature = "keyring")]
+pub use keyring::{aoc_session_from_keyring, delete_from_keyring};
+pub use login::{aoc_session_from_login, LOGIN_URL};
+pub use profile::{
+    aoc_session_from_firefox_profile, BrowserProfile, BrowserSource, FirefoxProfile,
+};
+pub use provider::{ChainProvider, SessionProvider, StaticProvider};
+pub use puzzle_id::{PuzzleDay, PuzzleId, PuzzleYear};
+#[cfg(feature = "remote")]
+pub use remote::aoc_session_from_ssh;
+#[cfg(feature = "secret-managers")]
+pub use secret_manager::aoc_session_from_secret_manager;
+pub use secret_manager::SecretManager;
+pub use selection::SelectionStrategy;
+pub use source::{
+    delete_token_file, session_file_paths, token_file_path, Source, SourceWarning,
+    DOCKER_SECRET_PATH, SESSION_FILE_NAME,
+};
+pub use spec::{session_for, SessionSpec};
+pub use watch::{watch_session, SessionWatcher};
+#[cfg(feature = "wsl")]
+pub use wsl::{aoc_session_from_wsl, is_wsl};
 
 /// The error type for this crate.
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("No session cookie found")]
     NoSessionCookieFound,
-    #[error("Rookie crate error: {0}")]
-    RookieError(anyhow::Error),
+    #[error("None of the environment variables {ENV_VARS:?} is set")]
+    EnvVarNotSet,
+    /// No cookie database of the browser was found, usually because it isn't installed.
+    #[error("No cookie database of {browser} was found: {reason}")]
+    CookieStoreNotFound { browser: Browser, reason: String },
+    /// The cookie database of the browser can't be read by the current user.
+    #[error("Permission denied to read the cookie database of {browser} at {}", path.display())]
+    PermissionDenied { browser: Browser, path: PathBuf },
+    /// The browser holds a lock on its cookie database, usually because it's running.
+    #[error("The cookie database of {browser} at {} is locked", path.display())]
+    LockedDatabase { browser: Browser, path: PathBuf },
+    /// The key that the browser encrypts its cookies with couldn't be retrieved or didn't work,
+    /// e.g. because access to the macOS Keychain or the Secret Service was denied.
+    #[error("Failed to decrypt the cookies of {browser}: {reason}")]
+    DecryptionFailed { browser: Browser, reason: String },
+    /// The cookie database of the browser couldn't be read for another reason, e.g. an unknown
+    /// format.
+    #[error("Failed to read the cookie database of {browser} at {}: {reason}", path.display())]
+    CookieStoreUnreadable {
+        browser: Browser,
+        path: PathBuf,
+        reason: String,
+    },
+    /// No browser holds a session cookie, and the cookie stores of some couldn't be read, for
+    /// the listed reasons. Browsers that aren't installed are not listed.
+    #[error("No session cookie found; {}", failures(.0))]
+    BrowsersFailed(Vec<Error>),
+    #[error("Cookies of {0} can't be read on this platform or without its cargo feature")]
+    UnsupportedBrowser(Browser),
+    /// Reading the browser may prompt for the password of the macOS Keychain, which
+    /// [`AocSessionBuilder::allow_keychain_prompt`] forbade.
+    #[error("Reading {0} may prompt for the macOS Keychain password, which isn't allowed")]
+    KeychainPromptNotAllowed(Browser),
+    /// The browser was excluded with [`AocSessionBuilder::exclude_browser`] or the
+    /// `exclude_browsers` setting of the configuration file.
+    #[error("Reading {0} isn't allowed; it's excluded")]
+    BrowserExcluded(Browser),
+    #[error("Unknown browser: {0:?}")]
+    UnknownBrowser(String),
+    #[error("The cookie store of {browser} can't be seen from inside the {sandbox} sandbox")]
+    Sandboxed {
+        browser: Browser,
+        sandbox: &'static str,
+    },
+    /// The browsers weren't scanned because this runs on a continuous integration service, where
+    /// none is logged in to Advent of Code. The hint tells how to pass the session cookie value
+    /// from a secret of the service.
+    #[error("The browsers aren't scanned on {ci}; {hint}")]
+    InCi {
+        ci: &'static str,
+        hint: &'static str,
+    },
+    #[error("{browser} has no profile {name:?}")]
+    ProfileNotFound { browser: Browser, name: String },
+    #[error("Invalid account profile name {0:?}; use letters, digits, '-' and '_'")]
+    InvalidProfileName(String),
+    #[error("I/O error on {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Invalid configuration: {0}")]
+    InvalidConfig(String),
+    /// The file holds a session cookie value sealed by the `encrypted-cache` feature, which
+    /// couldn't be decrypted.
+    #[error("Failed to decrypt {}: {reason}", path.display())]
+    SealedFile { path: PathBuf, reason: String },
+    #[cfg(feature = "har")]
+    #[error("Invalid HAR file: {0}")]
+    InvalidHar(String),
+    #[error("Malformed session cookie value: {0}")]
+    MalformedToken(String),
+    #[error("Timed out after {0:?}")]
+    Timeout(Duration),
+    #[error("The configuration directory is unknown; set HOME")]
+    NoConfigDir,
+    #[cfg(feature = "keyring")]
+    #[error("No session cookie value is stored in the keyring")]
+    KeyringEntryNotFound,
+    #[cfg(feature = "keyring")]
+    #[error("Keyring error: {0}")]
+    Keyring(String),
+    #[cfg(feature = "remote")]
+    #[error("SSH error: {0}")]
+    Ssh(String),
+    #[cfg(feature = "cdp")]
+    #[error("Chrome DevTools Protocol error: {0}")]
+    Cdp(String),
+    #[cfg(feature = "secret-managers")]
+    #[error("Password manager error: {0}")]
+    SecretManager(String),
+    #[cfg(feature = "agent")]
+    #[error("No agent is running; start one with `aoc-session agent`")]
+    AgentNotRunning,
+    #[cfg(feature = "agent")]
+    #[error("Agent error: {0}")]
+    Agent(String),
+    #[cfg(feature = "wsl")]
+    #[error("This isn't the Windows Subsystem for Linux")]
+    NotWsl,
+    #[cfg(feature = "wsl")]
+    #[error(
+        "{0} encrypts its cookies in a way only {0} itself can undo; log in with Firefox or \
+         export the cookie to a cookies.txt file"
+    )]
+    AppBoundEncryption(Browser),
+    #[cfg(feature = "client")]
+    #[error("Request to {url} failed with status {status}")]
+    HttpStatus { url: String, status: u16 },
+    #[cfg(feature = "client")]
+    #[error("HTTP transport error: {0}")]
+    Transport(String),
+    #[cfg(feature = "client")]
+    #[error("Advent of Code rejected the session; log in again")]
+    SessionRejected,
+    #[error("Advent of Code had no event in {0}")]
+    NoSuchYear(u16),
+    #[error("Advent of Code has no day {0}")]
+    NoSuchDay(u8),
+    #[error("Advent of Code {year} has no day {day}")]
+    NoSuchPuzzle { year: u16, day: u8 },
+    #[cfg(feature = "client")]
+    #[error("Unexpected response from Advent of Code: {0}")]
+    UnexpectedResponse(String),
+    /// Advent of Code asked to wait before submitting another answer for the day, and the wait
+    /// isn't over yet.
+    #[cfg(feature = "client")]
+    #[error("Wait {}s before submitting another answer", remaining.as_secs())]
+    CooldownActive { remaining: Duration },
+    /// The client is [offline](AocClient::offline), and the page at the URL isn't cached.
+    #[cfg(feature = "client")]
+    #[error("{0} isn't cached, and the client is offline")]
+    Offline(String),
+}
+
+impl Error {
+    /// A stable, `snake_case` identifier of the kind of error, e.g. `no_session_cookie_found`,
+    /// for tools that branch on it rather than on the message.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoc_session::Error;
+    ///
+    /// assert_eq!(Error::NoSessionCookieFound.code(), "no_session_cookie_found");
+    /// ```
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::NoSessionCookieFound => "no_session_cookie_found",
+            Error::EnvVarNotSet => "env_var_not_set",
+            Error::CookieStoreNotFound { .. } => "cookie_store_not_found",
+            Error::PermissionDenied { .. } => "permission_denied",
+            Error::LockedDatabase { .. } => "locked_database",
+            Error::DecryptionFailed { .. } => "decryption_failed",
+            Error::CookieStoreUnreadable { .. } => "cookie_store_unreadable",
+            Error::BrowsersFailed(_) => "browsers_failed",
+            Error::UnsupportedBrowser(_) => "unsupported_browser",
+            Error::KeychainPromptNotAllowed(_) => "keychain_prompt_not_allowed",
+            Error::BrowserExcluded(_) => "browser_excluded",
+            Error::UnknownBrowser(_) => "unknown_browser",
+            Error::ProfileNotFound { .. } => "profile_not_found",
+            Error::InvalidProfileName(_) => "invalid_profile_name",
+            Error::Sandboxed { .. } => "sandboxed",
+            Error::InCi { .. } => "in_ci",
+            Error::Io { .. } => "io",
+            Error::InvalidConfig(_) => "invalid_config",
+            Error::SealedFile { .. } => "sealed_file",
+            #[cfg(feature = "har")]
+            Error::InvalidHar(_) => "invalid_har",
+            Error::MalformedToken(_) => "malformed_token",
+            Error::Timeout(_) => "timeout",
+            Error::NoConfigDir => "no_config_dir",
+            #[cfg(feature = "keyring")]
+            Error::KeyringEntryNotFound => "keyring_entry_not_found",
+            #[cfg(feature = "keyring")]
+            Error::Keyring(_) => "keyring",
+            #[cfg(feature = "remote")]
+            Error::Ssh(_) => "ssh",
+            #[cfg(feature = "cdp")]
+            Error::Cdp(_) => "cdp",
+            #[cfg(feature = "secret-managers")]
+            Error::SecretManager(_) => "secret_manager",
+            #[cfg(feature = "agent")]
+            Error::AgentNotRunning => "agent_not_running",
+            #[cfg(feature = "agent")]
+            Error::Agent(_) => "agent",
+            #[cfg(feature = "wsl")]
+            Error::NotWsl => "not_wsl",
+            #[cfg(feature = "wsl")]
+            Error::AppBoundEncryption(_) => "app_bound_encryption",
+            #[cfg(feature = "client")]
+            Error::HttpStatus { .. } => "http_status",
+            #[cfg(feature = "client")]
+            Error::Transport(_) => "transport",
+            #[cfg(feature = "client")]
+            Error::SessionRejected => "session_rejected",
+            Error::NoSuchYear(_) => "no_such_year",
+            Error::NoSuchDay(_) => "no_such_day",
+            Error::NoSuchPuzzle { .. } => "no_such_puzzle",
+            #[cfg(feature = "client")]
+            Error::UnexpectedResponse(_) => "unexpected_response",
+            #[cfg(feature = "client")]
+            Error::CooldownActive { .. } => "cooldown_active",
+            #[cfg(feature = "client")]
+            Error::Offline(_) => "offline",
+        }
+    }
+
+    /// Whether the error only says that a source holds no session cookie value, as opposed to
+    /// the source being broken.
+    pub(crate) fn is_absence(&self) -> bool {
+        match self {
+            Error::NoSessionCookieFound | Error::EnvVarNotSet => true,
+            #[cfg(feature = "keyring")]
+            Error::KeyringEntryNotFound => true,
+            #[cfg(feature = "agent")]
+            Error::AgentNotRunning => true,
+            Error::Io { source, .. } => source.kind() == std::io::ErrorKind::NotFound,
+            _ => false,
+        }
+    }
+}
+
+/// The messages of the errors of [`Error::BrowsersFailed`], separated by semicolons.
+fn failures(errors: &[Error]) -> String {
+    let messages: Vec<_> = errors.iter().map(Error::to_string).collect();
+    messages.join("; ")
 }
 
 /// The result type for this crate.
 pub type Result<T> = core::result::Result<T, Error>;
 
+/// The `User-Agent` sent with every request, identifying this crate to the AoC maintainers.
+#[cfg(any(feature = "client", feature = "reqwest", feature = "ureq"))]
+pub(crate) const DEFAULT_USER_AGENT: &str = concat!(
+    "aoc-session/",
+    env!("CARGO_PKG_VERSION"),
+    " (+https://github.com/JohnScience/aoc-session)"
+);
+
 /// Value of the session cookie for Advent of Code.
 ///
 /// For example, this value can be used to get access to the puzzle input.
 ///
+/// The session cookie value is a secret: anyone who has it is logged in to Advent of Code as you.
+/// That's why [`Display`] and [`Debug`] only show its first and last few characters, e.g.
+/// `session=25a1…8dcc`, and the value itself has to be asked for with
+/// [`expose_secret`](AocSession::expose_secret).
+///
 ///  # Examples
 ///
-/// ## Debug-print the session cookie value to stdout:
+/// ## Debug-print the redacted session cookie value to stdout:
 ///
 /// ```
 /// use aoc_session::aoc_session;
@@ -41,44 +388,392 @@ pub type Result<T> = core::result::Result<T, Error>;
 /// use aoc_session::aoc_session;
 ///
 /// let session_id: String = match aoc_session() {
-///     Ok(session) => session.to_string(),
+///     Ok(session) => session.expose_secret().to_string(),
 ///     Err(e) => panic!("Error: {e}"),
 /// };
 ///
 /// assert!(session_id.len() > 0);
 /// assert!(!session_id.starts_with("session="));
-/// println!("My session ID: {}", session_id);
 /// ```
 ///
-pub struct AocSession(String);
+#[derive(Clone)]
+pub struct AocSession {
+    value: String,
+    expires: Option<SystemTime>,
+}
 
 impl AocSession {
     #[cfg(test)]
     pub fn new(session: impl ToString) -> Self {
         let session = session.to_string();
         for symbol in session.chars() {
-            if !('a'..='z').contains(&symbol) && !('0'..='9').contains(&symbol) {
+            if !symbol.is_ascii_lowercase() && !symbol.is_ascii_digit() {
                 panic!("Session cookie value must be a lowercase string that represents a base-16 number");
             }
         }
-        Self(session)
+        Self::unchecked(session)
+    }
+
+    /// The shortest and longest session cookie values accepted by [`AocSession::try_new`].
+    /// Advent of Code currently issues values of 128 hex digits.
+    const LENGTHS: std::ops::RangeInclusive<usize> = 32..=256;
+
+    /// Create a session from a session cookie value, e.g. copied from the developer tools of a
+    /// browser. Like the values of every source, it's normalized first: surrounding whitespace,
+    /// a leading `session=` and trailing `;`, and uppercase hex digits are fixed.
+    ///
+    /// Returns [`Error::MalformedToken`] unless the value then is a lowercase hex string of a
+    /// plausible length (see [`AocSession::is_well_formed`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoc_session::{AocSession, Error};
+    ///
+    /// let value = "53616c7465645f5f".repeat(8);
+    /// let session = AocSession::try_new(format!("session={}\n", value.to_uppercase())).unwrap();
+    /// assert_eq!(session.expose_secret(), value);
+    /// assert!(matches!(
+    ///     AocSession::try_new("session=53616c74-65645f5f"),
+    ///     Err(Error::MalformedToken(_))
+    /// ));
+    /// ```
+    pub fn try_new(value: impl Into<String>) -> Result<Self> {
+        // Wrapping the value first wipes it on errors with the `zeroize` feature.
+        let session = Self::unchecked(value.into());
+        session.check_format()?;
+        Ok(session)
+    }
+
+    /// Whether the value looks like a session cookie value of Advent of Code: a lowercase hex
+    /// string of a plausible length. A malformed value, e.g. one pasted with stray characters,
+    /// is bound to be rejected by Advent of Code, but sessions from the browsers aren't checked
+    /// because mirrors of Advent of Code may use other formats.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoc_session::AocSession;
+    ///
+    /// assert!(AocSession::fake(1).is_well_formed());
+    /// ```
+    pub fn is_well_formed(&self) -> bool {
+        self.check_format().is_ok()
+    }
+
+    fn check_format(&self) -> Result<()> {
+        let value = &self.value;
+        if let Some(c) = value.chars().find(|c| !matches!(c, '0'..='9' | 'a'..='f')) {
+            return Err(Error::MalformedToken(format!(
+                "unexpected character {c:?}; expected lowercase hex digits"
+            )));
+        }
+        if !Self::LENGTHS.contains(&value.len()) || !value.len().is_multiple_of(2) {
+            return Err(Error::MalformedToken(format!(
+                "unexpected length {}; expected an even length between {} and {}",
+                value.len(),
+                Self::LENGTHS.start(),
+                Self::LENGTHS.end()
+            )));
+        }
+        Ok(())
+    }
+
+    /// A well-formed but fake session, for tests of code that needs an [`AocSession`] but
+    /// shouldn't touch a browser or the network. See also
+    /// [`StaticProvider::fake`](crate::StaticProvider::fake).
+    ///
+    /// The value is 128 hex digits, like real ones, derived from the seed, so that tests can tell
+    /// several fake sessions apart. Advent of Code rejects it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoc_session::AocSession;
+    ///
+    /// let session = AocSession::fake(1);
+    /// assert_eq!(session.expose_secret(), AocSession::fake(1).expose_secret());
+    /// assert_ne!(session.expose_secret(), AocSession::fake(2).expose_secret());
+    /// assert!(AocSession::try_new(session.expose_secret()).is_ok());
+    /// ```
+    pub fn fake(seed: u64) -> Self {
+        // SplitMix64, which is good enough to spread the seed over the value.
+        let mut state = seed;
+        let value = (0..8)
+            .map(|_| {
+                state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+                let mut z = state;
+                z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+                z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+                format!("{:016x}", z ^ (z >> 31))
+            })
+            .collect();
+        Self::unchecked(value)
+    }
+
+    /// The session cookie value itself, e.g. to send it to Advent of Code.
+    ///
+    /// Be careful not to log it or paste it anywhere public.
+    pub fn expose_secret(&self) -> &str {
+        &self.value
+    }
+
+    /// Same as [`expose_secret`](AocSession::expose_secret).
+    pub fn as_str(&self) -> &str {
+        &self.value
+    }
+
+    /// The `Cookie` header that authenticates a request to Advent of Code, i.e.
+    /// `Cookie: session=<value>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoc_session::AocSession;
+    ///
+    /// let session = AocSession::try_new("53616c7465645f5f".repeat(8)).unwrap();
+    /// assert!(session.to_cookie_header().starts_with("Cookie: session=53616c74"));
+    /// ```
+    pub fn to_cookie_header(&self) -> String {
+        self.with_prefix("Cookie: session=")
+    }
+
+    /// The value of the `Cookie` header that authenticates a request to Advent of Code, i.e.
+    /// `session=<value>`, for HTTP clients that take header names and values separately.
+    pub fn to_cookie_value(&self) -> String {
+        self.with_prefix("session=")
+    }
+
+    /// The value of the `Cookie` header as an [`http::HeaderValue`], marked as sensitive so that
+    /// HTTP clients built on the `http` crate keep it out of their logs, with the `http` feature.
+    ///
+    /// Returns [`Error::MalformedToken`] if the value can't be sent in a header, which may happen
+    /// with values from the browsers, as those aren't checked.
+    #[cfg(feature = "http")]
+    pub fn to_header_value(&self) -> Result<http::HeaderValue> {
+        let mut value = self.to_cookie_value();
+        let header = http::HeaderValue::from_str(&value);
+        crate::secret::wipe(&mut value);
+        let mut header = header.map_err(|_| {
+            Error::MalformedToken("the value can't be sent in an HTTP header".to_string())
+        })?;
+        header.set_sensitive(true);
+        Ok(header)
+    }
+
+    fn with_prefix(&self, prefix: &str) -> String {
+        // Built in a buffer of the exact size, so that no reallocation leaves a copy behind.
+        let mut s = String::with_capacity(prefix.len() + self.value.len());
+        s.push_str(prefix);
+        s.push_str(&self.value);
+        s
+    }
+
+    /// The first and last few characters of the value, or nothing if it's too short to reveal
+    /// any of it.
+    fn redacted(&self) -> String {
+        const SHOWN: usize = 4;
+        let value = &self.value;
+        match (
+            value.get(..SHOWN),
+            value.get(value.len().saturating_sub(SHOWN)..),
+        ) {
+            (Some(start), Some(end)) if value.len() > 3 * SHOWN => format!("{start}…{end}"),
+            _ => "…".to_string(),
+        }
+    }
+
+    /// A session with the normalized value, as every source creates them.
+    pub(crate) fn unchecked(value: String) -> Self {
+        Self {
+            value: secret::normalize(value),
+            expires: None,
+        }
+    }
+
+    pub(crate) fn with_expiry(mut self, expires: Option<SystemTime>) -> Self {
+        self.expires = expires;
+        self
+    }
+
+    /// When the session cookie expires, if known. The expiry is known for session cookies taken
+    /// from browsers.
+    ///
+    /// Advent of Code sessions last for about a month.
+    pub fn expires_at(&self) -> Option<SystemTime> {
+        self.expires
+    }
+
+    /// Whether the session cookie is known to have expired.
+    pub fn is_expired(&self) -> bool {
+        self.time_to_expiry() == Some(Duration::ZERO)
+    }
+
+    /// How long until the session cookie expires, if known. Zero if it has already expired.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use aoc_session::aoc_session;
+    ///
+    /// let session = aoc_session().unwrap();
+    /// if let Some(left) = session.time_to_expiry() {
+    ///     if left < Duration::from_secs(7 * 24 * 60 * 60) {
+    ///         eprintln!("Your Advent of Code session expires within a week; log in again soon");
+    ///     }
+    /// }
+    /// ```
+    pub fn time_to_expiry(&self) -> Option<Duration> {
+        self.expires.map(|expires| {
+            expires
+                .duration_since(SystemTime::now())
+                .unwrap_or(Duration::ZERO)
+        })
+    }
+}
+
+/// With the `zeroize` feature, the session cookie value is wiped from memory on drop.
+#[cfg(feature = "zeroize")]
+impl Drop for AocSession {
+    fn drop(&mut self) {
+        secret::wipe(&mut self.value);
     }
 }
 
+/// Shows the redacted value as a cookie, e.g. `session=25a1…8dcc`.
 impl Debug for AocSession {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "session={}", self.0)
+        write!(f, "session={}", self.redacted())
     }
 }
 
+/// Shows the redacted value, e.g. `25a1…8dcc`.
 impl Display for AocSession {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.0)
+        f.write_str(&self.redacted())
+    }
+}
+
+/// Sessions are equal if their values are, whatever their expiry. The values are compared in
+/// constant time, so that the comparison doesn't leak how much of a guessed value is right.
+impl PartialEq for AocSession {
+    fn eq(&self, other: &Self) -> bool {
+        secret::constant_time_eq(&self.value, &other.value)
+    }
+}
+
+impl Eq for AocSession {}
+
+/// Hashes the value itself, consistently with [`PartialEq`], rather than its redacted display,
+/// which distinct sessions may share. The value only reaches the hasher, e.g. the keyed one of
+/// [`HashMap`](std::collections::HashMap).
+impl Hash for AocSession {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
+impl FromStr for AocSession {
+    type Err = Error;
+
+    /// See [`AocSession::try_new`].
+    fn from_str(s: &str) -> Result<Self> {
+        AocSession::try_new(s)
+    }
+}
+
+impl TryFrom<String> for AocSession {
+    type Error = Error;
+
+    /// See [`AocSession::try_new`].
+    fn try_from(value: String) -> Result<Self> {
+        AocSession::try_new(value)
     }
 }
 
-/// Get the session cookie for Advent of Code. Beware that this function works for all browsers
-/// supported by [`rookie`] but is slow.
+impl TryFrom<&str> for AocSession {
+    type Error = Error;
+
+    /// See [`AocSession::try_new`].
+    fn try_from(value: &str) -> Result<Self> {
+        AocSession::try_new(value)
+    }
+}
+
+/// Names of the environment variables that [`aoc_session_from_env`] consults, in order.
+pub const ENV_VARS: [&str; 2] = ["AOC_SESSION", "ADVENT_OF_CODE_SESSION"];
+
+/// The suffix of the environment variables that name a file holding the session cookie value
+/// instead of holding it, as Docker and Kubernetes secrets are handed to containers, e.g.
+/// `AOC_SESSION_FILE=/run/secrets/aoc_session`.
+pub const FILE_ENV_VAR_SUFFIX: &str = "_FILE";
+
+/// Get the session cookie for Advent of Code from the environment variables listed in
+/// [`ENV_VARS`]. Surrounding whitespace is trimmed and empty values are ignored.
+///
+/// Each variable may instead be set with the [`FILE_ENV_VAR_SUFFIX`], e.g. `AOC_SESSION_FILE`, to
+/// the path of a file holding the value, which is read when the variable itself isn't set.
+///
+/// This is the source to use in CI and on headless servers, where there are no browsers to scan.
+///
+/// # Examples
+///
+/// ```
+/// use aoc_session::{aoc_session_from_env, Error};
+///
+/// match aoc_session_from_env() {
+///     Ok(session) => println!("My session ID: {session}"),
+///     Err(Error::EnvVarNotSet) => println!("Set AOC_SESSION first"),
+///     Err(e) => panic!("Error: {e}"),
+/// }
+/// ```
+pub fn aoc_session_from_env() -> Result<AocSession> {
+    session_from_env_with(|name| std::env::var(name).ok())
+}
+
+pub(crate) fn session_from_env_with(var: impl Fn(&str) -> Option<String>) -> Result<AocSession> {
+    for name in ENV_VARS {
+        let session = AocSession::unchecked(var(name).unwrap_or_default());
+        if !session.value.is_empty() {
+            return Ok(session);
+        }
+        let path = var(&format!("{name}{FILE_ENV_VAR_SUFFIX}")).unwrap_or_default();
+        if !path.trim().is_empty() {
+            return source::from_file(Path::new(path.trim()));
+        }
+    }
+    Err(Error::EnvVarNotSet)
+}
+
+/// Get the session cookie for Advent of Code from a file that contains nothing but the session
+/// cookie value. Surrounding whitespace is trimmed.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoc_session::aoc_session_from_file;
+///
+/// let session = aoc_session_from_file(".aoc-session").unwrap();
+/// println!("My session ID: {session}");
+/// ```
+pub fn aoc_session_from_file(path: impl AsRef<std::path::Path>) -> Result<AocSession> {
+    source::from_file(path.as_ref())
+}
+
+/// Get the session cookie for Advent of Code. The environment variables listed in [`ENV_VARS`]
+/// are consulted first (see [`aoc_session_from_env`]), then, with the `dotenv` feature, the
+/// `.env` files (see `aoc_session_from_dotenv`), then the files listed by
+/// [`session_file_paths`], then the `token` setting of the [`Config`] file. Otherwise, beware that
+/// this function works for all browsers supported by [`rookie`] but is slow.
+///
+/// If several browsers hold a session cookie, the newest one is returned
+/// (see [`SelectionStrategy::Newest`]), unless the [`Config`] file names a preferred browser.
+///
+/// With the `keyring` feature, the OS credential store is consulted after the environment
+/// variables (see [`aoc_session_from_keyring`]).
+///
+/// Use [`AocSessionBuilder`] to consult a different chain of sources.
 ///
 /// # Examples
 ///
@@ -102,22 +797,167 @@ impl Display for AocSession {
 /// use aoc_session::aoc_session;
 ///
 /// let session_id: String = match aoc_session() {
-///     Ok(session) => session.to_string(),
+///     Ok(session) => session.expose_secret().to_string(),
 ///     Err(e) => panic!("Error: {e}"),
 /// };
 ///
 /// assert!(session_id.len() > 0);
-/// println!("My session ID: {}", session_id);
 /// ```
 ///
 pub fn aoc_session() -> Result<AocSession> {
-    let domains = Some(vec!["adventofcode.com"]); // set to None to get all
-    let cookies: Vec<_> = rookie::load(domains).map_err(Error::RookieError)?;
-    let session = cookies
-        .into_iter()
-        .find(|c| c.name == "session")
-        .ok_or(Error::NoSessionCookieFound)?;
-    Ok(AocSession(session.value))
+    Config::load()?.session_builder().resolve()
+}
+
+/// Like [`aoc_session`], but gives up after the given time, e.g. when a keychain prompt is left
+/// unanswered or a cookie database stays locked.
+///
+/// Returns [`Error::Timeout`] if no session cookie value was retrieved in time. The retrieval
+/// can't be cancelled, so it keeps running in the background until it finishes on its own.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use aoc_session::{aoc_session_with_timeout, Error};
+///
+/// match aoc_session_with_timeout(Duration::from_secs(5)) {
+///     Ok(session) => println!("My session ID: {session}"),
+///     Err(Error::Timeout(_)) => eprintln!("The browsers took too long to answer"),
+///     Err(e) => panic!("Error: {e}"),
+/// }
+/// ```
+pub fn aoc_session_with_timeout(timeout: Duration) -> Result<AocSession> {
+    with_timeout(timeout, aoc_session)
+}
+
+/// Like [`aoc_session`], but retrieves the session cookie value only once per process.
+///
+/// Later calls return the value found by the first successful one, which saves rescanning the
+/// browser cookie stores, e.g. when every puzzle day of a test harness asks for the session.
+/// Errors are not remembered, so a failed call is retried the next time.
+///
+/// Call [`invalidate_cache`] when the session cookie value changes, e.g. after logging in again.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoc_session::aoc_session_cached;
+///
+/// for day in 1..=25 {
+///     // Only the first iteration scans the browsers.
+///     let session = aoc_session_cached().unwrap();
+///     println!("Day {day}: {session}");
+/// }
+/// ```
+pub fn aoc_session_cached() -> Result<AocSession> {
+    cached_with(session_cache(), aoc_session)
+}
+
+/// The session remembered in `cache`, or the one retrieved with `retrieve`, which is remembered.
+fn cached_with(
+    cache: &Mutex<Option<AocSession>>,
+    retrieve: impl FnOnce() -> Result<AocSession>,
+) -> Result<AocSession> {
+    let mut cached = cache.lock().unwrap_or_else(|e| e.into_inner());
+    // The lock is held during the retrieval, so concurrent callers don't scan twice.
+    if let Some(session) = &*cached {
+        return Ok(session.clone());
+    }
+    let session = retrieve()?;
+    *cached = Some(session.clone());
+    Ok(session)
+}
+
+/// Forget the session cookie value remembered by [`aoc_session_cached`], so that its next call
+/// retrieves it again.
+pub fn invalidate_cache() {
+    *session_cache().lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Forget the session cookie value remembered on disk by
+/// [`AocSessionBuilder::persist`], so that the next resolution consults the sources again. The
+/// session of the mirror set as `base_url` in the [`Config`] file is forgotten too.
+///
+/// Call it after storing a new session cookie value by hand.
+pub fn forget_persisted_session() -> Result<()> {
+    let mirror = Config::load()
+        .ok()
+        .and_then(|config| config.base_url)
+        .and_then(|url| config::host(&url).map(str::to_string));
+    let domains = [Some(source::DOMAIN.to_string()), mirror];
+    for cache in domains
+        .iter()
+        .flatten()
+        .filter_map(|domain| session_cache::SessionCache::for_domain(domain))
+    {
+        cache.clear()?;
+    }
+    Ok(())
+}
+
+fn session_cache() -> &'static Mutex<Option<AocSession>> {
+    static CACHE: OnceLock<Mutex<Option<AocSession>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(None))
+}
+
+/// Run `f` on its own thread and wait for its result for at most `timeout`.
+fn with_timeout<T, F>(timeout: Duration, f: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        // The receiver is gone if the caller stopped waiting.
+        let _ = sender.send(f());
+    });
+    match receiver.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(std::sync::mpsc::RecvTimeoutError::Timeout) => Err(Error::Timeout(timeout)),
+        // The thread panicked before sending its result.
+        Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
+            panic!("retrieval of the session cookie panicked")
+        }
+    }
+}
+
+/// Get the session cookie for Advent of Code from the cookie store of a single browser.
+///
+/// This is much faster than [`aoc_session`] and doesn't trigger keychain prompts for other
+/// browsers.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoc_session::{aoc_session_from, Browser};
+///
+/// let session = aoc_session_from(Browser::Firefox).expect("Not logged in with Firefox");
+/// println!("My session ID: {session}");
+/// ```
+pub fn aoc_session_from(browser: Browser) -> Result<AocSession> {
+    source::from_browser(browser, &SessionSpec::ADVENT_OF_CODE)
+}
+
+/// Get every session cookie for Advent of Code found in the supported browsers, along with the
+/// browser, profile, and expiry of each.
+///
+/// This is useful when different accounts are logged in with different browsers. The browsers
+/// are probed in parallel, and those whose cookie stores can't be read are skipped. If no session
+/// cookie is found at all, [`Error::BrowsersFailed`] lists the browsers that couldn't be read, or
+/// [`Error::NoSessionCookieFound`] is returned if there are none.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoc_session::aoc_sessions;
+///
+/// for found in aoc_sessions().expect("No session cookie found") {
+///     println!("{} ({}): {:?}", found.browser, found.profile.display(), found.session);
+/// }
+/// ```
+pub fn aoc_sessions() -> Result<Vec<DiscoveredSession>> {
+    source::discover(&SessionSpec::ADVENT_OF_CODE)
 }
 
 #[cfg(test)]
@@ -132,25 +972,167 @@ mod tests {
                 "Error: {e}.\nIf you haven't logged in to Advent of Code yet, please do so now."
             ),
         };
-        // session=25a16c7465645f5f286128b604b18e3d5a906611b3eac6740672d5e471a7ab0d3af049fb7363eadb2e07edfe51b600927ddd29b2311ea418ce366e8b9cf98dcc
+        // session=25a1…8dcc
         println!("{:?}", session);
     }
 
     #[test]
     fn check_debug_format() {
         let session = AocSession::new("25a16c7465645f5f286128b604b18e3d5a906611b3eac6740672d5e471a7ab0d3af049fb7363eadb2e07edfe51b600927ddd29b2311ea418ce366e8b9cf98dcc");
-        assert_eq!(
-            format!("{:?}", session),
-            "session=25a16c7465645f5f286128b604b18e3d5a906611b3eac6740672d5e471a7ab0d3af049fb7363eadb2e07edfe51b600927ddd29b2311ea418ce366e8b9cf98dcc"
-        );
+        assert_eq!(format!("{:?}", session), "session=25a1…8dcc");
+        assert_eq!(format!("{:?}", AocSession::new("25a16c74")), "session=…");
     }
 
     #[test]
     fn check_to_string() {
         let session = AocSession::new("25a16c7465645f5f286128b604b18e3d5a906611b3eac6740672d5e471a7ab0d3af049fb7363eadb2e07edfe51b600927ddd29b2311ea418ce366e8b9cf98dcc");
+        assert_eq!(session.to_string(), "25a1…8dcc");
+        assert_eq!(
+            session.to_cookie_header(),
+            format!("Cookie: {}", session.to_cookie_value())
+        );
         assert_eq!(
-            session.to_string(),
+            session.expose_secret(),
             "25a16c7465645f5f286128b604b18e3d5a906611b3eac6740672d5e471a7ab0d3af049fb7363eadb2e07edfe51b600927ddd29b2311ea418ce366e8b9cf98dcc"
         );
     }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn header_value_is_sensitive() {
+        let session = AocSession::new("25a16c74");
+        let header = session.to_header_value().unwrap();
+        assert!(header.is_sensitive());
+        assert_eq!(header, session.to_cookie_value().as_str());
+        assert!(matches!(
+            AocSession::unchecked("25a1\n6c74".to_string()).to_header_value(),
+            Err(Error::MalformedToken(_))
+        ));
+    }
+
+    #[test]
+    fn expiry() {
+        let session = AocSession::new("25a16c74");
+        assert_eq!(session.expires_at(), None);
+        assert_eq!(session.time_to_expiry(), None);
+        assert!(!session.is_expired());
+
+        let session = session.with_expiry(Some(SystemTime::now() - Duration::from_secs(1)));
+        assert!(session.is_expired());
+
+        let week = Duration::from_secs(7 * 24 * 60 * 60);
+        let session = session.with_expiry(Some(SystemTime::now() + week));
+        assert!(!session.is_expired());
+        assert!(session.time_to_expiry().unwrap() > week - Duration::from_secs(60));
+    }
+
+    #[test]
+    fn env_vars_are_consulted_in_order() {
+        let session = session_from_env_with(|name| match name {
+            "AOC_SESSION" => Some("  ".to_string()),
+            "ADVENT_OF_CODE_SESSION" => Some(" 25a16c74\n".to_string()),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(session.as_str(), "25a16c74");
+        assert!(matches!(
+            session_from_env_with(|_| None),
+            Err(Error::EnvVarNotSet)
+        ));
+    }
+
+    #[test]
+    fn env_vars_can_point_to_files() {
+        let dir = std::env::temp_dir().join(format!("aoc-session-env-file-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("aoc_session");
+        std::fs::write(&path, "25a16c74\n").unwrap();
+        let file = path.display().to_string();
+        let session = session_from_env_with(|name| match name {
+            "AOC_SESSION_FILE" => Some(file.clone()),
+            "ADVENT_OF_CODE_SESSION" => Some("ffffffff".to_string()),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(session.as_str(), "25a16c74");
+        let session = session_from_env_with(|name| match name {
+            "AOC_SESSION" => Some("ffffffff".to_string()),
+            "AOC_SESSION_FILE" => Some(file.clone()),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(session.as_str(), "ffffffff");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn cached_session_is_remembered_until_invalidated() {
+        let cache = Mutex::new(None);
+        let retrievals = std::cell::Cell::new(0);
+        let retrieve = || {
+            retrievals.set(retrievals.get() + 1);
+            Ok(AocSession::new(format!("25a16c7{}", retrievals.get())))
+        };
+        let first = cached_with(&cache, retrieve).unwrap();
+        assert_eq!(
+            cached_with(&cache, retrieve).unwrap().as_str(),
+            first.as_str()
+        );
+        assert_eq!(retrievals.get(), 1);
+        assert!(cached_with(&cache, || Err(Error::NoSessionCookieFound)).is_ok());
+        *cache.lock().unwrap() = None;
+        assert!(cached_with(&cache, || Err(Error::NoSessionCookieFound)).is_err());
+        assert_ne!(
+            cached_with(&cache, retrieve).unwrap().as_str(),
+            first.as_str()
+        );
+        assert_eq!(retrievals.get(), 2);
+    }
+
+    #[test]
+    fn malformed_tokens_are_rejected() {
+        let token = "53616c7465645f5f".repeat(8);
+        let session: AocSession = format!(" {token}\n").parse().unwrap();
+        assert_eq!(session.as_str(), token);
+        let pasted = format!("Session={};", token.to_uppercase());
+        assert_eq!(AocSession::try_new(pasted).unwrap().as_str(), token);
+        assert!(!AocSession::unchecked(format!("{token}!")).is_well_formed());
+        for malformed in ["", "ff", &token[1..], &format!("session={token}g")] {
+            assert!(matches!(
+                AocSession::try_new(malformed),
+                Err(Error::MalformedToken(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn sessions_compare_by_value() {
+        use std::collections::HashSet;
+
+        let token = "53616c7465645f5f".repeat(8);
+        let session = AocSession::unchecked(token.clone());
+        let expiring = AocSession::unchecked(token.clone()).with_expiry(Some(SystemTime::now()));
+        let mut other = token.clone();
+        other.replace_range(10..12, "00");
+        let other = AocSession::unchecked(other);
+        assert_eq!(session.to_string(), other.to_string());
+        assert_eq!(session, expiring);
+        assert_ne!(session, other);
+        let sessions: HashSet<_> = [session, expiring, other].into_iter().collect();
+        assert_eq!(sessions.len(), 2);
+    }
+
+    #[test]
+    fn slow_retrieval_times_out() {
+        let timeout = Duration::from_millis(10);
+        let slow = with_timeout(timeout, || {
+            std::thread::sleep(Duration::from_secs(1));
+            Ok(())
+        });
+        assert!(matches!(slow, Err(Error::Timeout(t)) if t == timeout));
+        assert!(matches!(
+            with_timeout(Duration::from_secs(1), || Ok(42)),
+            Ok(42)
+        ));
+    }
 }
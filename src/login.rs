@@ -0,0 +1,118 @@
+//! The way out when no browser holds a session cookie: open the login page of Advent of Code in
+//! the default browser and wait for the cookie to show up in its cookie store.
+
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::{AocSession, Error, Result, Source};
+
+/// The login page of Advent of Code.
+pub const LOGIN_URL: &str = "https://adventofcode.com/auth/login";
+
+/// How long to wait between two scans of the cookie stores.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Open [`LOGIN_URL`] in the default browser and wait until a browser holds a session cookie for
+/// Advent of Code, then return it.
+///
+/// The cookie stores are scanned every few seconds. Browsers based on Chromium write new cookies
+/// to disk up to half a minute after the login. If the default browser can't be opened, the page
+/// can be visited by hand while this function waits.
+///
+/// Returns [`Error::Timeout`] if no session cookie shows up in time.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use aoc_session::{aoc_session, aoc_session_from_login, Error};
+///
+/// let session = match aoc_session() {
+///     Err(Error::NoSessionCookieFound) => {
+///         aoc_session_from_login(Duration::from_secs(300)).unwrap()
+///     }
+///     result => result.unwrap(),
+/// };
+/// println!("My session ID: {session}");
+/// ```
+pub fn aoc_session_from_login(timeout: Duration) -> Result<AocSession> {
+    // Failing to open the page isn't fatal, since it can be visited by hand.
+    let _ = open_in_browser(LOGIN_URL);
+    wait_for(timeout, || Source::Browsers.resolve())
+}
+
+/// Call `lookup` until it succeeds or `timeout` elapses.
+fn wait_for(timeout: Duration, lookup: impl Fn() -> Result<AocSession>) -> Result<AocSession> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Ok(session) = lookup() {
+            return Ok(session);
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(Error::Timeout(timeout));
+        }
+        thread::sleep(POLL_INTERVAL.min(deadline - now));
+    }
+}
+
+/// Open the URL in the default browser, without waiting for it.
+fn open_in_browser(url: &str) -> std::io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) || is_wsl() {
+        // The empty argument is the title of the window, which `start` takes from the first
+        // quoted argument.
+        let mut command = Command::new(if cfg!(windows) { "cmd" } else { "cmd.exe" });
+        command.args(["/d", "/c", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    let mut child = command
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    // Some openers only return once the browser exits.
+    thread::spawn(move || child.wait());
+    Ok(())
+}
+
+#[cfg(feature = "wsl")]
+fn is_wsl() -> bool {
+    crate::is_wsl()
+}
+
+#[cfg(not(feature = "wsl"))]
+fn is_wsl() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    #[test]
+    fn waits_until_found_or_timed_out() {
+        let attempts = Cell::new(0);
+        let session = wait_for(Duration::from_secs(10), || {
+            attempts.set(attempts.get() + 1);
+            match attempts.get() {
+                1 => Err(Error::NoSessionCookieFound),
+                _ => Ok(AocSession::unchecked("25a16c74".to_string())),
+            }
+        });
+        assert_eq!(session.unwrap().expose_secret(), "25a16c74");
+        assert_eq!(attempts.get(), 2);
+        assert!(matches!(
+            wait_for(Duration::ZERO, || Err(Error::NoSessionCookieFound)),
+            Err(Error::Timeout(Duration::ZERO))
+        ));
+    }
+}
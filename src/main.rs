@@ -1,7 +1,454 @@
-use aoc_session::{aoc_session, Result};
+mod cli;
 
-fn main() -> Result<()> {
-    let session = aoc_session()?;
-    println!("{session:?}");
-    Ok(())
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use aoc_session::{Error, PuzzleId};
+use cli::{Format, Output, Shell};
+
+const USAGE: &str = "\
+Print the session cookie value for Advent of Code
+
+Usage: aoc-session [OPTIONS] [COMMAND]
+       aoc-session input [<YEAR> <DAY> | --today] [-o <FILE>]
+       aoc-session submit <YEAR> <DAY> <PART> [<ANSWER>]
+       aoc-session agent [stop]
+       aoc-session git-credential <get|store|erase>
+       aoc-session native-host
+       aoc-session login
+       aoc-session completions <SHELL>
+
+Commands:
+  status          Show where the session cookie comes from, when it expires, and whether it's valid
+  doctor          Check every source, including each browser, and explain what went wrong
+  compare         Show which account the session cookie of each browser belongs to
+  export          Print the session cookie in the format given by --format
+  set             Store the session cookie value given as an argument or on the standard input
+  clear           Remove the session cookie value stored by `set`
+  input           Print the puzzle input of a day, today's by default
+  submit          Submit an answer, read from the standard input if not given, and print the outcome
+  login           Open the login page in the browser and wait for the session cookie to show up
+  agent           Keep the session cookie value in memory for other runs until `agent stop`
+  profiles        List the account profiles, marking the one in use with `*`
+  git-credential  Act as a git credential helper for adventofcode.com
+  native-host     Store the session cookie values pushed by a browser extension over native messaging
+  completions     Print the completion script of bash, zsh, fish, or powershell
+
+Options:
+  -c, --cookie-header    Print the value as `session=<value>`
+      --cookies-txt      Print the session cookie in the Netscape cookies.txt format
+      --curl <URL>       Print a curl command that fetches the URL with the session cookie
+  -f, --format <FORMAT>  The format of `export`: env (default), json, cookies-txt, curl-header,
+                         gh-secret, or github-actions
+      --today            Pick the puzzle that unlocked last, during Advent of Code
+  -o, --output <FILE>    Write the puzzle input to the file instead of printing it
+  -p, --profile <NAME>   Use the session cookie, caches, and answers of the named account
+                         profile (default: $AOC_SESSION_PROFILE)
+      --json             Print the output, and errors, as JSON
+  -q, --quiet            Do not print error messages, only set the exit code
+  -h, --help             Print help
+  -V, --version          Print version
+
+Exit status:
+  0   Success
+  1   Any other failure
+  2   No session cookie was found
+  3   Advent of Code rejected the session cookie, which has likely expired
+  4   Advent of Code couldn't be reached, or answered with an error
+  5   An answer was submitted too soon after the previous one
+  64  The command line is invalid";
+
+#[derive(Debug, PartialEq, Eq)]
+enum Command {
+    Print(Output),
+    Status,
+    Doctor,
+    Compare,
+    Export(Format),
+    Set(Option<String>),
+    Clear,
+    Input {
+        puzzle: PuzzleId,
+        output: Option<PathBuf>,
+    },
+    Submit {
+        puzzle: PuzzleId,
+        level: u8,
+        answer: Option<String>,
+    },
+    Login,
+    Agent {
+        stop: bool,
+    },
+    Profiles,
+    GitCredential(String),
+    NativeHost,
+    Completions(Shell),
+    Help,
+    Version,
+}
+
+/// Command-line options of the executable.
+#[derive(Debug, PartialEq, Eq)]
+struct Args {
+    command: Command,
+    profile: Option<String>,
+    json: bool,
+    quiet: bool,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut output = Output::default();
+    let mut format = None;
+    let mut today = false;
+    let mut file = None;
+    let mut profile = None;
+    let mut json = false;
+    let mut quiet = false;
+    let mut flag = None;
+    let mut positionals = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-c" | "--cookie-header" => output = Output::CookieHeader,
+            "--cookies-txt" => output = Output::CookiesTxt,
+            "--curl" => output = Output::Curl(value(&mut args, "--curl <URL>")?),
+            "-f" | "--format" => format = Some(value(&mut args, "--format <FORMAT>")?.parse()?),
+            "--today" => today = true,
+            "-o" | "--output" => file = Some(PathBuf::from(value(&mut args, "--output <FILE>")?)),
+            "-p" | "--profile" => profile = Some(value(&mut args, "--profile <NAME>")?),
+            "--json" => json = true,
+            "-q" | "--quiet" => quiet = true,
+            "-h" | "--help" => flag = Some(Command::Help),
+            "-V" | "--version" => flag = Some(Command::Version),
+            // Chrome passes the handle of its window to native messaging hosts on Windows.
+            _ if arg.starts_with("--parent-window=") => {}
+            _ if arg.starts_with('-') => return Err(format!("unexpected argument '{arg}'")),
+            _ => positionals.push(arg),
+        }
+    }
+    let mut positionals = positionals.into_iter();
+    let subcommand = positionals.next();
+    let command = match (flag, subcommand.as_deref()) {
+        (Some(flag), _) => flag,
+        (None, None) if json => match output {
+            Output::Bare => Command::Print(Output::Json),
+            _ => return Err("'--json' can't be combined with other output options".to_string()),
+        },
+        (None, None) => Command::Print(output),
+        (None, Some("status")) => Command::Status,
+        (None, Some("doctor")) => Command::Doctor,
+        (None, Some("compare")) => Command::Compare,
+        (None, Some("export")) => match (format.take(), json) {
+            (None, true) => Command::Export(Format::Json),
+            (Some(format), true) if format != Format::Json => {
+                return Err("'--json' can't be combined with other formats".to_string())
+            }
+            (format, _) => Command::Export(format.unwrap_or_default()),
+        },
+        (None, Some("set")) => Command::Set(positionals.next()),
+        (None, Some("clear")) => Command::Clear,
+        (None, Some("input")) => {
+            let puzzle = puzzle(&mut positionals, std::mem::take(&mut today))?;
+            Command::Input {
+                puzzle,
+                output: file.take(),
+            }
+        }
+        (None, Some("submit")) => {
+            let puzzle = puzzle(&mut positionals, false)?;
+            let level = value(&mut positionals, "<PART>")?;
+            let level = match level.as_str() {
+                "1" => 1,
+                "2" => 2,
+                _ => return Err(format!("invalid part '{level}', expected 1 or 2")),
+            };
+            Command::Submit {
+                puzzle,
+                level,
+                answer: positionals.next(),
+            }
+        }
+        (None, Some("login")) => Command::Login,
+        (None, Some("agent")) => match positionals.next().as_deref() {
+            None => Command::Agent { stop: false },
+            Some("stop") => Command::Agent { stop: true },
+            Some(action) => return Err(format!("unrecognized agent action '{action}'")),
+        },
+        (None, Some("profiles")) => Command::Profiles,
+        (None, Some("git-credential")) => {
+            Command::GitCredential(value(&mut positionals, "<ACTION>")?)
+        }
+        (None, Some("native-host")) => {
+            // Browsers pass the origin of the extension, or the path of the manifest and the ID
+            // of the extension.
+            positionals.by_ref().for_each(drop);
+            Command::NativeHost
+        }
+        (None, Some("completions")) => {
+            Command::Completions(value(&mut positionals, "<SHELL>")?.parse()?)
+        }
+        (None, Some(command)) => return Err(format!("unrecognized subcommand '{command}'")),
+    };
+    if let Some(arg) = positionals.next() {
+        return Err(format!("unexpected argument '{arg}'"));
+    }
+    if format.is_some() {
+        return Err("'--format' only applies to 'export'".to_string());
+    }
+    if today || file.is_some() {
+        return Err("'--today' and '--output' only apply to 'input'".to_string());
+    }
+    Ok(Args {
+        command,
+        profile,
+        json,
+        quiet,
+    })
+}
+
+/// The puzzle given by the `<YEAR> <DAY>` arguments, or the one of today if there are none or
+/// `--today` is passed.
+///
+/// `input` may omit the arguments; `submit` must not, lest the part be taken for the year.
+fn puzzle(args: &mut impl Iterator<Item = String>, today: bool) -> Result<PuzzleId, String> {
+    let year = if today { None } else { args.next() };
+    let Some(year) = year else {
+        return PuzzleId::today()
+            .ok_or_else(|| "no puzzle is unlocked today; pass <YEAR> <DAY>".to_string());
+    };
+    let day = value(args, "<DAY>")?;
+    let year = year.parse().map_err(|_| format!("invalid year '{year}'"))?;
+    let day = day.parse().map_err(|_| format!("invalid day '{day}'"))?;
+    PuzzleId::new(year, day).map_err(|e| e.to_string())
+}
+
+/// The value of an option, which is the next argument.
+fn value(args: &mut impl Iterator<Item = String>, name: &str) -> Result<String, String> {
+    args.next()
+        .ok_or_else(|| format!("a value is required for '{name}'"))
+}
+
+fn main() -> ExitCode {
+    run(std::env::args().skip(1).collect())
+}
+
+/// Run the executable with the given arguments, which exclude the program name.
+pub(crate) fn run(args: Vec<String>) -> ExitCode {
+    let json = args.iter().any(|arg| arg == "--json");
+    let args = match parse_args(args.into_iter()) {
+        Ok(args) => args,
+        Err(e) if json => {
+            let json = serde_json::json!({"error": {"code": "usage", "message": e}});
+            eprintln!("{json}");
+            return ExitCode::from(cli::USAGE_EXIT_CODE);
+        }
+        Err(e) => {
+            eprintln!("error: {e}\n\n{USAGE}");
+            return ExitCode::from(cli::USAGE_EXIT_CODE);
+        }
+    };
+    let json = args.json;
+    if let Some(profile) = args.profile {
+        if let Err(e) = aoc_session::set_account_profile(profile) {
+            if json {
+                eprintln!("{}", cli::error_json(&e));
+            } else {
+                eprintln!("error: {e}\n\n{USAGE}");
+            }
+            return ExitCode::from(cli::USAGE_EXIT_CODE);
+        }
+    }
+    let result = match args.command {
+        Command::Print(output) => cli::print(output),
+        Command::Status => cli::status(json),
+        Command::Doctor => cli::doctor(json),
+        Command::Compare => cli::compare(json),
+        Command::Export(format) => cli::export(format),
+        Command::Set(token) => cli::set(token, json),
+        Command::Clear => cli::clear(json),
+        Command::Input { puzzle, output } => cli::input(puzzle, output, json),
+        Command::Submit {
+            puzzle,
+            level,
+            answer,
+        } => cli::submit(puzzle, level, answer, json),
+        Command::Login => cli::login(json),
+        Command::Agent { stop } => cli::agent(stop, json),
+        Command::Profiles => cli::profiles(json),
+        Command::GitCredential(action) => cli::git_credential(&action),
+        Command::NativeHost => cli::native_host(),
+        Command::Completions(shell) => {
+            cli::completions(shell);
+            Ok(())
+        }
+        Command::Help => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        Command::Version if json => {
+            println!(
+                "{}",
+                serde_json::json!({"version": env!("CARGO_PKG_VERSION")})
+            );
+            Ok(())
+        }
+        Command::Version => {
+            println!("aoc-session {}", env!("CARGO_PKG_VERSION"));
+            Ok(())
+        }
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            if !args.quiet {
+                if json {
+                    eprintln!("{}", cli::error_json(&e));
+                } else {
+                    eprintln!("error: {e}");
+                    let missing =
+                        matches!(e, Error::NoSessionCookieFound | Error::BrowsersFailed(_));
+                    if missing && std::io::stderr().is_terminal() {
+                        eprintln!("hint: run `aoc-session login` to log in with your browser");
+                    }
+                }
+            }
+            ExitCode::from(cli::exit_code(&e))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn args() {
+        assert_eq!(
+            parse(&["-q", "--curl", "https://adventofcode.com"]),
+            Ok(Args {
+                command: Command::Print(Output::Curl("https://adventofcode.com".to_string())),
+                profile: None,
+                json: false,
+                quiet: true,
+            })
+        );
+        assert_eq!(parse(&["status"]).unwrap().command, Command::Status);
+        assert_eq!(
+            parse(&["export", "--format", "json"]).unwrap().command,
+            Command::Export(Format::Json)
+        );
+        assert_eq!(
+            parse(&["export"]).unwrap().command,
+            Command::Export(Format::Env)
+        );
+        assert_eq!(
+            parse(&["set", "25a16c74"]).unwrap().command,
+            Command::Set(Some("25a16c74".to_string()))
+        );
+        assert_eq!(parse(&["set"]).unwrap().command, Command::Set(None));
+        assert_eq!(
+            parse(&["input", "2023", "1", "-o", "input.txt"])
+                .unwrap()
+                .command,
+            Command::Input {
+                puzzle: PuzzleId::new(2023, 1).unwrap(),
+                output: Some(PathBuf::from("input.txt"))
+            }
+        );
+        assert_eq!(
+            parse(&["submit", "--json", "2023", "1", "2", "281"])
+                .unwrap()
+                .command,
+            Command::Submit {
+                puzzle: PuzzleId::new(2023, 1).unwrap(),
+                level: 2,
+                answer: Some("281".to_string()),
+            }
+        );
+        assert_eq!(
+            parse(&["--json"]).unwrap().command,
+            Command::Print(Output::Json)
+        );
+        assert_eq!(
+            parse(&["export", "--json"]).unwrap().command,
+            Command::Export(Format::Json)
+        );
+        assert!(parse(&["--json", "--curl", "https://adventofcode.com"]).is_err());
+        assert!(parse(&["export", "--json", "-f", "env"]).is_err());
+        assert!(parse(&["submit", "2023", "1", "3"]).is_err());
+        assert_eq!(
+            parse(&["git-credential", "get"]).unwrap().command,
+            Command::GitCredential("get".to_string())
+        );
+        assert_eq!(
+            parse(&[
+                "native-host",
+                "chrome-extension://abc/",
+                "--parent-window=0"
+            ])
+            .unwrap()
+            .command,
+            Command::NativeHost
+        );
+        assert!(parse(&["submit", "2"]).is_err());
+        assert!(parse(&["input", "2023"]).is_err());
+        assert!(parse(&["input", "2023", "26"]).is_err());
+        assert!(parse(&["-o", "input.txt"]).is_err());
+        assert_eq!(parse(&["status", "-h"]).unwrap().command, Command::Help);
+        assert!(parse(&["status", "status"]).is_err());
+        assert!(parse(&["--format", "json"]).is_err());
+        assert!(parse(&["export", "--format", "xml"]).is_err());
+        assert!(parse(&["--curl"]).is_err());
+        assert!(parse(&["bogus"]).is_err());
+        assert_eq!(
+            parse(&["agent", "stop"]).unwrap().command,
+            Command::Agent { stop: true }
+        );
+        assert!(parse(&["agent", "start"]).is_err());
+        assert_eq!(parse(&["login"]).unwrap().command, Command::Login);
+        assert_eq!(parse(&["compare"]).unwrap().command, Command::Compare);
+        let args = parse(&["--profile", "work", "input", "2023", "1"]).unwrap();
+        assert_eq!(args.profile.as_deref(), Some("work"));
+        assert_eq!(parse(&["profiles"]).unwrap().command, Command::Profiles);
+        assert!(parse(&["-p"]).is_err());
+        assert_eq!(
+            parse(&["completions", "fish"]).unwrap().command,
+            Command::Completions(Shell::Fish)
+        );
+        assert!(parse(&["completions", "tcsh"]).is_err());
+    }
+
+    /// The completion scripts are written by hand, so make sure they keep up with the usage.
+    #[test]
+    fn completions_cover_usage() {
+        let commands = USAGE
+            .split("Commands:\n")
+            .nth(1)
+            .unwrap()
+            .lines()
+            .take_while(|line| !line.is_empty())
+            .map(|line| line.split_whitespace().next().unwrap());
+        let options = USAGE
+            .split("Options:\n")
+            .nth(1)
+            .unwrap()
+            .lines()
+            .take_while(|line| !line.is_empty())
+            .flat_map(|line| line.split([' ', ',']))
+            .filter(|word| word.starts_with('-'));
+        let words: Vec<_> = commands.chain(options).collect();
+        assert!(words.contains(&"completions") && words.contains(&"--json"));
+        for shell in Shell::ALL {
+            let script = shell.script();
+            for word in &words {
+                let word = word.trim_start_matches('-');
+                assert!(script.contains(word), "{shell:?} completions lack {word}");
+            }
+        }
+    }
 }
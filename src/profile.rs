@@ -0,0 +1,457 @@
+//! The profiles of the browsers, e.g. the work and personal profiles of Firefox or the
+//! `Profile 2` directory of Chrome.
+
+use core::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::browser::CookieStore;
+use crate::{AocSession, Browser, Error, Result};
+
+/// Which Firefox profile to read the session cookie from.
+///
+/// Firefox keeps a separate cookie store per profile, so users with, say, a work and a personal
+/// profile may be logged in to Advent of Code in only one of them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub enum FirefoxProfile {
+    /// The profile Firefox starts with, as marked in its `profiles.ini`.
+    #[default]
+    Default,
+    /// The profile with the given name, as shown on `about:profiles`.
+    Named(String),
+    /// The profile directory at the given path, which doesn't have to be listed in `profiles.ini`.
+    Path(PathBuf),
+}
+
+impl FirefoxProfile {
+    /// The directory of the profile.
+    ///
+    /// Fails with [`Error::ProfileNotFound`] if no profile has the given name.
+    pub fn dir(&self) -> Result<PathBuf> {
+        let profiles = match self {
+            FirefoxProfile::Path(path) => return Ok(path.clone()),
+            _ => Browser::Firefox.profiles()?,
+        };
+        let found = match self {
+            FirefoxProfile::Named(name) => profiles.into_iter().find(|p| p.name == *name),
+            _ => profiles.into_iter().find(|p| p.is_default),
+        };
+        found
+            .map(|profile| profile.path)
+            .ok_or_else(|| Error::ProfileNotFound {
+                browser: Browser::Firefox,
+                name: self.to_string(),
+            })
+    }
+}
+
+impl fmt::Display for FirefoxProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FirefoxProfile::Default => f.write_str("default"),
+            FirefoxProfile::Named(name) => f.write_str(name),
+            FirefoxProfile::Path(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// A profile of a browser, as listed by [`Browser::profiles`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrowserProfile {
+    pub browser: Browser,
+    /// The name of the profile, as shown by the browser.
+    pub name: String,
+    /// The profile directory.
+    pub path: PathBuf,
+    /// Whether the browser starts with this profile.
+    pub is_default: bool,
+}
+
+impl Browser {
+    /// The profiles of the browser.
+    ///
+    /// The profiles of Firefox and LibreWolf are read from their `profiles.ini` files, and those
+    /// of the browsers based on Chromium are the `Default` and `Profile N` directories of their
+    /// user data directories. For the other browsers, only the profile whose cookie store is read
+    /// is listed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use aoc_session::Browser;
+    ///
+    /// for profile in Browser::Firefox.profiles().unwrap() {
+    ///     println!("{} at {}", profile.name, profile.path.display());
+    /// }
+    /// ```
+    pub fn profiles(self) -> Result<Vec<BrowserProfile>> {
+        if !self.is_supported() {
+            return Err(Error::UnsupportedBrowser(self));
+        }
+        let profiles: Vec<_> = self
+            .data_dirs()
+            .iter()
+            .flat_map(|dir| self.profiles_in(dir))
+            .collect();
+        if !profiles.is_empty() {
+            return Ok(profiles);
+        }
+        let path = self.find_store()?.profile();
+        let name = path.file_name().unwrap_or_default();
+        Ok(vec![BrowserProfile {
+            browser: self,
+            name: name.to_string_lossy().into_owned(),
+            path,
+            is_default: true,
+        }])
+    }
+
+    /// The profiles in a directory that holds the `profiles.ini` of a browser based on Firefox, or
+    /// in the user data directory of a browser based on Chromium, e.g. the one given to Chrome
+    /// with `--user-data-dir`.
+    pub(crate) fn profiles_in(self, dir: &Path) -> Vec<BrowserProfile> {
+        if self.is_gecko() {
+            return match std::fs::read_to_string(dir.join("profiles.ini")) {
+                Ok(contents) => parse_profiles_ini(self, dir, &contents),
+                Err(_) => Vec::new(),
+            };
+        }
+        if !self.is_chromium() {
+            return Vec::new();
+        }
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut profiles: Vec<_> = entries
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .filter(|name| name == "Default" || name.starts_with("Profile "))
+            .map(|name| BrowserProfile {
+                browser: self,
+                is_default: name == "Default",
+                path: dir.join(&name),
+                name,
+            })
+            .filter(|profile| CookieStore::in_profile(self, &profile.path).is_ok())
+            .collect();
+        profiles.sort_by_key(|profile| {
+            (
+                !profile.is_default,
+                profile.name.len(),
+                profile.name.clone(),
+            )
+        });
+        // Opera keeps a single profile in the user data directory itself.
+        if profiles.is_empty() && CookieStore::in_profile(self, dir).is_ok() {
+            let name = dir.file_name().unwrap_or_default();
+            profiles.push(BrowserProfile {
+                browser: self,
+                name: name.to_string_lossy().into_owned(),
+                path: dir.to_path_buf(),
+                is_default: true,
+            });
+        }
+        profiles
+    }
+
+    /// The cookie stores of every profile of the browser that has one, or else the one that
+    /// [`rookie`] finds.
+    pub(crate) fn cookie_stores(self) -> Result<Vec<CookieStore>> {
+        let stores: Vec<_> = self
+            .profiles()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|profile| CookieStore::in_profile(self, &profile.path).ok())
+            .collect();
+        if stores.is_empty() {
+            return Ok(vec![self.find_store()?]);
+        }
+        Ok(stores)
+    }
+}
+
+/// A browser, narrowed down to one of its profiles or to a custom data directory.
+///
+/// Without a profile, the default one is read; without a data directory, the usual ones are
+/// searched. The profiles of Firefox are named as on `about:profiles`, and those of the browsers
+/// based on Chromium after their directories, e.g. `Default` or `Profile 2`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoc_session::{AocSessionBuilder, BrowserSource};
+///
+/// let session = AocSessionBuilder::new()
+///     .browser_source(BrowserSource::chrome().profile("Profile 2"))
+///     .resolve()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BrowserSource {
+    browser: Browser,
+    profile: Option<String>,
+    data_dir: Option<PathBuf>,
+}
+
+impl BrowserSource {
+    /// Read the default profile of the browser.
+    pub fn new(browser: Browser) -> Self {
+        BrowserSource {
+            browser,
+            profile: None,
+            data_dir: None,
+        }
+    }
+
+    /// Read Firefox.
+    pub fn firefox() -> Self {
+        Self::new(Browser::Firefox)
+    }
+
+    /// Read Google Chrome.
+    pub fn chrome() -> Self {
+        Self::new(Browser::Chrome)
+    }
+
+    /// Read Chromium.
+    pub fn chromium() -> Self {
+        Self::new(Browser::Chromium)
+    }
+
+    /// Read Microsoft Edge.
+    pub fn edge() -> Self {
+        Self::new(Browser::Edge)
+    }
+
+    /// Read the profile with the given name, e.g. `Profile 2` for Chrome. The name of the
+    /// profile directory is accepted for Firefox too.
+    pub fn profile(mut self, name: impl Into<String>) -> Self {
+        self.profile = Some(name.into());
+        self
+    }
+
+    /// Look the profiles up in the given directory: the user data directory of a browser based
+    /// on Chromium, as given with `--user-data-dir`, or the directory holding the `profiles.ini` of
+    /// Firefox.
+    pub fn user_data_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.data_dir = Some(dir.into());
+        self
+    }
+
+    pub fn browser(&self) -> Browser {
+        self.browser
+    }
+
+    /// The profiles of the browser in the chosen data directory, or in the usual ones.
+    pub fn profiles(&self) -> Result<Vec<BrowserProfile>> {
+        let Some(dir) = &self.data_dir else {
+            return self.browser.profiles();
+        };
+        if !self.browser.is_supported() {
+            return Err(Error::UnsupportedBrowser(self.browser));
+        }
+        let profiles = self.browser.profiles_in(dir);
+        if profiles.is_empty() {
+            return Err(Error::CookieStoreNotFound {
+                browser: self.browser,
+                reason: format!("{} holds no profile", dir.display()),
+            });
+        }
+        Ok(profiles)
+    }
+
+    /// Get the session cookie for Advent of Code from the chosen profile.
+    ///
+    /// Fails with [`Error::ProfileNotFound`] if the browser has no such profile.
+    pub fn resolve(&self) -> Result<AocSession> {
+        if self.profile.is_none() && self.data_dir.is_none() {
+            return crate::aoc_session_from(self.browser);
+        }
+        let profiles = self.profiles()?;
+        let found = match &self.profile {
+            Some(name) => profiles.into_iter().find(|profile| {
+                profile.name == *name || profile.path.file_name() == Some(name.as_ref())
+            }),
+            None => profiles.into_iter().find(|profile| profile.is_default),
+        };
+        let profile = found.ok_or_else(|| Error::ProfileNotFound {
+            browser: self.browser,
+            name: self
+                .profile
+                .clone()
+                .unwrap_or_else(|| "default".to_string()),
+        })?;
+        CookieStore::in_profile(self.browser, &profile.path)?
+            .sessions()?
+            .into_iter()
+            .next()
+            .ok_or(Error::NoSessionCookieFound)
+    }
+}
+
+impl From<Browser> for BrowserSource {
+    fn from(browser: Browser) -> Self {
+        Self::new(browser)
+    }
+}
+
+impl fmt::Display for BrowserSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.profile {
+            Some(profile) => write!(f, "{} profile {profile}", self.browser)?,
+            None => write!(f, "{} cookie store", self.browser)?,
+        }
+        match &self.data_dir {
+            Some(dir) => write!(f, " in {}", dir.display()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Get the session cookie for Advent of Code from a profile of Firefox.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoc_session::{aoc_session_from_firefox_profile, FirefoxProfile};
+///
+/// let profile = FirefoxProfile::Named("work".to_string());
+/// let session = aoc_session_from_firefox_profile(&profile).unwrap();
+/// println!("My session ID: {session}");
+/// ```
+pub fn aoc_session_from_firefox_profile(profile: &FirefoxProfile) -> Result<AocSession> {
+    if !Browser::Firefox.is_supported() {
+        return Err(Error::UnsupportedBrowser(Browser::Firefox));
+    }
+    CookieStore::in_profile(Browser::Firefox, &profile.dir()?)?
+        .sessions()?
+        .into_iter()
+        .next()
+        .ok_or(Error::NoSessionCookieFound)
+}
+
+/// The profiles listed in the `profiles.ini` file in `dir`.
+///
+/// The default profile is the one the `Install` section points to, as Firefox has done since
+/// version 67, or else the one marked with `Default=1`.
+fn parse_profiles_ini(browser: Browser, dir: &Path, contents: &str) -> Vec<BrowserProfile> {
+    let mut sections: Vec<(&str, Vec<(&str, &str)>)> = Vec::new();
+    for line in contents.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            sections.push((name, Vec::new()));
+        } else if let (Some((key, value)), Some((_, entries))) =
+            (line.split_once('='), sections.last_mut())
+        {
+            entries.push((key.trim(), value.trim()));
+        }
+    }
+    let install_default = sections
+        .iter()
+        .filter(|(name, _)| name.starts_with("Install"))
+        .find_map(|(_, entries)| get(entries, "Default"));
+    sections
+        .iter()
+        .filter(|(name, _)| name.starts_with("Profile"))
+        .filter_map(|(_, entries)| {
+            let path = get(entries, "Path")?;
+            let is_default = match install_default {
+                Some(default) => default == path,
+                None => get(entries, "Default") == Some("1"),
+            };
+            Some(BrowserProfile {
+                browser,
+                name: get(entries, "Name").unwrap_or(path).to_string(),
+                path: match get(entries, "IsRelative") {
+                    Some("0") => PathBuf::from(path),
+                    _ => dir.join(path),
+                },
+                is_default,
+            })
+        })
+        .collect()
+}
+
+fn get<'a>(entries: &[(&str, &'a str)], key: &str) -> Option<&'a str> {
+    entries.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROFILES_INI: &str = "\
+[Install4F96D1932A9F858E]
+Default=abcd.default-release
+Locked=1
+
+[Profile1]
+Name=default
+IsRelative=1
+Path=wxyz.default
+Default=1
+
+[Profile0]
+Name=work
+IsRelative=1
+Path=abcd.default-release
+
+[Profile2]
+Name=elsewhere
+IsRelative=0
+Path=/mnt/profiles/elsewhere
+
+[General]
+StartWithLastProfile=1
+Version=2
+";
+
+    #[test]
+    fn profiles_ini_is_parsed() {
+        let dir = Path::new("/home/me/.mozilla/firefox");
+        let profiles = parse_profiles_ini(Browser::Firefox, dir, PROFILES_INI);
+        let summary: Vec<_> = profiles
+            .iter()
+            .map(|p| (p.name.as_str(), p.path.to_str().unwrap(), p.is_default))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("default", "/home/me/.mozilla/firefox/wxyz.default", false),
+                (
+                    "work",
+                    "/home/me/.mozilla/firefox/abcd.default-release",
+                    true
+                ),
+                ("elsewhere", "/mnt/profiles/elsewhere", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn chromium_profiles_are_directories() {
+        let dir = std::env::temp_dir().join("aoc-session-chromium-profiles-test");
+        for profile in [
+            "Profile 10",
+            "Default",
+            "Profile 2",
+            "Guest Profile",
+            "System Profile",
+        ] {
+            std::fs::create_dir_all(dir.join(profile).join("Network")).unwrap();
+            std::fs::write(dir.join(profile).join("Network/Cookies"), "").unwrap();
+        }
+        std::fs::create_dir_all(dir.join("Profile 3")).unwrap();
+        let profiles = Browser::Chrome.profiles_in(&dir);
+        let names: Vec<_> = profiles.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["Default", "Profile 2", "Profile 10"]);
+        let store = CookieStore::in_profile(Browser::Chrome, &profiles[1].path).unwrap();
+        assert_eq!(store.db_path, dir.join("Profile 2/Network/Cookies"));
+    }
+
+    #[test]
+    fn legacy_default_is_marked() {
+        let legacy = PROFILES_INI.replace("Default=abcd.default-release", "");
+        let profiles = parse_profiles_ini(Browser::Firefox, Path::new("/ff"), &legacy);
+        let default: Vec<_> = profiles.iter().filter(|p| p.is_default).collect();
+        assert_eq!(default.len(), 1);
+        assert_eq!(default[0].name, "default");
+    }
+}
@@ -0,0 +1,203 @@
+//! Lookup of the session cookie value behind a trait, so that it can be mocked and composed.
+
+use core::fmt;
+
+use crate::{AocSession, AocSessionBuilder, Browser, BrowserSource, Error, Result, Source};
+
+/// Something that yields a session cookie value.
+///
+/// Code that needs a session can take a `&dyn SessionProvider` rather than calling
+/// [`aoc_session`](crate::aoc_session), so that tests can hand it a [`StaticProvider`] instead
+/// of scanning browsers. [`Source`] (e.g. `Source::Env`, `Source::File(path)`, or
+/// `Source::Keyring`), [`Browser`], [`BrowserSource`], [`AocSessionBuilder`], and closures
+/// returning `Result<AocSession>` are providers too, and [`ChainProvider`] tries several in order.
+///
+/// # Examples
+///
+/// ```
+/// use aoc_session::{AocSession, Result, SessionProvider, StaticProvider};
+///
+/// fn greet(provider: &dyn SessionProvider) -> Result<String> {
+///     Ok(format!("Logged in as {}", provider.provide()?))
+/// }
+///
+/// let session = AocSession::try_new("53616c7465645f5f".repeat(8)).unwrap();
+/// assert!(greet(&StaticProvider::new(session)).is_ok());
+/// ```
+pub trait SessionProvider {
+    /// Look the session cookie value up.
+    ///
+    /// A provider that holds no value should return [`Error::NoSessionCookieFound`], which lets
+    /// a [`ChainProvider`] move on quietly, whereas other errors are reported if no later
+    /// provider yields a value.
+    fn provide(&self) -> Result<AocSession>;
+}
+
+impl SessionProvider for Source {
+    fn provide(&self) -> Result<AocSession> {
+        self.resolve()
+    }
+}
+
+impl SessionProvider for Browser {
+    fn provide(&self) -> Result<AocSession> {
+        Source::Browser(*self).resolve()
+    }
+}
+
+impl SessionProvider for BrowserSource {
+    fn provide(&self) -> Result<AocSession> {
+        self.resolve()
+    }
+}
+
+impl SessionProvider for AocSessionBuilder {
+    fn provide(&self) -> Result<AocSession> {
+        self.resolve()
+    }
+}
+
+impl<F> SessionProvider for F
+where
+    F: Fn() -> Result<AocSession>,
+{
+    fn provide(&self) -> Result<AocSession> {
+        self()
+    }
+}
+
+/// A provider that always yields the same session cookie value.
+#[derive(Debug, Clone)]
+pub struct StaticProvider(AocSession);
+
+impl StaticProvider {
+    /// Create a provider that yields the given session.
+    pub fn new(session: AocSession) -> Self {
+        Self(session)
+    }
+
+    /// Create a provider that yields [`AocSession::fake`] with the given seed, for tests.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoc_session::{SessionProvider, StaticProvider};
+    ///
+    /// let provider = StaticProvider::fake(7);
+    /// assert_eq!(provider.provide().unwrap().expose_secret().len(), 128);
+    /// ```
+    pub fn fake(seed: u64) -> Self {
+        Self(AocSession::fake(seed))
+    }
+
+    /// The session this provider yields.
+    pub fn session(&self) -> &AocSession {
+        &self.0
+    }
+}
+
+impl SessionProvider for StaticProvider {
+    fn provide(&self) -> Result<AocSession> {
+        Ok(self.0.clone())
+    }
+}
+
+/// A provider that tries other providers in order and yields the first value found, with the
+/// same error reporting as [`AocSessionBuilder::resolve`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoc_session::{Browser, ChainProvider, SessionProvider, Source};
+///
+/// let provider = ChainProvider::new()
+///     .with(Source::Env)
+///     .with(|| aoc_session::aoc_session_from_cookies_txt("cookies.txt"))
+///     .with(Browser::Firefox);
+/// println!("My session ID: {}", provider.provide().unwrap());
+/// ```
+#[derive(Default)]
+pub struct ChainProvider {
+    providers: Vec<Box<dyn SessionProvider + Send + Sync>>,
+}
+
+impl ChainProvider {
+    /// Create a chain without providers, which yields nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a provider to the chain.
+    pub fn with(mut self, provider: impl SessionProvider + Send + Sync + 'static) -> Self {
+        self.providers.push(Box::new(provider));
+        self
+    }
+
+    /// The number of providers in the chain.
+    pub fn len(&self) -> usize {
+        self.providers.len()
+    }
+
+    /// Whether the chain has no providers.
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+    }
+}
+
+impl fmt::Debug for ChainProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChainProvider")
+            .field("providers", &self.providers.len())
+            .finish()
+    }
+}
+
+impl SessionProvider for ChainProvider {
+    fn provide(&self) -> Result<AocSession> {
+        let mut error = None;
+        for provider in &self.providers {
+            match provider.provide() {
+                Ok(session) => return Ok(session),
+                Err(e) if e.is_absence() => {}
+                Err(e) => {
+                    error.get_or_insert(e);
+                }
+            }
+        }
+        Err(error.unwrap_or(Error::NoSessionCookieFound))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn absent() -> Result<AocSession> {
+        Err(Error::NoSessionCookieFound)
+    }
+
+    fn broken() -> Result<AocSession> {
+        Err(Error::InvalidConfig("broken".to_string()))
+    }
+
+    #[test]
+    fn chain_yields_the_first_value() {
+        let chain = ChainProvider::new()
+            .with(absent)
+            .with(broken)
+            .with(StaticProvider::new(AocSession::new("25a16c74")))
+            .with(StaticProvider::fake(1));
+        assert_eq!(chain.len(), 4);
+        assert_eq!(chain.provide().unwrap().as_str(), "25a16c74");
+    }
+
+    #[test]
+    fn chain_reports_the_first_real_error() {
+        let chain = ChainProvider::new().with(absent).with(broken).with(absent);
+        assert!(matches!(chain.provide(), Err(Error::InvalidConfig(_))));
+        assert!(matches!(
+            ChainProvider::new().with(absent).provide(),
+            Err(Error::NoSessionCookieFound)
+        ));
+    }
+}
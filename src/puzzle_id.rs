@@ -0,0 +1,247 @@
+//! Years and days of Advent of Code that are checked once, when they're made, so that a request
+//! for day 32 can't be written.
+
+use std::fmt;
+use std::time::SystemTime;
+
+use crate::schedule::{self, FIRST_YEAR};
+use crate::{Error, Result};
+
+/// The most days an event has.
+const MAX_DAY: u8 = 25;
+
+/// The year of an event of Advent of Code, from 2015 to the current year.
+///
+/// # Examples
+///
+/// ```
+/// use aoc_session::PuzzleYear;
+///
+/// assert_eq!(PuzzleYear::try_from(2023).unwrap().get(), 2023);
+/// assert!(PuzzleYear::try_from(2014).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PuzzleYear(u16);
+
+impl PuzzleYear {
+    /// The year, unless Advent of Code had no event that year.
+    pub fn new(year: u16) -> Result<PuzzleYear> {
+        if (FIRST_YEAR..=schedule::current_year()).contains(&year) {
+            Ok(PuzzleYear(year))
+        } else {
+            Err(Error::NoSuchYear(year))
+        }
+    }
+
+    /// The year as a number, e.g. 2023.
+    pub const fn get(self) -> u16 {
+        self.0
+    }
+
+    /// The days of the event: 1 to 25 until 2024, 1 to 12 since 2025.
+    pub fn days(self) -> impl Iterator<Item = PuzzleDay> {
+        (1..=schedule::last_day(self.0)).map(PuzzleDay)
+    }
+
+    /// The puzzles of the event, in order.
+    pub fn puzzles(self) -> impl Iterator<Item = PuzzleId> {
+        self.days().map(move |day| PuzzleId { year: self, day })
+    }
+}
+
+/// A day of an event of Advent of Code, from 1 to 25.
+///
+/// Events since 2025 only have 12 days, which [`PuzzleId::new`] checks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PuzzleDay(u8);
+
+impl PuzzleDay {
+    /// The day, unless no event has it.
+    pub fn new(day: u8) -> Result<PuzzleDay> {
+        if (1..=MAX_DAY).contains(&day) {
+            Ok(PuzzleDay(day))
+        } else {
+            Err(Error::NoSuchDay(day))
+        }
+    }
+
+    /// The day as a number, from 1 to 25.
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+}
+
+/// A puzzle of Advent of Code: a day of the event of a year. It keys the requests of
+/// [`AocClient`](crate::AocClient) and the cache of puzzle inputs.
+///
+/// # Examples
+///
+/// ```
+/// use aoc_session::PuzzleId;
+///
+/// let puzzle = PuzzleId::new(2023, 1).unwrap();
+/// assert_eq!(puzzle.to_string(), "2023 day 1");
+/// assert!(PuzzleId::new(2023, 32).is_err());
+/// assert!(PuzzleId::new(2025, 13).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PuzzleId {
+    year: PuzzleYear,
+    day: PuzzleDay,
+}
+
+impl PuzzleId {
+    /// The puzzle of the given day of the given year, unless the event has no such day.
+    pub fn new(year: u16, day: u8) -> Result<PuzzleId> {
+        PuzzleId::of(PuzzleYear::new(year)?, PuzzleDay::new(day)?)
+    }
+
+    /// The puzzle of the given day of the given year, unless the event has no such day, as
+    /// events since 2025 only have 12.
+    pub fn of(year: PuzzleYear, day: PuzzleDay) -> Result<PuzzleId> {
+        if day.0 <= schedule::last_day(year.0) {
+            Ok(PuzzleId { year, day })
+        } else {
+            Err(Error::NoSuchPuzzle {
+                year: year.0,
+                day: day.0,
+            })
+        }
+    }
+
+    /// The puzzle that unlocked last, while the event of the current year is running: from
+    /// midnight US/Eastern on December 1st to the day after its last puzzle. `None` outside of
+    /// it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoc_session::PuzzleId;
+    ///
+    /// match PuzzleId::today() {
+    ///     Some(puzzle) => println!("Today is {puzzle}"),
+    ///     None => println!("Advent of Code isn't running"),
+    /// }
+    /// ```
+    pub fn today() -> Option<PuzzleId> {
+        PuzzleId::at(SystemTime::now())
+    }
+
+    /// The puzzle that unlocked last at the given time, during the event of its year.
+    pub fn at(time: SystemTime) -> Option<PuzzleId> {
+        let (year, day) = schedule::puzzle_at(time)?;
+        Some(PuzzleId {
+            year: PuzzleYear(year),
+            day: PuzzleDay(day),
+        })
+    }
+
+    /// The year of the event.
+    pub const fn year(self) -> PuzzleYear {
+        self.year
+    }
+
+    /// The day of the event.
+    pub const fn day(self) -> PuzzleDay {
+        self.day
+    }
+}
+
+impl TryFrom<u16> for PuzzleYear {
+    type Error = Error;
+
+    fn try_from(year: u16) -> Result<PuzzleYear> {
+        PuzzleYear::new(year)
+    }
+}
+
+impl TryFrom<u8> for PuzzleDay {
+    type Error = Error;
+
+    fn try_from(day: u8) -> Result<PuzzleDay> {
+        PuzzleDay::new(day)
+    }
+}
+
+impl TryFrom<(u16, u8)> for PuzzleId {
+    type Error = Error;
+
+    fn try_from((year, day): (u16, u8)) -> Result<PuzzleId> {
+        PuzzleId::new(year, day)
+    }
+}
+
+impl From<PuzzleYear> for u16 {
+    fn from(year: PuzzleYear) -> u16 {
+        year.0
+    }
+}
+
+impl From<PuzzleDay> for u8 {
+    fn from(day: PuzzleDay) -> u8 {
+        day.0
+    }
+}
+
+impl fmt::Display for PuzzleYear {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl fmt::Display for PuzzleDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// E.g. `2023 day 1`.
+impl fmt::Display for PuzzleId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} day {}", self.year, self.day)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn puzzles_are_checked() {
+        assert!(PuzzleYear::new(2015).is_ok());
+        assert!(PuzzleYear::new(schedule::current_year()).is_ok());
+        assert!(matches!(
+            PuzzleYear::new(2014),
+            Err(Error::NoSuchYear(2014))
+        ));
+        assert!(PuzzleYear::new(schedule::current_year() + 1).is_err());
+        assert!(matches!(PuzzleDay::new(0), Err(Error::NoSuchDay(0))));
+        assert!(matches!(PuzzleDay::new(32), Err(Error::NoSuchDay(32))));
+        assert!(matches!(
+            PuzzleId::new(2025, 13),
+            Err(Error::NoSuchPuzzle {
+                year: 2025,
+                day: 13
+            })
+        ));
+        let puzzle = PuzzleId::try_from((2024, 25)).unwrap();
+        assert_eq!((puzzle.year().get(), puzzle.day().get()), (2024, 25));
+        assert_eq!(PuzzleYear::new(2025).unwrap().days().count(), 12);
+    }
+
+    #[test]
+    fn puzzle_of_the_day() {
+        let at = |secs| SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(secs);
+        // Midnight US/Eastern on December 1st, 2023, and the second before.
+        assert_eq!(
+            PuzzleId::at(at(1_701_406_800)),
+            Some(PuzzleId::new(2023, 1).unwrap())
+        );
+        assert_eq!(PuzzleId::at(at(1_701_406_799)), None);
+        assert_eq!(
+            PuzzleId::at(at(1_703_480_400)),
+            Some(PuzzleId::new(2023, 25).unwrap())
+        );
+        assert_eq!(PuzzleId::at(at(1_703_480_400 + 86_400)), None);
+    }
+}
@@ -0,0 +1,101 @@
+//! The session cookie value of another machine, fetched over SSH.
+
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+
+use crate::{AocSession, Error, Result};
+
+/// The exit status of `aoc-session` when it finds no session cookie.
+const NO_SESSION_EXIT_CODE: i32 = 2;
+/// The exit status of `ssh` when the connection fails.
+const SSH_FAILURE_EXIT_CODE: i32 = 255;
+/// The exit status of a shell when the command isn't found.
+const COMMAND_NOT_FOUND_EXIT_CODE: i32 = 127;
+
+/// Get the session cookie for Advent of Code from another machine, e.g. the laptop whose browser
+/// is logged in, when solving on a headless server.
+///
+/// This runs `aoc-session --json` on `destination` (e.g. `me@laptop` or a host of
+/// `~/.ssh/config`) through the `ssh` executable, so `aoc-session` must be installed there, on the
+/// `PATH` of non-interactive shells. Only the session cookie value and its expiry time travel back,
+/// through the encrypted channel of SSH. `ssh` runs in batch mode, so the key must be loaded in an
+/// agent or have no passphrase.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoc_session::aoc_session_from_ssh;
+///
+/// let session = aoc_session_from_ssh("me@laptop").unwrap();
+/// println!("My session ID: {session}");
+/// ```
+pub fn aoc_session_from_ssh(destination: &str) -> Result<AocSession> {
+    if destination.starts_with('-') {
+        return Err(Error::Ssh(format!("invalid destination {destination:?}")));
+    }
+    let output = Command::new("ssh")
+        .args(["-T", "-o", "BatchMode=yes", "--", destination])
+        .args(["aoc-session", "--json"])
+        .output()
+        .map_err(|e| Error::Ssh(format!("can't run ssh: {e}")))?;
+    match output.status.code() {
+        Some(0) => parse(&output.stdout),
+        Some(NO_SESSION_EXIT_CODE) => Err(Error::NoSessionCookieFound),
+        Some(COMMAND_NOT_FOUND_EXIT_CODE) => Err(Error::Ssh(format!(
+            "aoc-session isn't installed on {destination}, or not on its PATH"
+        ))),
+        code => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let message = stderr.lines().last().unwrap_or_default().trim();
+            Err(Error::Ssh(match code {
+                Some(SSH_FAILURE_EXIT_CODE) => format!("can't connect to {destination}: {message}"),
+                _ => format!("aoc-session failed on {destination}: {message}"),
+            }))
+        }
+    }
+}
+
+/// The session in the output of `aoc-session --json`.
+fn parse(stdout: &[u8]) -> Result<AocSession> {
+    let json: serde_json::Value = serde_json::from_slice(stdout)
+        .map_err(|e| Error::Ssh(format!("unexpected output of aoc-session: {e}")))?;
+    let value = json["session"]
+        .as_str()
+        .ok_or_else(|| Error::Ssh("aoc-session printed no session".to_string()))?;
+    let expires = json["expires"]
+        .as_u64()
+        .map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs));
+    Ok(AocSession::try_new(value)?.with_expiry(expires))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_is_parsed() {
+        let value = "53616c7465645f5f".repeat(8);
+        let stdout = format!(
+            r#"{{"session":"{value}","source":"firefox cookie store","expires":1700000000}}"#
+        );
+        let session = parse(stdout.as_bytes()).unwrap();
+        assert_eq!(session.expose_secret(), value);
+        assert_eq!(
+            session.expires_at(),
+            Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000))
+        );
+        assert!(matches!(
+            parse(br#"{"session":"session=1"}"#),
+            Err(Error::MalformedToken(_))
+        ));
+        assert!(matches!(parse(b"25a16c74"), Err(Error::Ssh(_))));
+    }
+
+    #[test]
+    fn options_are_not_destinations() {
+        assert!(matches!(
+            aoc_session_from_ssh("-oProxyCommand=true"),
+            Err(Error::Ssh(_))
+        ));
+    }
+}
@@ -0,0 +1,116 @@
+//! The browsers installed as Snap or Flatpak packages on Linux, which keep their profiles apart
+//! from the usual locations, and the sandbox this program may itself run in.
+
+use std::path::{Path, PathBuf};
+
+use crate::Browser;
+
+/// The data directories of the Snap and Flatpak packages of the browser, relative to the home
+/// directory: the directory holding `profiles.ini` for the browsers based on Firefox, and the
+/// user data directory for those based on Chromium. `*` stands for any name, e.g. the revision
+/// of a Snap.
+fn relative_data_dirs(browser: Browser) -> &'static [&'static str] {
+    match browser {
+        Browser::Firefox => &[
+            "snap/firefox/common/.mozilla/firefox",
+            ".var/app/org.mozilla.firefox/.mozilla/firefox",
+        ],
+        Browser::LibreWolf => &[
+            "snap/librewolf/common/.librewolf",
+            ".var/app/io.gitlab.librewolf-community/.librewolf",
+        ],
+        Browser::Chrome => &[".var/app/com.google.Chrome/config/google-chrome"],
+        Browser::Chromium => &[
+            "snap/chromium/common/chromium",
+            ".var/app/org.chromium.Chromium/config/chromium",
+        ],
+        Browser::Edge => &[".var/app/com.microsoft.Edge/config/microsoft-edge"],
+        Browser::Brave => &[
+            "snap/brave/*/.config/BraveSoftware/Brave-Browser",
+            ".var/app/com.brave.Browser/config/BraveSoftware/Brave-Browser",
+        ],
+        Browser::Opera => &[
+            "snap/opera/*/.config/opera",
+            ".var/app/com.opera.Opera/config/opera",
+        ],
+        Browser::Vivaldi => &[".var/app/com.vivaldi.Vivaldi/config/vivaldi"],
+        Browser::Safari | Browser::Arc => &[],
+    }
+}
+
+/// The existing data directories of the Snap and Flatpak packages of the browser, if this is
+/// Linux.
+pub(crate) fn data_dirs(browser: Browser, home: &Path) -> Vec<PathBuf> {
+    if !cfg!(target_os = "linux") {
+        return Vec::new();
+    }
+    relative_data_dirs(browser)
+        .iter()
+        .flat_map(|dir| expand(home, dir))
+        .filter(|dir| dir.is_dir())
+        .collect()
+}
+
+/// The paths matching `pattern` under `base`, where a component of `*` matches any directory.
+fn expand(base: &Path, pattern: &str) -> Vec<PathBuf> {
+    let mut paths = vec![base.to_path_buf()];
+    for component in pattern.split('/') {
+        paths = if component == "*" {
+            let mut matches: Vec<_> = paths
+                .iter()
+                .filter_map(|path| std::fs::read_dir(path).ok())
+                .flatten()
+                .filter_map(|entry| Some(entry.ok()?.path()))
+                .collect();
+            // A Snap links `current` to the revision in use; otherwise the newest revision has the
+            // highest number.
+            match matches.iter().find(|path| path.ends_with("current")) {
+                Some(current) => vec![current.clone()],
+                None => {
+                    matches.sort();
+                    matches.reverse();
+                    matches
+                }
+            }
+        } else {
+            paths.into_iter().map(|path| path.join(component)).collect()
+        };
+    }
+    paths
+}
+
+/// The kind of sandbox this program runs in, if any. The cookie stores of the browsers are
+/// usually out of its reach there.
+#[cfg(all(feature = "browser", not(target_family = "wasm")))]
+pub(crate) fn current() -> Option<&'static str> {
+    if !cfg!(target_os = "linux") {
+        None
+    } else if std::env::var_os("FLATPAK_ID").is_some() {
+        Some("Flatpak")
+    } else if std::env::var_os("SNAP_NAME").is_some() {
+        Some("Snap")
+    } else {
+        None
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snap_revisions_are_expanded() {
+        let home = std::env::temp_dir().join("aoc-session-sandbox-test");
+        for revision in ["228", "231", "current"] {
+            let dir = format!("snap/brave/{revision}/.config/BraveSoftware/Brave-Browser");
+            std::fs::create_dir_all(home.join(dir)).unwrap();
+        }
+        let dirs = data_dirs(Browser::Brave, &home);
+        assert_eq!(dirs.len(), 1);
+        assert!(dirs[0].starts_with(home.join("snap/brave/current")));
+        std::fs::remove_dir_all(home.join("snap/brave/current")).unwrap();
+        let dirs = data_dirs(Browser::Brave, &home);
+        assert!(dirs[0].starts_with(home.join("snap/brave/231")));
+        assert_eq!(data_dirs(Browser::Vivaldi, &home), Vec::<PathBuf>::new());
+    }
+}
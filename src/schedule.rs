@@ -0,0 +1,137 @@
+//! When the puzzles of Advent of Code unlock.
+//!
+//! Puzzles unlock at midnight US/Eastern, which is always 05:00 UTC in December since daylight
+//! saving time is over by then.
+//!
+//! # Examples
+//!
+//! ```
+//! use aoc_session::schedule;
+//!
+//! let unlock = schedule::unlock_time(2023, 1).unwrap();
+//! match schedule::time_until_unlock(2023, 1) {
+//!     Some(wait) => println!("Day 1 unlocks in {}s", wait.as_secs()),
+//!     None => println!("Day 1 unlocked at {unlock:?}"),
+//! }
+//! ```
+
+use std::time::{Duration, SystemTime};
+
+/// The year of the first event.
+pub const FIRST_YEAR: u16 = 2015;
+
+/// The hour of the day, in UTC, when puzzles unlock.
+const UNLOCK_HOUR_UTC: u64 = 5;
+
+/// The number of puzzles of the event of the given year: 25 until 2024, 12 since 2025.
+pub fn last_day(year: u16) -> u8 {
+    if year >= 2025 {
+        12
+    } else {
+        25
+    }
+}
+
+/// When the puzzle of the given day unlocks, or `None` if the event has no such day.
+pub fn unlock_time(year: u16, day: u8) -> Option<SystemTime> {
+    if year < FIRST_YEAR || !(1..=last_day(year)).contains(&day) {
+        return None;
+    }
+    let days = days_since_epoch(year, day);
+    let secs = days * 24 * 60 * 60 + UNLOCK_HOUR_UTC * 60 * 60;
+    Some(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+}
+
+/// How long until the puzzle of the given day unlocks, or `None` if it's already unlocked or
+/// doesn't exist.
+pub fn time_until_unlock(year: u16, day: u8) -> Option<Duration> {
+    unlock_time(year, day)?
+        .duration_since(SystemTime::now())
+        .ok()
+        .filter(|wait| !wait.is_zero())
+}
+
+/// The puzzle that unlocked last, as `(year, day)`, while the event of the current year is
+/// running; `None` outside of it. See [`PuzzleId::today`](crate::PuzzleId::today).
+///
+/// # Examples
+///
+/// ```
+/// match aoc_session::schedule::today() {
+///     Some((year, day)) => println!("Today is day {day} of {year}"),
+///     None => println!("Advent of Code isn't running"),
+/// }
+/// ```
+pub fn today() -> Option<(u16, u8)> {
+    puzzle_at(SystemTime::now())
+}
+
+/// The year it is, in UTC.
+pub(crate) fn current_year() -> u16 {
+    year_at(SystemTime::now()).unwrap_or(u16::MAX)
+}
+
+fn year_at(time: SystemTime) -> Option<u16> {
+    let secs = time.duration_since(SystemTime::UNIX_EPOCH).ok()?.as_secs();
+    // Close enough: the event is nowhere near the turn of the year.
+    u16::try_from(1970 + secs / 31_556_952).ok()
+}
+
+pub(crate) fn puzzle_at(time: SystemTime) -> Option<(u16, u8)> {
+    let year = year_at(time)?;
+    let first = unlock_time(year, 1)?;
+    let elapsed = time.duration_since(first).ok()?;
+    let day = u8::try_from(elapsed.as_secs() / (24 * 60 * 60) + 1).ok()?;
+    (day <= last_day(year)).then_some((year, day))
+}
+
+/// Block the thread until the puzzle of the given day unlocks.
+///
+/// The sleep is split into short steps, so that changes of the system clock, e.g. after
+/// suspending a laptop overnight, are noticed.
+#[cfg(feature = "client")]
+pub(crate) fn sleep_until_unlock(year: u16, day: u8) {
+    const STEP: Duration = Duration::from_secs(60);
+    while let Some(wait) = time_until_unlock(year, day) {
+        std::thread::sleep(wait.min(STEP));
+    }
+}
+
+/// The number of days from 1970-01-01 to December `day` of `year`, per the `days_from_civil`
+/// algorithm of Howard Hinnant.
+fn days_since_epoch(year: u16, day: u8) -> u64 {
+    let year = u64::from(year);
+    let era = year / 400;
+    let year_of_era = year - era * 400;
+    // March is the first month of the shifted year, so December is its tenth month.
+    let day_of_year = (153 * 9 + 2) / 5 + u64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unlock_times() {
+        let at = |secs| Some(SystemTime::UNIX_EPOCH + Duration::from_secs(secs));
+        assert_eq!(unlock_time(2015, 1), at(1_448_946_000));
+        assert_eq!(unlock_time(2024, 25), at(1_735_102_800));
+        assert_eq!(unlock_time(2014, 1), None);
+        assert_eq!(unlock_time(2023, 0), None);
+        assert_eq!(unlock_time(2025, 13), None);
+        assert_eq!(time_until_unlock(2015, 1), None);
+    }
+
+    #[test]
+    fn puzzle_of_the_day() {
+        let at = |secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+        assert_eq!(puzzle_at(at(1_448_946_000)), Some((2015, 1)));
+        assert_eq!(puzzle_at(at(1_448_946_000 - 1)), None);
+        assert_eq!(puzzle_at(at(1_735_102_800 + 86_399)), Some((2024, 25)));
+        assert_eq!(puzzle_at(at(1_735_102_800 + 86_400)), None);
+        // Mid-July.
+        assert_eq!(puzzle_at(at(1_720_000_000)), None);
+    }
+}
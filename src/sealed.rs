@@ -0,0 +1,195 @@
+//! Encryption at rest of the session cookie value in the token file and the session cache, with
+//! the `encrypted-cache` feature, so that a dotfile committed by accident doesn't give the session
+//! away.
+//!
+//! A sealed file holds `aoc-session-sealed:v1:<nonce><ciphertext>` in hexadecimal, encrypted
+//! with AES-256-GCM under a random key kept apart from it: in the OS credential store with the
+//! `keyring` feature, or else in `sealing.key` in the data directory, e.g.
+//! `~/.local/share/aoc-session/sealing.key`, which is only readable by the current user on Unix.
+
+use std::path::Path;
+
+use crate::{Error, Result};
+
+/// What sealed contents start with.
+const PREFIX: &str = "aoc-session-sealed:v1:";
+
+/// Seal the contents of a file with the `encrypted-cache` feature; leave them as is without it.
+pub(crate) fn seal_if_enabled(contents: String) -> Result<String> {
+    #[cfg(feature = "encrypted-cache")]
+    {
+        let sealed = imp::seal(&contents);
+        let mut contents = contents;
+        crate::secret::wipe(&mut contents);
+        sealed
+    }
+    #[cfg(not(feature = "encrypted-cache"))]
+    Ok(contents)
+}
+
+/// The contents of the file at `path`, decrypted if they're sealed.
+pub(crate) fn unseal(path: &Path, contents: String) -> Result<String> {
+    let Some(sealed) = contents.trim().strip_prefix(PREFIX) else {
+        return Ok(contents);
+    };
+    let failed = |reason: String| Error::SealedFile {
+        path: path.to_path_buf(),
+        reason,
+    };
+    #[cfg(feature = "encrypted-cache")]
+    return imp::unseal(sealed).map_err(failed);
+    #[cfg(not(feature = "encrypted-cache"))]
+    {
+        let _ = sealed;
+        Err(failed(
+            "this build lacks the `encrypted-cache` feature".to_string(),
+        ))
+    }
+}
+
+#[cfg(feature = "encrypted-cache")]
+mod imp {
+    use std::sync::OnceLock;
+
+    use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+    use aes_gcm::{Aes256Gcm, Key, Nonce};
+
+    use super::PREFIX;
+    use crate::{Error, Result};
+
+    /// The name of the key file in the data directory.
+    const KEY_FILE_NAME: &str = "sealing.key";
+    const NONCE_LEN: usize = 12;
+
+    /// The key, looked up once per process.
+    static KEY: OnceLock<[u8; 32]> = OnceLock::new();
+
+    pub(super) fn seal(plaintext: &str) -> Result<String> {
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key()?));
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .expect("AES-GCM encrypts messages this short");
+        Ok(format!(
+            "{PREFIX}{}{}\n",
+            hex(nonce.as_slice()),
+            hex(&ciphertext)
+        ))
+    }
+
+    pub(super) fn unseal(sealed: &str) -> std::result::Result<String, String> {
+        let bytes = unhex(sealed).ok_or("the file is garbled")?;
+        if bytes.len() < NONCE_LEN {
+            return Err("the file is garbled".to_string());
+        }
+        let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+        let key = key().map_err(|e| e.to_string())?;
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| "it was sealed with another key".to_string())?;
+        String::from_utf8(plaintext).map_err(|_| "the file is garbled".to_string())
+    }
+
+    /// The key, created on first use.
+    fn key() -> Result<[u8; 32]> {
+        if let Some(key) = KEY.get() {
+            return Ok(*key);
+        }
+        let key = load_or_create()?;
+        Ok(*KEY.get_or_init(|| key))
+    }
+
+    fn load_or_create() -> Result<[u8; 32]> {
+        let parse = |stored: &str| -> Option<[u8; 32]> { unhex(stored.trim())?.try_into().ok() };
+        #[cfg(feature = "keyring")]
+        match crate::keyring::load_sealing_key() {
+            Ok(stored) => {
+                if let Some(key) = parse(&stored) {
+                    return Ok(key);
+                }
+            }
+            Err(Error::KeyringEntryNotFound) => {
+                // Only a key file made while the credential store was unavailable counts.
+                if !key_file_exists() {
+                    let key = new_key();
+                    if crate::keyring::store_sealing_key(&hex(&key)).is_ok() {
+                        return Ok(key);
+                    }
+                }
+            }
+            // The credential store is unavailable; fall back to the key file.
+            Err(_) => {}
+        }
+        let path = crate::dirs::data_dir()
+            .ok_or(Error::NoConfigDir)?
+            .join(KEY_FILE_NAME);
+        match std::fs::read_to_string(&path) {
+            Ok(stored) => parse(&stored).ok_or_else(|| {
+                Error::InvalidConfig(format!("{} isn't a sealing key", path.display()))
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let key = new_key();
+                crate::fs::write_private(&path, &format!("{}\n", hex(&key)))?;
+                Ok(key)
+            }
+            Err(source) => Err(Error::Io { path, source }),
+        }
+    }
+
+    #[cfg(feature = "keyring")]
+    fn key_file_exists() -> bool {
+        crate::dirs::data_dir().is_some_and(|dir| dir.join(KEY_FILE_NAME).exists())
+    }
+
+    fn new_key() -> [u8; 32] {
+        Aes256Gcm::generate_key(&mut OsRng).into()
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+
+    fn unhex(s: &str) -> Option<Vec<u8>> {
+        if !s.len().is_multiple_of(2) || !s.is_ascii() {
+            return None;
+        }
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).ok())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plaintext_is_left_alone() {
+        let path = Path::new("token");
+        assert_eq!(
+            unseal(path, "25a16c74\n".to_string()).unwrap(),
+            "25a16c74\n"
+        );
+    }
+
+    #[cfg(feature = "encrypted-cache")]
+    #[test]
+    fn sealed_contents_round_trip() {
+        let sealed = seal_if_enabled("25a16c74\n".to_string()).unwrap();
+        assert!(sealed.starts_with(PREFIX));
+        assert!(!sealed.contains("25a16c74"));
+        let path = Path::new("token");
+        assert_eq!(unseal(path, sealed.clone()).unwrap(), "25a16c74\n");
+        // Flip a hex digit of the ciphertext, past the nonce, so that authentication fails.
+        let mut tampered = sealed.into_bytes();
+        let i = PREFIX.len() + 2 * 12 + 1;
+        tampered[i] = if tampered[i] == b'0' { b'1' } else { b'0' };
+        let tampered = String::from_utf8(tampered).unwrap();
+        assert!(matches!(
+            unseal(path, tampered),
+            Err(Error::SealedFile { reason, .. }) if reason == "it was sealed with another key"
+        ));
+    }
+}
@@ -0,0 +1,105 @@
+//! Handling of strings holding the session cookie value, so that as few copies as possible are
+//! left behind in memory.
+
+/// Trim surrounding whitespace without copying the string.
+pub(crate) fn trim(mut s: String) -> String {
+    s.truncate(s.trim_end().len());
+    let start = s.len() - s.trim_start().len();
+    s.drain(..start);
+    s
+}
+
+/// Undo the usual accidents of pasting a session cookie value by hand, without copying the
+/// string: surrounding whitespace, a leading `session=` and trailing `;` copied along from a
+/// `Cookie` header, and uppercase hex digits.
+pub(crate) fn normalize(s: String) -> String {
+    const PREFIX: &str = "session=";
+    let mut s = trim(s);
+    if s.get(..PREFIX.len())
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(PREFIX))
+    {
+        s.drain(..PREFIX.len());
+    }
+    if s.ends_with(';') {
+        s.pop();
+    }
+    let mut s = trim(s);
+    if s.bytes().all(|b| b.is_ascii_hexdigit()) {
+        s.make_ascii_lowercase();
+    }
+    s
+}
+
+/// Whether the strings are equal, in a time that only depends on their lengths, so that
+/// comparing a guess against a session cookie value doesn't tell how much of it is right.
+pub(crate) fn constant_time_eq(a: &str, b: &str) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    // `black_box` keeps the compiler from stopping at the first difference.
+    let diff = a
+        .bytes()
+        .zip(b.bytes())
+        .fold(0, |diff, (x, y)| std::hint::black_box(diff | (x ^ y)));
+    diff == 0
+}
+
+/// Overwrite the whole buffer of the string with zeros, including the spare capacity that may
+/// hold stale bytes, and clear it. Does nothing without the `zeroize` feature.
+pub(crate) fn wipe(s: &mut String) {
+    #[cfg(feature = "zeroize")]
+    {
+        // SAFETY: the buffer is only filled with zeros, which is valid UTF-8, and then cleared.
+        let bytes = unsafe { s.as_mut_vec() };
+        let ptr = bytes.as_mut_ptr();
+        for i in 0..bytes.capacity() {
+            // SAFETY: `i` is within the allocation. Volatile writes aren't optimized away even
+            // though the buffer is about to be freed.
+            unsafe { std::ptr::write_volatile(ptr.add(i), 0) };
+        }
+        std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
+    }
+    s.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trim_keeps_the_buffer() {
+        let s = String::from(" \t25a16c74\n");
+        let ptr = s.as_ptr();
+        let mut s = trim(s);
+        assert_eq!(s, "25a16c74");
+        assert_eq!(s.as_ptr(), ptr);
+        wipe(&mut s);
+        assert!(s.is_empty());
+        #[cfg(feature = "zeroize")]
+        // SAFETY: the wiped buffer is still allocated and was filled with zeros.
+        unsafe {
+            let buffer = std::slice::from_raw_parts(s.as_ptr(), s.capacity());
+            assert!(buffer.iter().all(|&b| b == 0));
+        }
+    }
+
+    #[test]
+    fn equality() {
+        assert!(constant_time_eq("25a16c74", "25a16c74"));
+        assert!(!constant_time_eq("25a16c74", "25a16c75"));
+        assert!(!constant_time_eq("25a16c74", "25a16c7"));
+        assert!(constant_time_eq("", ""));
+    }
+
+    #[test]
+    fn pasting_accidents_are_undone() {
+        let s = String::from(" Session=25A16C74;\n");
+        let ptr = s.as_ptr();
+        let s = normalize(s);
+        assert_eq!(s, "25a16c74");
+        assert_eq!(s.as_ptr(), ptr);
+        assert_eq!(normalize("session= 25a1;".to_string()), "25a1");
+        assert_eq!(normalize("Ab-Cd".to_string()), "Ab-Cd");
+        assert_eq!(normalize("sessionid=FF".to_string()), "sessionid=FF");
+    }
+}
@@ -0,0 +1,159 @@
+//! The session cookie value kept in a password manager shared by a team: 1Password, Bitwarden, or
+//! `pass`, read through their command-line tools.
+
+use core::fmt;
+
+#[cfg(feature = "secret-managers")]
+use crate::{Error, Result};
+
+/// An item of a password manager that holds the session cookie value as its password.
+///
+/// Items are named in the `[secret_managers]` table of the configuration file (see
+/// [`Config`](crate::Config)), and looked up with the `secret-managers` feature.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SecretManager {
+    /// An item of 1Password, read with `op`, which must be signed in: either a secret reference
+    /// such as `op://Private/Advent of Code/password`, or the name or ID of an item whose
+    /// `password` field holds the value.
+    OnePassword(String),
+    /// An item of Bitwarden, by name or ID, read with `bw get password`. The vault must be
+    /// unlocked, with `BW_SESSION` set.
+    Bitwarden(String),
+    /// An entry of `pass`, the standard Unix password manager, whose first line holds the value.
+    Pass(String),
+}
+
+impl SecretManager {
+    /// The item of the password manager named by `key` in the `[secret_managers]` table.
+    pub(crate) fn from_key(key: &str, item: String) -> Option<SecretManager> {
+        match key {
+            "op" => Some(SecretManager::OnePassword(item)),
+            "bw" => Some(SecretManager::Bitwarden(item)),
+            "pass" => Some(SecretManager::Pass(item)),
+            _ => None,
+        }
+    }
+
+    /// The name of the item.
+    pub fn item(&self) -> &str {
+        match self {
+            SecretManager::OnePassword(item)
+            | SecretManager::Bitwarden(item)
+            | SecretManager::Pass(item) => item,
+        }
+    }
+}
+
+/// E.g. `1Password item Advent of Code`.
+impl fmt::Display for SecretManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SecretManager::OnePassword(_) => "1Password",
+            SecretManager::Bitwarden(_) => "Bitwarden",
+            SecretManager::Pass(_) => "pass",
+        };
+        write!(f, "{name} item {}", self.item())
+    }
+}
+
+/// Get the session cookie for Advent of Code from an item of a password manager, through its
+/// command-line tool, which must be on the `PATH` and unlocked.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoc_session::{aoc_session_from_secret_manager, SecretManager};
+///
+/// let item = SecretManager::Pass("aoc/session".to_string());
+/// let session = aoc_session_from_secret_manager(&item).unwrap();
+/// println!("My session ID: {session}");
+/// ```
+#[cfg(feature = "secret-managers")]
+pub fn aoc_session_from_secret_manager(
+    secret_manager: &SecretManager,
+) -> Result<crate::AocSession> {
+    let item = secret_manager.item();
+    if item.is_empty() || item.starts_with('-') {
+        return Err(Error::SecretManager(format!("invalid item name {item:?}")));
+    }
+    let (program, args): (_, &[&str]) = match secret_manager {
+        SecretManager::OnePassword(reference) if reference.starts_with("op://") => {
+            ("op", &["read", "--no-newline"])
+        }
+        SecretManager::OnePassword(_) => {
+            ("op", &["item", "get", "--fields", "password", "--reveal"])
+        }
+        SecretManager::Bitwarden(_) => ("bw", &["get", "password"]),
+        SecretManager::Pass(_) => ("pass", &["show"]),
+    };
+    let output = std::process::Command::new(program)
+        .args(args)
+        .arg(item)
+        .stdin(std::process::Stdio::null())
+        .output()
+        .map_err(|e| Error::SecretManager(format!("can't run {program}: {e}")))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = stderr.lines().rfind(|line| !line.trim().is_empty());
+        return Err(Error::SecretManager(format!(
+            "{program} can't read {item:?}: {}",
+            message.unwrap_or("it failed").trim()
+        )));
+    }
+    let mut stdout = String::from_utf8(output.stdout)
+        .map_err(|_| Error::SecretManager(format!("{program} printed no text")))?;
+    let session = parse(&stdout);
+    crate::secret::wipe(&mut stdout);
+    session
+}
+
+/// The session cookie value on the first line of the output of a password manager.
+#[cfg(feature = "secret-managers")]
+fn parse(stdout: &str) -> Result<crate::AocSession> {
+    match stdout.lines().next().map(str::trim) {
+        Some(value) if !value.is_empty() => crate::AocSession::try_new(value),
+        _ => Err(Error::NoSessionCookieFound),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_name_password_managers() {
+        let item = || "Advent of Code".to_string();
+        assert_eq!(
+            SecretManager::from_key("op", item()),
+            Some(SecretManager::OnePassword(item()))
+        );
+        assert_eq!(
+            SecretManager::from_key("bw", item()),
+            Some(SecretManager::Bitwarden(item()))
+        );
+        assert_eq!(
+            SecretManager::from_key("pass", item()).unwrap().to_string(),
+            "pass item Advent of Code"
+        );
+        assert_eq!(SecretManager::from_key("lastpass", item()), None);
+    }
+
+    #[cfg(feature = "secret-managers")]
+    #[test]
+    fn first_line_is_the_value() {
+        let value = "53616c7465645f5f".repeat(8);
+        let session = parse(&format!("{value}\nurl: adventofcode.com\n")).unwrap();
+        assert_eq!(session.expose_secret(), value);
+        assert!(matches!(parse("\n"), Err(Error::NoSessionCookieFound)));
+        assert!(matches!(parse("session=1"), Err(Error::MalformedToken(_))));
+    }
+
+    #[cfg(feature = "secret-managers")]
+    #[test]
+    fn options_are_not_items() {
+        assert!(matches!(
+            aoc_session_from_secret_manager(&SecretManager::Pass("--help".to_string())),
+            Err(Error::SecretManager(_))
+        ));
+    }
+}
@@ -0,0 +1,149 @@
+use crate::{Browser, DiscoveredSession};
+
+/// How to pick one session cookie when several browsers hold one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SelectionStrategy {
+    /// The first session found, in the order of [`Browser::ALL`].
+    First,
+    /// The session whose cookie expires last, i.e. the one that was issued most recently.
+    /// Sessions without a recorded expiry are considered the oldest.
+    #[default]
+    Newest,
+    /// The newest session of the given browser, or the newest session overall if that browser
+    /// holds none.
+    PreferBrowser(Browser),
+    /// Whichever session is found first. The browsers are probed in parallel, and the result is
+    /// returned as soon as any of them yields a session, without waiting for the slower ones.
+    ///
+    /// When selecting from already discovered sessions, this is the same as [`First`](Self::First).
+    Fastest,
+}
+
+impl SelectionStrategy {
+    /// Pick one of the discovered sessions, e.g. as returned by [`aoc_sessions`](crate::aoc_sessions).
+    pub fn select(self, sessions: Vec<DiscoveredSession>) -> Option<DiscoveredSession> {
+        match self {
+            SelectionStrategy::First | SelectionStrategy::Fastest => sessions.into_iter().next(),
+            SelectionStrategy::Newest => newest(sessions),
+            SelectionStrategy::PreferBrowser(browser) => {
+                let (preferred, others) = sessions
+                    .into_iter()
+                    .partition::<Vec<_>, _>(|s| s.browser == browser);
+                newest(preferred).or_else(|| newest(others))
+            }
+        }
+    }
+
+    /// Pick one of the sessions arriving in batches, one batch per browser, in no particular
+    /// order. Returns early if the strategy allows it, abandoning the remaining batches.
+    pub(crate) fn select_as_found(
+        self,
+        batches: impl IntoIterator<Item = Vec<DiscoveredSession>>,
+    ) -> Option<DiscoveredSession> {
+        let mut found = Vec::new();
+        for batch in batches {
+            match self {
+                SelectionStrategy::Fastest if !batch.is_empty() => return batch.into_iter().next(),
+                SelectionStrategy::PreferBrowser(browser)
+                    if batch.first().is_some_and(|s| s.browser == browser) =>
+                {
+                    return newest(batch);
+                }
+                _ => found.extend(batch),
+            }
+        }
+        self.select(in_browser_order(found))
+    }
+}
+
+/// Sort the sessions in the order of [`Browser::ALL`], keeping the order within each browser.
+pub(crate) fn in_browser_order(mut sessions: Vec<DiscoveredSession>) -> Vec<DiscoveredSession> {
+    sessions.sort_by_key(|s| Browser::ALL.iter().position(|&b| b == s.browser));
+    sessions
+}
+
+fn newest(sessions: Vec<DiscoveredSession>) -> Option<DiscoveredSession> {
+    // `max_by_key` returns the last maximum; reverse to keep the first one on ties.
+    sessions.into_iter().rev().max_by_key(|s| s.expires)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime};
+
+    use super::*;
+    use crate::AocSession;
+
+    fn found(browser: Browser, value: &str, expires: Option<u64>) -> DiscoveredSession {
+        DiscoveredSession {
+            browser,
+            profile: PathBuf::new(),
+            expires: expires.map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs)),
+            session: AocSession::new(value),
+        }
+    }
+
+    fn sessions() -> Vec<DiscoveredSession> {
+        vec![
+            found(Browser::Firefox, "aa", Some(10)),
+            found(Browser::Chrome, "bb", Some(30)),
+            found(Browser::Chrome, "cc", None),
+            found(Browser::Edge, "dd", Some(20)),
+        ]
+    }
+
+    fn selected(strategy: SelectionStrategy) -> String {
+        strategy
+            .select(sessions())
+            .unwrap()
+            .session
+            .as_str()
+            .to_string()
+    }
+
+    #[test]
+    fn strategies() {
+        assert_eq!(selected(SelectionStrategy::First), "aa");
+        assert_eq!(selected(SelectionStrategy::Newest), "bb");
+        assert_eq!(
+            selected(SelectionStrategy::PreferBrowser(Browser::Edge)),
+            "dd"
+        );
+        assert_eq!(
+            selected(SelectionStrategy::PreferBrowser(Browser::Brave)),
+            "bb"
+        );
+        assert!(SelectionStrategy::Newest.select(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn strategies_as_found() {
+        let batches = || {
+            vec![
+                vec![found(Browser::Edge, "dd", Some(20))],
+                vec![],
+                vec![
+                    found(Browser::Chrome, "bb", Some(30)),
+                    found(Browser::Chrome, "cc", None),
+                ],
+                vec![found(Browser::Firefox, "aa", Some(10))],
+            ]
+        };
+        let selected = |strategy: SelectionStrategy| {
+            strategy
+                .select_as_found(batches())
+                .unwrap()
+                .session
+                .as_str()
+                .to_string()
+        };
+        assert_eq!(selected(SelectionStrategy::Fastest), "dd");
+        assert_eq!(selected(SelectionStrategy::First), "aa");
+        assert_eq!(selected(SelectionStrategy::Newest), "bb");
+        assert_eq!(
+            selected(SelectionStrategy::PreferBrowser(Browser::Chrome)),
+            "bb"
+        );
+    }
+}
@@ -0,0 +1,168 @@
+//! (De)serialization of [`AocSession`], and serialization of [`DiagnosticsReport`].
+
+use std::time::SystemTime;
+
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::{AocSession, DiagnosticsReport, ErrorDetails, SourceDiagnosis, SourceStatus};
+
+/// What a session serializes to unless it's exposed with [`AocSession::serialize_exposed`].
+const REDACTED: &str = "<redacted>";
+
+/// Serializes to a placeholder string so that the session cookie value doesn't end up in logs or
+/// dumped configs by accident. Use [`AocSession::serialize_exposed`] to serialize the value.
+impl Serialize for AocSession {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(REDACTED)
+    }
+}
+
+/// Deserializes from the session cookie value, validated by [`AocSession::try_new`].
+impl<'de> Deserialize<'de> for AocSession {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        if value == REDACTED {
+            return Err(de::Error::custom(
+                "the session cookie value was redacted when serialized",
+            ));
+        }
+        AocSession::try_new(value).map_err(de::Error::custom)
+    }
+}
+
+impl AocSession {
+    /// Serialize the session cookie value itself rather than a placeholder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoc_session::AocSession;
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// struct Settings {
+    ///     #[serde(serialize_with = "AocSession::serialize_exposed")]
+    ///     session: AocSession,
+    /// }
+    /// ```
+    pub fn serialize_exposed<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.value)
+    }
+}
+
+/// Serializes to an object with a `sources` array.
+impl Serialize for DiagnosticsReport {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut report = serializer.serialize_struct("DiagnosticsReport", 1)?;
+        report.serialize_field("sources", &self.sources)?;
+        report.end()
+    }
+}
+
+/// Serializes to an object whose `status` is one of `unsupported`, `unavailable`, `failed`,
+/// `no_session` and `session`, with the expiry in seconds since the Unix epoch and the error as
+/// an object with a `code` and a `message`.
+impl Serialize for SourceDiagnosis {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let status = match self.status {
+            SourceStatus::Unsupported => "unsupported",
+            SourceStatus::Unavailable(_) => "unavailable",
+            SourceStatus::Failed(_) => "failed",
+            SourceStatus::NoSession => "no_session",
+            SourceStatus::Session { .. } => "session",
+        };
+        let expires = self.expires_at().map(|expires| {
+            expires
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs()
+        });
+        let mut diagnosis = serializer.serialize_struct("SourceDiagnosis", 8)?;
+        diagnosis.serialize_field("source", &self.source.to_string())?;
+        diagnosis.serialize_field("available", &self.is_available())?;
+        diagnosis.serialize_field("status", status)?;
+        diagnosis.serialize_field("expires", &expires)?;
+        diagnosis.serialize_field("error", &self.error())?;
+        diagnosis.serialize_field("database", &self.db_path)?;
+        diagnosis.serialize_field("elapsed_ms", &(self.elapsed.as_millis() as u64))?;
+        diagnosis.serialize_field("hint", &self.hint)?;
+        diagnosis.end()
+    }
+}
+
+impl Serialize for ErrorDetails {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut error = serializer.serialize_struct("ErrorDetails", 2)?;
+        error.serialize_field("code", self.code)?;
+        error.serialize_field("message", &self.message)?;
+        error.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct Settings {
+        session: AocSession,
+        #[serde(serialize_with = "AocSession::serialize_exposed")]
+        exposed: AocSession,
+    }
+
+    #[test]
+    fn redacted_unless_exposed() {
+        let token = "53616c7465645f5f".repeat(8);
+        let json = format!(r#"{{"session": " {token}\n", "exposed": "{token}"}}"#);
+        let settings: Settings = serde_json::from_str(&json).unwrap();
+        assert_eq!(settings.session.as_str(), token);
+        assert_eq!(
+            serde_json::to_string(&settings).unwrap(),
+            format!(r#"{{"session":"<redacted>","exposed":"{token}"}}"#)
+        );
+        assert!(serde_json::from_str::<AocSession>(r#""<redacted>""#).is_err());
+        assert!(serde_json::from_str::<AocSession>(r#""  ""#).is_err());
+    }
+
+    #[test]
+    fn diagnostics_report() {
+        let report = DiagnosticsReport {
+            sources: vec![
+                SourceDiagnosis {
+                    source: crate::Source::Env,
+                    db_path: None,
+                    elapsed: Duration::ZERO,
+                    status: SourceStatus::Unavailable(ErrorDetails {
+                        code: "env_var_not_set",
+                        message: "AOC_SESSION is not set".to_string(),
+                    }),
+                    hint: None,
+                },
+                SourceDiagnosis {
+                    source: crate::Source::Browser(crate::Browser::Firefox),
+                    db_path: Some(PathBuf::from("/profile/cookies.sqlite")),
+                    elapsed: Duration::from_millis(12),
+                    status: SourceStatus::Session {
+                        expires: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+                    },
+                    hint: None,
+                },
+            ],
+        };
+        assert_eq!(
+            serde_json::to_string(&report).unwrap(),
+            "{\"sources\":[{\"source\":\"environment variables\",\"available\":false,\
+             \"status\":\"unavailable\",\"expires\":null,\"error\":{\"code\":\"env_var_not_set\",\
+             \"message\":\"AOC_SESSION is not set\"},\"database\":null,\"elapsed_ms\":0,\
+             \"hint\":null},{\"source\":\"firefox cookie store\",\"available\":true,\
+             \"status\":\"session\",\"expires\":1700000000,\"error\":null,\
+             \"database\":\"/profile/cookies.sqlite\",\"elapsed_ms\":12,\"hint\":null}]}"
+        );
+    }
+}
@@ -0,0 +1,192 @@
+//! The session cookie value remembered on disk between runs, see
+//! [`AocSessionBuilder::persist`](crate::AocSessionBuilder::persist).
+
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use crate::fs::write_private;
+use crate::source::DOMAIN;
+use crate::{AocSession, Error, Result};
+
+/// A file holding the session cookie value along with when it was stored, sealed with the
+/// `encrypted-cache` feature:
+///
+/// ```text
+/// <stored at, in seconds since the Unix epoch>
+/// <cookie expiry, in seconds since the Unix epoch, or `-`>
+/// <session cookie value>
+/// ```
+#[derive(Debug, Clone)]
+pub(crate) struct SessionCache {
+    path: PathBuf,
+}
+
+impl SessionCache {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// The cache at `session` in the cache directory, e.g. `~/.cache/aoc-session/session`.
+    pub(crate) fn default_location() -> Option<Self> {
+        crate::dirs::cache_dir().map(|dir| Self::new(dir.join("session")))
+    }
+
+    /// The cache of the session cookie of the domain: the one at
+    /// [`default_location`](Self::default_location) for adventofcode.com, and `session-<domain>`
+    /// next to it for a mirror, so that their sessions aren't mixed up.
+    pub(crate) fn for_domain(domain: &str) -> Option<Self> {
+        if domain.eq_ignore_ascii_case(DOMAIN) {
+            return Self::default_location();
+        }
+        let name: String = domain
+            .chars()
+            .map(|c| match c {
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' => c.to_ascii_lowercase(),
+                _ => '_',
+            })
+            .collect();
+        crate::dirs::cache_dir().map(|dir| Self::new(dir.join(format!("session-{name}"))))
+    }
+
+    /// The remembered session, unless it was stored more than `ttl` ago or its cookie expired.
+    pub(crate) fn load(&self, ttl: Duration) -> Result<Option<AocSession>> {
+        let mut contents = match std::fs::read_to_string(&self.path) {
+            Ok(contents) => crate::sealed::unseal(&self.path, contents)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(source) => {
+                return Err(Error::Io {
+                    path: self.path.clone(),
+                    source,
+                })
+            }
+        };
+        let parsed = parse(&contents);
+        crate::secret::wipe(&mut contents);
+        // A malformed file is treated like a missing one; it gets overwritten on the next store.
+        let Some((stored, session)) = parsed else {
+            return Ok(None);
+        };
+        let age = SystemTime::now().duration_since(stored).unwrap_or_default();
+        if age > ttl || session.is_expired() {
+            return Ok(None);
+        }
+        Ok(Some(session))
+    }
+
+    pub(crate) fn store(&self, session: &AocSession) -> Result<()> {
+        let secs = |time: SystemTime| {
+            time.duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs()
+        };
+        let expires = session
+            .expires_at()
+            .map_or_else(|| "-".to_string(), |time| secs(time).to_string());
+        let mut contents = format!("{}\n{expires}\n", secs(SystemTime::now()));
+        contents.reserve_exact(session.value.len() + 1);
+        contents.push_str(&session.value);
+        contents.push('\n');
+        let mut contents = crate::sealed::seal_if_enabled(contents)?;
+        let written = write_private(&self.path, &contents);
+        crate::secret::wipe(&mut contents);
+        written
+    }
+
+    /// Remove the remembered session, if any.
+    pub(crate) fn clear(&self) -> Result<()> {
+        remove(&self.path)
+    }
+
+    /// Remove the remembered session if it's the given one, e.g. because it was rejected.
+    #[cfg(feature = "client")]
+    pub(crate) fn forget(&self, session: &AocSession) -> Result<()> {
+        let remembered = std::fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|contents| crate::sealed::unseal(&self.path, contents).ok())
+            .and_then(|mut contents| {
+                let remembered = parse(&contents);
+                crate::secret::wipe(&mut contents);
+                remembered
+            });
+        match remembered {
+            Some((_, remembered)) if remembered.value == session.value => remove(&self.path),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn remove(path: &std::path::Path) -> Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(Error::Io {
+            path: path.to_path_buf(),
+            source: e,
+        }),
+        _ => Ok(()),
+    }
+}
+
+fn parse(contents: &str) -> Option<(SystemTime, AocSession)> {
+    let time = |secs: &str| {
+        secs.parse()
+            .ok()
+            .map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+    };
+    let mut lines = contents.lines();
+    let stored = time(lines.next()?)?;
+    let expires = match lines.next()? {
+        "-" => None,
+        secs => Some(time(secs)?),
+    };
+    let value = lines.next()?.trim();
+    if value.is_empty() {
+        return None;
+    }
+    Some((
+        stored,
+        AocSession::unchecked(value.to_string()).with_expiry(expires),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mirrors_have_their_own_cache() {
+        let path = |domain| SessionCache::for_domain(domain).map(|cache| cache.path);
+        assert_eq!(
+            path("adventofcode.com"),
+            SessionCache::default_location().map(|cache| cache.path)
+        );
+        let mirror = path("AoC.example.edu").unwrap();
+        assert_eq!(mirror.file_name().unwrap(), "session-aoc.example.edu");
+        assert_ne!(Some(mirror), path("adventofcode.com"));
+        assert_eq!(path("../x").unwrap().file_name().unwrap(), "session-.._x");
+    }
+
+    #[test]
+    fn round_trip() {
+        let dir = std::env::temp_dir().join("aoc-session-session-cache-test");
+        let _ = std::fs::remove_dir_all(&dir);
+        let cache = SessionCache::new(dir.join("session"));
+        let hour = Duration::from_secs(3600);
+        assert!(cache.load(hour).unwrap().is_none());
+
+        cache.store(&AocSession::new("25a16c74")).unwrap();
+        assert_eq!(cache.load(hour).unwrap().unwrap().as_str(), "25a16c74");
+        std::thread::sleep(Duration::from_millis(1100));
+        assert!(cache.load(Duration::ZERO).unwrap().is_none());
+
+        #[cfg(feature = "client")]
+        {
+            cache.forget(&AocSession::new("ff")).unwrap();
+            assert!(cache.load(hour).unwrap().is_some());
+            cache.forget(&AocSession::new("25a16c74")).unwrap();
+            assert!(cache.load(hour).unwrap().is_none());
+        }
+
+        let expired = AocSession::new("25a16c74").with_expiry(Some(SystemTime::UNIX_EPOCH));
+        cache.store(&expired).unwrap();
+        assert!(cache.load(hour).unwrap().is_none());
+    }
+}
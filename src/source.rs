@@ -0,0 +1,566 @@
+use core::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::selection::in_browser_order;
+use crate::session_cache::SessionCache;
+use crate::trace;
+use crate::{
+    aoc_session_from_env, AocSession, Browser, BrowserSource, DiscoveredSession, Error,
+    FirefoxProfile, Result, SelectionStrategy, SessionSpec,
+};
+
+/// The name of the file that [`session_file_paths`] looks for in the current directory and the
+/// workspace root. Remember to add it to `.gitignore`.
+pub const SESSION_FILE_NAME: &str = ".aoc-session";
+
+/// Where Docker Compose and Swarm mount the secret named `aoc_session` in a container. Kubernetes
+/// mounts secrets wherever the pod says; point `AOC_SESSION_FILE` at them.
+pub const DOCKER_SECRET_PATH: &str = "/run/secrets/aoc_session";
+
+/// The domain of the session cookie.
+pub(crate) const DOMAIN: &str = "adventofcode.com";
+
+/// The browsers that the browser sources must not read.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Exclusions {
+    /// The browsers excluded by name.
+    pub(crate) browsers: Vec<Browser>,
+    /// Whether the browsers that may prompt for the password of the macOS Keychain are excluded.
+    pub(crate) keychain_prompt: bool,
+}
+
+impl Exclusions {
+    /// Fails if the browser is excluded.
+    pub(crate) fn check(&self, browser: Browser) -> Result<()> {
+        if self.browsers.contains(&browser) {
+            Err(Error::BrowserExcluded(browser))
+        } else if self.keychain_prompt && browser.prompts_for_keychain() {
+            Err(Error::KeychainPromptNotAllowed(browser))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// A place where the session cookie value can be looked up.
+///
+/// Sources are usually chained with [`AocSessionBuilder`](crate::AocSessionBuilder).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// The environment variables listed in [`ENV_VARS`](crate::ENV_VARS).
+    Env,
+    /// The `.env` files. See
+    /// [`aoc_session_from_dotenv`](crate::aoc_session_from_dotenv).
+    #[cfg(feature = "dotenv")]
+    DotEnv,
+    /// A file that contains nothing but the session cookie value.
+    File(PathBuf),
+    /// A cookie file in the Netscape `cookies.txt` format. See
+    /// [`aoc_session_from_cookies_txt`](crate::aoc_session_from_cookies_txt).
+    CookiesTxt(PathBuf),
+    /// A HAR file saved by the developer tools of a browser. See
+    /// [`aoc_session_from_har`](crate::aoc_session_from_har).
+    #[cfg(feature = "har")]
+    Har(PathBuf),
+    /// A cookie database written by the given browser, at a path that isn't found on its own. See
+    /// [`aoc_session_from_cookie_db`](crate::aoc_session_from_cookie_db).
+    CookieDb(PathBuf, Browser),
+    /// The first of the files listed by [`session_file_paths`] that exists.
+    SessionFiles,
+    /// The `token` setting of the configuration file (see [`Config`](crate::Config)).
+    Config,
+    /// The OS credential store. See [`aoc_session_from_keyring`](crate::aoc_session_from_keyring).
+    #[cfg(feature = "keyring")]
+    Keyring,
+    /// The cookie store of a single browser.
+    Browser(Browser),
+    /// The cookie store of a profile of Firefox.
+    FirefoxProfile(FirefoxProfile),
+    /// The cookie store of a profile of a browser, or of a browser with a custom data directory.
+    Profile(BrowserSource),
+    /// The agent started by `aoc-session agent`. See
+    /// [`aoc_session_from_agent`](crate::aoc_session_from_agent).
+    #[cfg(feature = "agent")]
+    Agent,
+    /// Another machine, reached with SSH. See
+    /// [`aoc_session_from_ssh`](crate::aoc_session_from_ssh).
+    #[cfg(feature = "remote")]
+    Ssh(String),
+    /// A running Chrome, asked through the Chrome DevTools Protocol on the given port. See
+    /// [`aoc_session_from_cdp`](crate::aoc_session_from_cdp). Excluding Chrome excludes it too.
+    #[cfg(feature = "cdp")]
+    Cdp(u16),
+    /// An item of a password manager. See
+    /// [`aoc_session_from_secret_manager`](crate::aoc_session_from_secret_manager).
+    #[cfg(feature = "secret-managers")]
+    SecretManager(crate::SecretManager),
+    /// The cookie stores of the browsers of Windows, when running in the Windows Subsystem for
+    /// Linux, except the excluded ones. See
+    /// [`aoc_session_from_wsl`](crate::aoc_session_from_wsl).
+    #[cfg(feature = "wsl")]
+    Wsl,
+    /// The cookie stores of all supported browsers. This source is slow. On a continuous
+    /// integration service, it fails at once with [`Error::InCi`](crate::Error::InCi) instead.
+    Browsers,
+    /// The session cookie value remembered on disk by
+    /// [`AocSessionBuilder::persist`](crate::AocSessionBuilder::persist), however old.
+    Cache,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Env => f.write_str("environment variables"),
+            #[cfg(feature = "dotenv")]
+            Source::DotEnv => f.write_str(".env files"),
+            Source::File(path) => write!(f, "file {}", path.display()),
+            Source::CookiesTxt(path) => write!(f, "cookies.txt file {}", path.display()),
+            #[cfg(feature = "har")]
+            Source::Har(path) => write!(f, "HAR file {}", path.display()),
+            Source::CookieDb(path, browser) => {
+                write!(f, "{browser} cookie database {}", path.display())
+            }
+            Source::SessionFiles => f.write_str("session files"),
+            Source::Config => f.write_str("configuration file"),
+            #[cfg(feature = "keyring")]
+            Source::Keyring => f.write_str("OS credential store"),
+            Source::Browser(browser) => write!(f, "{browser} cookie store"),
+            Source::FirefoxProfile(profile) => write!(f, "firefox profile {profile}"),
+            Source::Profile(source) => source.fmt(f),
+            #[cfg(feature = "agent")]
+            Source::Agent => f.write_str("aoc-session agent"),
+            #[cfg(feature = "remote")]
+            Source::Ssh(destination) => write!(f, "aoc-session on {destination}"),
+            #[cfg(feature = "cdp")]
+            Source::Cdp(port) => write!(f, "Chrome DevTools on port {port}"),
+            #[cfg(feature = "secret-managers")]
+            Source::SecretManager(item) => item.fmt(f),
+            #[cfg(feature = "wsl")]
+            Source::Wsl => f.write_str("Windows browser cookie stores"),
+            Source::Browsers => f.write_str("browser cookie stores"),
+            Source::Cache => f.write_str("session cache"),
+        }
+    }
+}
+
+impl Source {
+    /// Look the session cookie value up in this source. If several browsers hold one, the newest
+    /// is returned.
+    pub fn resolve(&self) -> Result<AocSession> {
+        self.resolve_with(SelectionStrategy::default())
+    }
+
+    /// Look the session cookie value up in this source, picking among several browsers with the
+    /// given strategy.
+    pub fn resolve_with(&self, strategy: SelectionStrategy) -> Result<AocSession> {
+        self.locate(
+            &SessionSpec::ADVENT_OF_CODE,
+            strategy,
+            &Exclusions::default(),
+            &mut Vec::new(),
+        )
+        .map(|(session, _)| session)
+    }
+
+    /// Like [`Source::resolve_with`], but also returns the most specific source the value came
+    /// from, e.g. the [`Source::Browser`] that [`Source::Browsers`] found it in. The browsers
+    /// that [`Source::Browsers`] failed to read while finding a value are added to `warnings`.
+    ///
+    /// The browser sources look for the cookie described by `spec`. The browsers in `excluded`
+    /// are skipped by [`Source::Browsers`] and fail elsewhere.
+    pub(crate) fn locate(
+        &self,
+        spec: &SessionSpec,
+        strategy: SelectionStrategy,
+        excluded: &Exclusions,
+        warnings: &mut Vec<SourceWarning>,
+    ) -> Result<(AocSession, Source)> {
+        trace::probe(self, || self.lookup(spec, strategy, excluded, warnings))
+    }
+
+    fn lookup(
+        &self,
+        spec: &SessionSpec,
+        strategy: SelectionStrategy,
+        excluded: &Exclusions,
+        warnings: &mut Vec<SourceWarning>,
+    ) -> Result<(AocSession, Source)> {
+        match self {
+            Source::CookieDb(_, browser) | Source::Browser(browser) => excluded.check(*browser)?,
+            Source::Profile(source) => excluded.check(source.browser())?,
+            Source::FirefoxProfile(_) => excluded.check(Browser::Firefox)?,
+            #[cfg(feature = "cdp")]
+            Source::Cdp(_) => excluded.check(Browser::Chrome)?,
+            _ => {}
+        }
+        let session = match self {
+            Source::Env => aoc_session_from_env(),
+            #[cfg(feature = "dotenv")]
+            Source::DotEnv => crate::aoc_session_from_dotenv(),
+            Source::File(path) => from_file(path),
+            Source::CookiesTxt(path) => crate::aoc_session_from_cookies_txt(path),
+            #[cfg(feature = "har")]
+            Source::Har(path) => crate::aoc_session_from_har(path),
+            Source::CookieDb(path, browser) => crate::aoc_session_from_cookie_db(path, *browser),
+            Source::SessionFiles => {
+                return from_session_files().map(|(session, path)| (session, Source::File(path)))
+            }
+            Source::Config => crate::Config::load()?
+                .token
+                .map(AocSession::unchecked)
+                .ok_or(Error::NoSessionCookieFound),
+            #[cfg(feature = "keyring")]
+            Source::Keyring => crate::aoc_session_from_keyring(),
+            Source::Browser(browser) => from_browser(*browser, spec),
+            Source::FirefoxProfile(profile) => crate::aoc_session_from_firefox_profile(profile),
+            Source::Profile(source) => source.resolve(),
+            #[cfg(feature = "agent")]
+            Source::Agent => crate::aoc_session_from_agent(),
+            #[cfg(feature = "remote")]
+            Source::Ssh(destination) => crate::aoc_session_from_ssh(destination),
+            #[cfg(feature = "cdp")]
+            Source::Cdp(port) => crate::cdp::from_cdp(*port, spec),
+            #[cfg(feature = "secret-managers")]
+            Source::SecretManager(item) => crate::aoc_session_from_secret_manager(item),
+            #[cfg(feature = "wsl")]
+            Source::Wsl => crate::wsl::from_windows_browsers(excluded),
+            Source::Browsers => {
+                let (found, failures) = from_browsers(spec, strategy, excluded)?;
+                warnings.extend(failures.into_iter().map(|error| SourceWarning {
+                    source: source_of(&error),
+                    error,
+                }));
+                return Ok((found.session, Source::Browser(found.browser)));
+            }
+            Source::Cache => SessionCache::for_domain(spec.domain())
+                .ok_or(Error::NoSessionCookieFound)?
+                .load(Duration::MAX)?
+                .ok_or(Error::NoSessionCookieFound),
+        };
+        Ok((session?, self.clone()))
+    }
+
+    /// Whether the source is slow or may prompt the user, e.g. a browser scan or the OS
+    /// credential store, as opposed to the explicit ones, e.g. environment variables and files,
+    /// which the cache of [`AocSessionBuilder::persist`](crate::AocSessionBuilder::persist) may
+    /// not stand in for.
+    pub(crate) fn is_slow(&self) -> bool {
+        match self {
+            Source::Env
+            | Source::File(_)
+            | Source::CookiesTxt(_)
+            | Source::SessionFiles
+            | Source::Config
+            | Source::Cache => false,
+            #[cfg(feature = "dotenv")]
+            Source::DotEnv => false,
+            #[cfg(feature = "har")]
+            Source::Har(_) => false,
+            #[cfg(feature = "agent")]
+            Source::Agent => false,
+            _ => true,
+        }
+    }
+}
+
+pub(crate) fn from_file(path: &Path) -> Result<AocSession> {
+    let contents = std::fs::read_to_string(path).map_err(|source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let contents = crate::sealed::unseal(path, contents)?;
+    let session = AocSession::unchecked(contents);
+    if session.value.is_empty() {
+        return Err(Error::NoSessionCookieFound);
+    }
+    Ok(session)
+}
+
+/// The standard locations of a file holding the session cookie value, in the order they are
+/// consulted:
+///
+/// 1. [`.aoc-session`](SESSION_FILE_NAME) in the current directory;
+/// 2. `.aoc-session` in the workspace root, i.e. the closest ancestor of the current directory
+///    that is a git repository or has a `Cargo.toml` declaring a `[workspace]`;
+/// 3. `token` in the configuration directory, e.g. `~/.config/aoc-session/token` on Linux and
+///    macOS, or `%APPDATA%\aoc-session\token` on Windows;
+/// 4. the Docker secret at [`DOCKER_SECRET_PATH`], on Unix.
+pub fn session_file_paths() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    if let Ok(cwd) = std::env::current_dir() {
+        paths.push(cwd.join(SESSION_FILE_NAME));
+        if let Some(root) = workspace_root(&cwd).filter(|root| *root != cwd) {
+            paths.push(root.join(SESSION_FILE_NAME));
+        }
+    }
+    paths.extend(token_file_path());
+    if cfg!(unix) {
+        paths.push(PathBuf::from(DOCKER_SECRET_PATH));
+    }
+    paths
+}
+
+/// The location of the `token` file in the configuration directory, e.g.
+/// `~/.config/aoc-session/token` on Linux and macOS, where
+/// [`AocSession::store_in_token_file`] puts the session cookie value.
+pub fn token_file_path() -> Option<PathBuf> {
+    crate::dirs::config_dir().map(|dir| dir.join("token"))
+}
+
+impl AocSession {
+    /// Store the session cookie value in the file at [`token_file_path`], replacing any stored
+    /// value, and return the path of the file.
+    ///
+    /// The file is only readable by the current user on Unix, and the value is encrypted with the
+    /// `encrypted-cache` feature. Afterwards,
+    /// [`aoc_session`](crate::aoc_session) finds the value there without scanning the browsers.
+    pub fn store_in_token_file(&self) -> Result<PathBuf> {
+        let path = token_file_path().ok_or(Error::NoConfigDir)?;
+        let mut contents = String::with_capacity(self.value.len() + 1);
+        contents.push_str(&self.value);
+        contents.push('\n');
+        let mut contents = crate::sealed::seal_if_enabled(contents)?;
+        let written = crate::fs::write_private(&path, &contents);
+        crate::secret::wipe(&mut contents);
+        written.map(|()| path)
+    }
+}
+
+/// Remove the file at [`token_file_path`], if it exists.
+pub fn delete_token_file() -> Result<()> {
+    let Some(path) = token_file_path() else {
+        return Ok(());
+    };
+    match std::fs::remove_file(&path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(Error::Io { path, source: e }),
+        _ => Ok(()),
+    }
+}
+
+pub(crate) fn workspace_root(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .find(|dir| {
+            dir.join(".git").exists()
+                || std::fs::read_to_string(dir.join("Cargo.toml"))
+                    .is_ok_and(|manifest| manifest.contains("[workspace]"))
+        })
+        .map(Path::to_path_buf)
+}
+
+fn from_session_files() -> Result<(AocSession, PathBuf)> {
+    for path in session_file_paths() {
+        match from_file(&path) {
+            Err(e) if e.is_absence() => continue,
+            result => return result.map(|session| (session, path)),
+        }
+    }
+    Err(Error::NoSessionCookieFound)
+}
+
+pub(crate) fn from_browser(browser: Browser, spec: &SessionSpec) -> Result<AocSession> {
+    browser
+        .sessions_for(spec)?
+        .into_iter()
+        .next()
+        .ok_or(Error::NoSessionCookieFound)
+}
+
+/// A source that failed while the session cookie value was found in another one, e.g. a browser
+/// whose cookie database was locked while another browser held the session cookie.
+///
+/// See [`AocSessionBuilder::resolve_with_warnings`](crate::AocSessionBuilder::resolve_with_warnings).
+#[derive(Debug)]
+pub struct SourceWarning {
+    /// The failed source, as precisely as known, e.g. a [`Source::Browser`].
+    pub source: Source,
+    pub error: Error,
+}
+
+impl fmt::Display for SourceWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.source, self.error)
+    }
+}
+
+/// The source that an error of a browser comes from.
+fn source_of(error: &Error) -> Source {
+    match error {
+        Error::CookieStoreNotFound { browser, .. }
+        | Error::PermissionDenied { browser, .. }
+        | Error::LockedDatabase { browser, .. }
+        | Error::DecryptionFailed { browser, .. }
+        | Error::CookieStoreUnreadable { browser, .. }
+        | Error::UnsupportedBrowser(browser) => Source::Browser(*browser),
+        _ => Source::Browsers,
+    }
+}
+
+/// Pick a session among the browsers, along with the failures of the browsers whose cookie
+/// stores exist but couldn't be read.
+///
+/// If no browser holds a session, [`Error::BrowsersFailed`] lists those failures, if any.
+pub(crate) fn from_browsers(
+    spec: &SessionSpec,
+    strategy: SelectionStrategy,
+    excluded: &Exclusions,
+) -> Result<(DiscoveredSession, Vec<Error>)> {
+    refuse_in_ci()?;
+    let mut failures = Vec::new();
+    let probes = probe_in_parallel(spec, excluded);
+    let found = strategy.select_as_found(successes(probes, &mut failures));
+    match found {
+        Some(found) => Ok((found, failures)),
+        None => Err(no_session(failures)),
+    }
+}
+
+pub(crate) fn discover(spec: &SessionSpec) -> Result<Vec<DiscoveredSession>> {
+    refuse_in_ci()?;
+    let mut failures = Vec::new();
+    let sessions: Vec<_> = successes(
+        probe_in_parallel(spec, &Exclusions::default()),
+        &mut failures,
+    )
+    .flatten()
+    .collect();
+    if sessions.is_empty() {
+        return Err(no_session(failures));
+    }
+    Ok(in_browser_order(sessions))
+}
+
+/// The batches of sessions of the browsers that could be read, setting the failures of the others
+/// aside. Browsers that aren't installed don't count as failures.
+fn successes<'a>(
+    probes: impl IntoIterator<Item = Result<Vec<DiscoveredSession>>> + 'a,
+    failures: &'a mut Vec<Error>,
+) -> impl Iterator<Item = Vec<DiscoveredSession>> + 'a {
+    probes.into_iter().filter_map(|probe| match probe {
+        Ok(batch) => Some(batch),
+        Err(Error::CookieStoreNotFound { .. } | Error::UnsupportedBrowser(_)) => None,
+        Err(e) => {
+            failures.push(e);
+            None
+        }
+    })
+}
+
+/// Fail at once on a continuous integration service, where scanning all the browsers would only
+/// find none logged in, slowly. A single browser named on purpose is still read.
+fn refuse_in_ci() -> Result<()> {
+    match crate::ci::current() {
+        Some(ci) => Err(Error::InCi {
+            ci: ci.name,
+            hint: ci.hint,
+        }),
+        None => Ok(()),
+    }
+}
+
+fn no_session(failures: Vec<Error>) -> Error {
+    if failures.is_empty() {
+        Error::NoSessionCookieFound
+    } else {
+        Error::BrowsersFailed(failures)
+    }
+}
+
+/// Probe the cookie store of every supported browser that isn't excluded on its own thread. The
+/// sessions found in each browser arrive as soon as that browser is done.
+///
+/// Dropping the receiver abandons the probes that are still running; their threads finish on
+/// their own.
+fn probe_in_parallel(
+    spec: &SessionSpec,
+    excluded: &Exclusions,
+) -> mpsc::Receiver<Result<Vec<DiscoveredSession>>> {
+    let (sender, receiver) = mpsc::channel();
+    let parent = trace::Parent::current();
+    let browsers = Browser::ALL
+        .into_iter()
+        .filter(|&b| b.is_supported() && excluded.check(b).is_ok());
+    for browser in browsers {
+        let sender = sender.clone();
+        let parent = parent.clone();
+        let spec = spec.clone();
+        std::thread::spawn(move || {
+            let sessions = parent.run(|| trace::probe(&browser, || probe(browser, &spec)));
+            // The receiver may be gone if another browser already yielded a session.
+            let _ = sender.send(sessions);
+        });
+    }
+    receiver
+}
+
+/// The sessions in every profile of the browser. The browser only fails if none of its cookie
+/// stores can be read.
+fn probe(browser: Browser, spec: &SessionSpec) -> Result<Vec<DiscoveredSession>> {
+    let mut found = Vec::new();
+    let mut error = None;
+    for store in browser.cookie_stores()? {
+        match store.sessions_for(spec) {
+            Ok(sessions) => found.extend(sessions.into_iter().map(|session| DiscoveredSession {
+                browser,
+                profile: store.profile(),
+                expires: session.expires_at(),
+                session,
+            })),
+            Err(e) => error = error.or(Some(e)),
+        }
+    }
+    match error {
+        Some(e) if found.is_empty() => Err(e),
+        _ => Ok(found),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn workspace_root_is_found() {
+        let root = std::env::temp_dir().join("aoc-session-workspace-test");
+        let member = root.join("crates").join("day01");
+        std::fs::create_dir_all(&member).unwrap();
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\n",
+        )
+        .unwrap();
+        std::fs::write(member.join("Cargo.toml"), "[package]\nname = \"day01\"\n").unwrap();
+        assert_eq!(workspace_root(&member), Some(root));
+    }
+
+    #[test]
+    fn absent_browsers_are_not_failures() {
+        let locked = || Error::LockedDatabase {
+            browser: Browser::Chrome,
+            path: PathBuf::from("/profile/Cookies"),
+        };
+        let probes = vec![
+            Err(Error::UnsupportedBrowser(Browser::Safari)),
+            Err(Error::CookieStoreNotFound {
+                browser: Browser::Firefox,
+                reason: "no profile".to_string(),
+            }),
+            Err(locked()),
+            Ok(Vec::new()),
+        ];
+        let mut failures = Vec::new();
+        assert_eq!(successes(probes, &mut failures).count(), 1);
+        assert_eq!(failures.len(), 1);
+        let error = no_session(failures);
+        assert!(matches!(&error, Error::BrowsersFailed(errors) if errors.len() == 1));
+        assert_eq!(
+            error.to_string(),
+            format!("No session cookie found; {}", locked())
+        );
+        assert!(matches!(
+            no_session(Vec::new()),
+            Error::NoSessionCookieFound
+        ));
+    }
+}
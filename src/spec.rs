@@ -0,0 +1,113 @@
+//! Looking up the session cookie of another site than Advent of Code, e.g. everybody.codes, whose
+//! cookies live in the same browser stores.
+
+use std::borrow::Cow;
+
+use crate::source::{self, DOMAIN};
+use crate::{AocSession, Result, SelectionStrategy};
+
+/// The cookie that holds the session of a site: its domain and name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SessionSpec {
+    domain: Cow<'static, str>,
+    cookie_name: Cow<'static, str>,
+}
+
+impl SessionSpec {
+    /// The `session` cookie of adventofcode.com, which [`aoc_session`](crate::aoc_session) looks
+    /// up.
+    pub const ADVENT_OF_CODE: SessionSpec = SessionSpec {
+        domain: Cow::Borrowed(DOMAIN),
+        cookie_name: Cow::Borrowed("session"),
+    };
+
+    /// The cookie with the given name set for the given domain, e.g. `everybody.codes`. A cookie
+    /// set for a subdomain, e.g. `www.everybody.codes`, matches too.
+    pub fn new(
+        domain: impl Into<Cow<'static, str>>,
+        cookie_name: impl Into<Cow<'static, str>>,
+    ) -> SessionSpec {
+        SessionSpec {
+            domain: domain.into(),
+            cookie_name: cookie_name.into(),
+        }
+    }
+
+    /// The domain of the cookie, e.g. `adventofcode.com`.
+    pub fn domain(&self) -> &str {
+        &self.domain
+    }
+
+    /// The name of the cookie, e.g. `session`.
+    pub fn cookie_name(&self) -> &str {
+        &self.cookie_name
+    }
+
+    /// Look the cookie up in the supported browsers, picking the newest one if several hold it.
+    ///
+    /// The value isn't checked against the format of Advent of Code, since other sites use
+    /// other formats.
+    pub fn resolve(&self) -> Result<AocSession> {
+        self.resolve_with(SelectionStrategy::default())
+    }
+
+    /// Look the cookie up in the supported browsers, picking one with the given strategy if
+    /// several hold it.
+    pub fn resolve_with(&self, strategy: SelectionStrategy) -> Result<AocSession> {
+        source::from_browsers(self, strategy, &source::Exclusions::default())
+            .map(|(found, _)| found.session)
+    }
+
+    /// Whether a cookie set for the host, as stored by a browser, e.g. `.adventofcode.com`,
+    /// belongs to the domain.
+    #[cfg_attr(
+        any(not(feature = "browser"), target_family = "wasm"),
+        allow(dead_code)
+    )]
+    pub(crate) fn matches_host(&self, host: &str) -> bool {
+        let host = host.trim_start_matches('.');
+        host.eq_ignore_ascii_case(&self.domain)
+            || host
+                .len()
+                .checked_sub(self.domain.len() + 1)
+                .is_some_and(|dot| {
+                    host.as_bytes()[dot] == b'.'
+                        && host[dot + 1..].eq_ignore_ascii_case(&self.domain)
+                })
+    }
+}
+
+/// Get the value of the cookie with the given name for the given domain from the supported
+/// browsers, for puzzle sites other than Advent of Code. See [`SessionSpec::resolve`].
+///
+/// [`aoc_session`](crate::aoc_session) is the same for adventofcode.com, except that it also
+/// consults the environment variables, files, and other sources of its chain.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoc_session::session_for;
+///
+/// let session = session_for("everybody.codes", "everybody-codes").unwrap();
+/// println!("My everybody.codes session: {}", session.expose_secret());
+/// ```
+pub fn session_for(domain: &str, cookie_name: &str) -> Result<AocSession> {
+    SessionSpec::new(domain.to_string(), cookie_name.to_string()).resolve()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hosts_are_matched_by_domain() {
+        let spec = SessionSpec::new("everybody.codes", "everybody-codes");
+        assert!(spec.matches_host("everybody.codes"));
+        assert!(spec.matches_host(".everybody.codes"));
+        assert!(spec.matches_host("www.Everybody.Codes"));
+        assert!(!spec.matches_host("noteverybody.codes"));
+        assert!(!spec.matches_host("everybody.codes.example"));
+        assert!(!spec.matches_host("codes"));
+        assert!(SessionSpec::ADVENT_OF_CODE.matches_host(".adventofcode.com"));
+    }
+}
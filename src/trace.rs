@@ -0,0 +1,108 @@
+//! The `tracing` spans and events around the lookups of the session cookie value, with the
+//! `tracing` feature. Without it, the functions here just run the lookups.
+
+use core::fmt::Display;
+
+use crate::Result;
+
+/// Run the lookup in a `probe` span named after the source, e.g. `source=firefox`, and record its
+/// outcome and duration as an event: `INFO` if a value was found, `DEBUG` if the source holds none,
+/// and `WARN` if the source is broken.
+#[cfg(feature = "tracing")]
+pub(crate) fn probe<T>(source: &dyn Display, lookup: impl FnOnce() -> Result<T>) -> Result<T> {
+    let span = tracing::debug_span!("probe", source = %source);
+    let _entered = span.enter();
+    let start = std::time::Instant::now();
+    let result = lookup();
+    let elapsed_ms = start.elapsed().as_millis() as u64;
+    match &result {
+        Ok(_) => tracing::info!(elapsed_ms, "found a session cookie value"),
+        Err(e) if e.is_absence() || is_not_installed(e) => {
+            tracing::debug!(elapsed_ms, reason = %e, "no session cookie value")
+        }
+        Err(e) => tracing::warn!(elapsed_ms, code = e.code(), error = %e, "lookup failed"),
+    }
+    result
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn probe<T>(_source: &dyn Display, lookup: impl FnOnce() -> Result<T>) -> Result<T> {
+    lookup()
+}
+
+#[cfg(feature = "tracing")]
+fn is_not_installed(error: &crate::Error) -> bool {
+    matches!(
+        error,
+        crate::Error::CookieStoreNotFound { .. } | crate::Error::UnsupportedBrowser(_)
+    )
+}
+
+/// The span that the lookups started on other threads belong to.
+#[derive(Clone)]
+pub(crate) struct Parent {
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+}
+
+impl Parent {
+    pub(crate) fn current() -> Self {
+        Parent {
+            #[cfg(feature = "tracing")]
+            span: tracing::Span::current(),
+        }
+    }
+
+    /// Run `f` in the span, e.g. on a spawned thread.
+    pub(crate) fn run<T>(&self, f: impl FnOnce() -> T) -> T {
+        #[cfg(feature = "tracing")]
+        let _entered = self.span.enter();
+        f()
+    }
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Level, Metadata};
+
+    use super::*;
+    use crate::Error;
+
+    /// Records the levels of the events.
+    #[derive(Default)]
+    struct Levels(Arc<Mutex<Vec<Level>>>);
+
+    impl tracing::Subscriber for Levels {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, _: &Attributes<'_>) -> Id {
+            Id::from_u64(1)
+        }
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+        fn event(&self, event: &Event<'_>) {
+            self.0.lock().unwrap().push(*event.metadata().level());
+        }
+        fn enter(&self, _: &Id) {}
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn outcomes_are_recorded() {
+        let subscriber = Levels::default();
+        let levels = Arc::clone(&subscriber.0);
+        tracing::subscriber::with_default(subscriber, || {
+            let _ = probe(&"env", || Ok(()));
+            let _ = probe(&"env", || Err::<(), _>(Error::EnvVarNotSet));
+            let _ = probe(&"config", || Err::<(), _>(Error::NoConfigDir));
+        });
+        assert_eq!(
+            *levels.lock().unwrap(),
+            [Level::INFO, Level::DEBUG, Level::WARN]
+        );
+    }
+}
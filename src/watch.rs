@@ -0,0 +1,191 @@
+//! Watching the browser cookie stores for a new session cookie value, e.g. after logging in
+//! again, so that long-running programs pick it up without restarting.
+
+use std::path::PathBuf;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime};
+
+use crate::source::Exclusions;
+use crate::{AocSession, Browser, Config, Result};
+
+/// How often the modification times of the cookie stores are checked.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The modification times of the files a lookup reads. The lookup is repeated when they change.
+type Fingerprint = Vec<(PathBuf, Option<SystemTime>)>;
+
+/// Call `callback` with the session cookie value held by the browsers, and again whenever it
+/// changes, until the returned [`SessionWatcher`] is stopped or dropped.
+///
+/// The cookie databases are checked for changes every two seconds, which is cheap, and only
+/// scanned again when they changed. A browser whose cookie store can't be read at that moment is
+/// scanned again at the next check. The browser preferred by the [`Config`] file wins when
+/// several browsers hold a session cookie, and the browsers it excludes, or that may prompt for
+/// the macOS Keychain password when it forbids that, are never read.
+///
+/// The callback runs on a thread of the watcher.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::sync::mpsc;
+///
+/// use aoc_session::watch_session;
+///
+/// let (sender, receiver) = mpsc::channel();
+/// let _watcher = watch_session(move |session| {
+///     let _ = sender.send(session);
+/// });
+/// for session in receiver {
+///     println!("New session ID: {session}");
+/// }
+/// ```
+pub fn watch_session(callback: impl FnMut(AocSession) + Send + 'static) -> SessionWatcher {
+    let builder = Config::load()
+        .unwrap_or_default()
+        .session_builder()
+        .browsers_only();
+    let excluded = builder.exclusions().clone();
+    SessionWatcher::spawn(
+        POLL_INTERVAL,
+        move || cookie_store_times(&excluded),
+        move || builder.resolve(),
+        callback,
+    )
+}
+
+/// The thread started by [`watch_session`]. Dropping it stops the watch.
+#[derive(Debug)]
+pub struct SessionWatcher {
+    stopped: Arc<(Mutex<bool>, Condvar)>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl SessionWatcher {
+    fn spawn(
+        interval: Duration,
+        fingerprint: impl Fn() -> Fingerprint + Send + 'static,
+        lookup: impl Fn() -> Result<AocSession> + Send + 'static,
+        mut callback: impl FnMut(AocSession) + Send + 'static,
+    ) -> Self {
+        let stopped = Arc::new((Mutex::new(false), Condvar::new()));
+        let thread = {
+            let stopped = Arc::clone(&stopped);
+            std::thread::spawn(move || {
+                let mut seen = None;
+                let mut last: Option<AocSession> = None;
+                loop {
+                    let current = fingerprint();
+                    if seen.as_ref() != Some(&current) {
+                        // A failed lookup leaves `seen` as it was, so it's retried next time.
+                        if let Ok(session) = lookup() {
+                            seen = Some(current);
+                            let changed = last.as_ref().map(AocSession::expose_secret)
+                                != Some(session.expose_secret());
+                            if changed {
+                                last = Some(session.clone());
+                                callback(session);
+                            }
+                        }
+                    }
+                    let (lock, condvar) = &*stopped;
+                    let guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+                    let (guard, _) = condvar
+                        .wait_timeout_while(guard, interval, |stopped| !*stopped)
+                        .unwrap_or_else(|e| e.into_inner());
+                    if *guard {
+                        return;
+                    }
+                }
+            })
+        };
+        SessionWatcher {
+            stopped,
+            thread: Some(thread),
+        }
+    }
+
+    /// Stop watching, and wait for a scan in progress to finish.
+    pub fn stop(mut self) {
+        self.stop_and_join();
+    }
+
+    fn stop_and_join(&mut self) {
+        let (lock, condvar) = &*self.stopped;
+        *lock.lock().unwrap_or_else(|e| e.into_inner()) = true;
+        condvar.notify_all();
+        if let Some(thread) = self.thread.take() {
+            // A panic of the callback has already been reported on the thread.
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for SessionWatcher {
+    fn drop(&mut self) {
+        self.stop_and_join();
+    }
+}
+
+/// The modification times of the cookie databases of every browser that isn't excluded,
+/// including their write-ahead logs, where new cookies land first.
+fn cookie_store_times(excluded: &Exclusions) -> Fingerprint {
+    let mut times = Vec::new();
+    let browsers = Browser::ALL
+        .into_iter()
+        .filter(|&b| b.is_supported() && excluded.check(b).is_ok());
+    for browser in browsers {
+        for store in browser.cookie_stores().unwrap_or_default() {
+            let mut wal = store.db_path.clone().into_os_string();
+            wal.push("-wal");
+            for path in [store.db_path, PathBuf::from(wal)] {
+                let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+                times.push((path, modified));
+            }
+        }
+    }
+    times
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::mpsc;
+
+    use super::*;
+    use crate::Error;
+
+    #[test]
+    fn only_new_values_are_reported() {
+        static CHECKS: AtomicU32 = AtomicU32::new(0);
+        // The stores change on every check, but the value only changes on the fourth lookup, and
+        // the second one fails.
+        let fingerprint = || {
+            let check = CHECKS.fetch_add(1, Ordering::SeqCst);
+            vec![(
+                PathBuf::from("cookies.sqlite"),
+                Some(SystemTime::UNIX_EPOCH + Duration::from_secs(check.into())),
+            )]
+        };
+        let lookups = AtomicU32::new(0);
+        let lookup = move || match lookups.fetch_add(1, Ordering::SeqCst) {
+            0 | 2 => Ok(AocSession::unchecked("25a16c74".to_string())),
+            1 => Err(Error::NoSessionCookieFound),
+            _ => Ok(AocSession::unchecked("53616c74".to_string())),
+        };
+        let (sender, receiver) = mpsc::channel();
+        let watcher = SessionWatcher::spawn(
+            Duration::from_millis(1),
+            fingerprint,
+            lookup,
+            move |session| {
+                let _ = sender.send(session.expose_secret().to_string());
+            },
+        );
+        assert_eq!(receiver.recv().unwrap(), "25a16c74");
+        assert_eq!(receiver.recv().unwrap(), "53616c74");
+        watcher.stop();
+        assert!(receiver.recv().is_err());
+    }
+}
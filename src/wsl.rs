@@ -0,0 +1,354 @@
+//! The cookie stores of the browsers of Windows, read from the Windows Subsystem for Linux.
+//!
+//! The profiles of Firefox are read as on any other system. The cookies of the browsers based on
+//! Chromium are encrypted with a key that only Windows can decrypt, through its Data Protection
+//! API, so PowerShell is asked to do it through the interoperability of WSL.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+
+use crate::browser::CookieStore;
+use crate::source::{Exclusions, DOMAIN};
+use crate::{AocSession, Browser, Error, Result};
+
+/// The browsers looked up by [`Source::Wsl`](crate::Source::Wsl), in order.
+const BROWSERS: [Browser; 6] = [
+    Browser::Firefox,
+    Browser::Chrome,
+    Browser::Edge,
+    Browser::Brave,
+    Browser::Vivaldi,
+    Browser::Chromium,
+];
+
+/// Seconds between 1601-01-01, the epoch of the timestamps of Chromium, and 1970-01-01.
+const CHROMIUM_EPOCH_OFFSET: u64 = 11_644_473_600;
+
+/// Whether this program runs in the Windows Subsystem for Linux.
+pub fn is_wsl() -> bool {
+    cfg!(target_os = "linux")
+        && (std::env::var_os("WSL_DISTRO_NAME").is_some()
+            || std::fs::read_to_string("/proc/sys/kernel/osrelease")
+                .is_ok_and(|release| release.to_lowercase().contains("microsoft")))
+}
+
+/// Get the session cookie for Advent of Code from a browser of Windows, when running in the
+/// Windows Subsystem for Linux.
+///
+/// The profiles are looked up under `/mnt/c/Users/<you>`. Firefox is read directly. The browsers
+/// based on Chromium need `powershell.exe` to decrypt the key of their cookies, and recent
+/// versions of Chrome and Edge encrypt cookies in a way that only they can undo, which is
+/// reported as [`Error::AppBoundEncryption`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoc_session::{aoc_session_from_wsl, Browser};
+///
+/// let session = aoc_session_from_wsl(Browser::Firefox).unwrap();
+/// println!("My session ID: {session}");
+/// ```
+pub fn aoc_session_from_wsl(browser: Browser) -> Result<AocSession> {
+    if !is_wsl() {
+        return Err(Error::NotWsl);
+    }
+    let home = windows_home()?;
+    let dir = windows_data_dir(browser)
+        .map(|dir| home.join(dir))
+        .ok_or(Error::UnsupportedBrowser(browser))?;
+    let profiles = browser.profiles_in(&dir);
+    let profile = profiles
+        .iter()
+        .find(|profile| profile.is_default)
+        .or(profiles.first())
+        .ok_or_else(|| Error::CookieStoreNotFound {
+            browser,
+            reason: format!("{} holds no profile", dir.display()),
+        })?;
+    let store = CookieStore::in_profile(browser, &profile.path)?;
+    if browser.is_gecko() {
+        return store
+            .sessions()?
+            .into_iter()
+            .next()
+            .ok_or(Error::NoSessionCookieFound);
+    }
+    let key = chromium_key(browser, &dir.join("Local State"))?;
+    chromium_session(browser, &store.db_path, &key)
+}
+
+/// The first session found in a browser of Windows, trying Firefox first since its cookies can
+/// always be read.
+pub(crate) fn from_windows_browsers(excluded: &Exclusions) -> Result<AocSession> {
+    if !is_wsl() {
+        return Err(Error::NoSessionCookieFound);
+    }
+    let mut error = None;
+    for browser in BROWSERS
+        .into_iter()
+        .filter(|&browser| excluded.check(browser).is_ok())
+    {
+        match aoc_session_from_wsl(browser) {
+            Ok(session) => return Ok(session),
+            Err(Error::CookieStoreNotFound { .. } | Error::NoSessionCookieFound) => {}
+            Err(e) => error = error.or(Some(e)),
+        }
+    }
+    Err(error.unwrap_or(Error::NoSessionCookieFound))
+}
+
+/// Where the browser keeps its profiles on Windows, relative to the home directory of the user.
+fn windows_data_dir(browser: Browser) -> Option<&'static str> {
+    Some(match browser {
+        Browser::Firefox => "AppData/Roaming/Mozilla/Firefox",
+        Browser::LibreWolf => "AppData/Roaming/librewolf",
+        Browser::Chrome => "AppData/Local/Google/Chrome/User Data",
+        Browser::Chromium => "AppData/Local/Chromium/User Data",
+        Browser::Edge => "AppData/Local/Microsoft/Edge/User Data",
+        Browser::Brave => "AppData/Local/BraveSoftware/Brave-Browser/User Data",
+        Browser::Opera => "AppData/Roaming/Opera Software/Opera Stable",
+        Browser::Vivaldi => "AppData/Local/Vivaldi/User Data",
+        Browser::Safari | Browser::Arc => return None,
+    })
+}
+
+/// The home directory of the Windows user, as seen from WSL, e.g. `/mnt/c/Users/me`.
+fn windows_home() -> Result<PathBuf> {
+    let output = Command::new("cmd.exe")
+        .args(["/d", "/c", "echo %USERPROFILE%"])
+        .current_dir("/")
+        .output();
+    if let Ok(output) = output {
+        let profile = String::from_utf8_lossy(&output.stdout);
+        if let Some(path) = wsl_path(profile.trim()).filter(|path| path.is_dir()) {
+            return Ok(path);
+        }
+    }
+    // Without interoperability, guess the only user with a profile.
+    let users = Path::new("/mnt/c/Users");
+    let candidates: Vec<_> = std::fs::read_dir(users)
+        .map_err(|source| Error::Io {
+            path: users.to_path_buf(),
+            source,
+        })?
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| path.join("AppData").is_dir())
+        .collect();
+    match <[PathBuf; 1]>::try_from(candidates) {
+        Ok([home]) => Ok(home),
+        Err(_) => Err(Error::CookieStoreNotFound {
+            browser: Browser::Firefox,
+            reason: "can't tell which user of /mnt/c/Users is you; is `cmd.exe` reachable?"
+                .to_string(),
+        }),
+    }
+}
+
+/// The path under `/mnt` of a Windows path such as `C:\Users\me`.
+fn wsl_path(windows: &str) -> Option<PathBuf> {
+    let (drive, rest) = windows.split_once(":\\")?;
+    let drive = drive.chars().next().filter(|_| drive.len() == 1)?;
+    let rest = rest.replace('\\', "/");
+    Some(PathBuf::from(format!(
+        "/mnt/{}/{rest}",
+        drive.to_ascii_lowercase()
+    )))
+}
+
+/// The key of the cookies of a browser based on Chromium, decrypted by Windows.
+fn chromium_key(browser: Browser, local_state: &Path) -> Result<Vec<u8>> {
+    let contents = std::fs::read_to_string(local_state).map_err(|source| Error::Io {
+        path: local_state.to_path_buf(),
+        source,
+    })?;
+    let decryption_failed = |reason: &str| Error::DecryptionFailed {
+        browser,
+        reason: reason.to_string(),
+    };
+    let state: serde_json::Value =
+        serde_json::from_str(&contents).map_err(|e| decryption_failed(&e.to_string()))?;
+    let encrypted_key = state["os_crypt"]["encrypted_key"]
+        .as_str()
+        .filter(|key| {
+            key.chars()
+                .all(|c| c.is_ascii_alphanumeric() || "+/=".contains(c))
+        })
+        .ok_or_else(|| decryption_failed("`Local State` holds no key"))?;
+    // The key is prefixed with `DPAPI`, which is skipped before handing it to Windows.
+    let script = format!(
+        "Add-Type -AssemblyName System.Security; \
+         $key = [Convert]::FromBase64String('{encrypted_key}'); \
+         $key = [Security.Cryptography.ProtectedData]::Unprotect(\
+         $key[5..($key.Length - 1)], $null, 'CurrentUser'); \
+         [Convert]::ToBase64String($key)"
+    );
+    let output = Command::new("powershell.exe")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .current_dir("/")
+        .output()
+        .map_err(|e| decryption_failed(&format!("can't run powershell.exe: {e}")))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(decryption_failed(stderr.trim()));
+    }
+    base64_decode(String::from_utf8_lossy(&output.stdout).trim())
+        .filter(|key| key.len() == 32)
+        .ok_or_else(|| decryption_failed("Windows returned a malformed key"))
+}
+
+/// The session cookie in the `Cookies` database of a browser based on Chromium on Windows.
+fn chromium_session(browser: Browser, db_path: &Path, key: &[u8]) -> Result<AocSession> {
+    let unreadable = |e: rusqlite::Error| Error::CookieStoreUnreadable {
+        browser,
+        path: db_path.to_path_buf(),
+        reason: e.to_string(),
+    };
+    // Windows locks the database while the browser runs, which `immutable` ignores.
+    let uri = format!("file:{}?immutable=1", db_path.display());
+    let flags = rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY | rusqlite::OpenFlags::SQLITE_OPEN_URI;
+    let connection = rusqlite::Connection::open_with_flags(uri, flags).map_err(unreadable)?;
+    let version: u32 = connection
+        .query_row("SELECT value FROM meta WHERE key = 'version'", [], |row| {
+            row.get::<_, String>(0)
+        })
+        .map_err(unreadable)?
+        .parse()
+        .unwrap_or_default();
+    let (encrypted, expires_utc): (Vec<u8>, i64) = connection
+        .query_row(
+            "SELECT encrypted_value, expires_utc FROM cookies \
+             WHERE name = 'session' AND host_key IN (?1, ?2) \
+             ORDER BY expires_utc DESC LIMIT 1",
+            [DOMAIN.to_string(), format!(".{DOMAIN}")],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => Error::NoSessionCookieFound,
+            e => unreadable(e),
+        })?;
+    let value = decrypt(browser, &encrypted, key, version)?;
+    Ok(AocSession::unchecked(value).with_expiry(chromium_time(expires_utc)))
+}
+
+/// Decrypt a cookie value encrypted by a browser based on Chromium on Windows.
+///
+/// Since version 24 of the database, the value is prefixed with the SHA-256 of the domain.
+fn decrypt(browser: Browser, encrypted: &[u8], key: &[u8], db_version: u32) -> Result<String> {
+    let decryption_failed = |reason: &str| Error::DecryptionFailed {
+        browser,
+        reason: reason.to_string(),
+    };
+    let (prefix, rest) = encrypted.split_at(encrypted.len().min(3));
+    match prefix {
+        b"v10" | b"v11" => {}
+        b"v20" => return Err(Error::AppBoundEncryption(browser)),
+        _ => return Err(decryption_failed("unknown encryption scheme")),
+    }
+    if rest.len() < 12 + 16 {
+        return Err(decryption_failed("the encrypted value is truncated"));
+    }
+    let (nonce, ciphertext) = rest.split_at(12);
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| decryption_failed("the key doesn't match"))?;
+    let value = match db_version {
+        24.. if plaintext.len() >= 32 => &plaintext[32..],
+        _ => &plaintext[..],
+    };
+    String::from_utf8(value.to_vec()).map_err(|e| decryption_failed(&e.to_string()))
+}
+
+/// Convert a timestamp of Chromium, in microseconds since 1601-01-01, to a time. Session cookies
+/// have none.
+fn chromium_time(micros: i64) -> Option<SystemTime> {
+    let secs = u64::try_from(micros).ok()? / 1_000_000;
+    let unix = secs.checked_sub(CHROMIUM_EPOCH_OFFSET)?;
+    Some(SystemTime::UNIX_EPOCH + Duration::from_secs(unix))
+}
+
+/// Decode standard base64 with padding.
+fn base64_decode(encoded: &str) -> Option<Vec<u8>> {
+    let sextet = |c: u8| match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a' + 26),
+        b'0'..=b'9' => Some(c - b'0' + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None,
+    };
+    let encoded = encoded.trim_end_matches('=').as_bytes();
+    let mut bytes = Vec::with_capacity(encoded.len() * 3 / 4);
+    let (mut buffer, mut bits) = (0u32, 0);
+    for &c in encoded {
+        buffer = (buffer << 6) | u32::from(sextet(c)?);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn windows_paths_are_mapped() {
+        assert_eq!(
+            wsl_path(r"C:\Users\me"),
+            Some(PathBuf::from("/mnt/c/Users/me"))
+        );
+        assert_eq!(wsl_path("%USERPROFILE%"), None);
+    }
+
+    #[test]
+    fn base64_is_decoded() {
+        assert_eq!(base64_decode("aGVsbG8=").unwrap(), b"hello");
+        assert_eq!(base64_decode("AAEC").unwrap(), [0, 1, 2]);
+        assert_eq!(base64_decode("a*"), None);
+    }
+
+    #[test]
+    fn cookies_are_decrypted() {
+        let key = [7; 32];
+        let nonce = [1; 12];
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+        let hash = [0; 32];
+        let plaintext = [&hash[..], b"25a16c74"].concat();
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce), &plaintext[..])
+            .unwrap();
+        let encrypted = [&b"v10"[..], &nonce, &ciphertext].concat();
+        assert_eq!(
+            decrypt(Browser::Chrome, &encrypted, &key, 24).unwrap(),
+            "25a16c74"
+        );
+        assert!(matches!(
+            decrypt(Browser::Chrome, &encrypted, &[8; 32], 24),
+            Err(Error::DecryptionFailed { .. })
+        ));
+        let app_bound = [&b"v20"[..], &nonce, &ciphertext].concat();
+        assert!(matches!(
+            decrypt(Browser::Chrome, &app_bound, &key, 24),
+            Err(Error::AppBoundEncryption(Browser::Chrome))
+        ));
+    }
+
+    #[test]
+    fn chromium_times_are_converted() {
+        let time = chromium_time(13_350_000_000_000_000).unwrap();
+        let secs = time
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        assert_eq!(secs, 13_350_000_000 - CHROMIUM_EPOCH_OFFSET);
+        assert_eq!(chromium_time(0), None);
+    }
+}